@@ -1,10 +1,11 @@
 use filament::{
     camera::{Camera, Projection},
     entity_manager::EntityManager,
+    image_loader::{self, ImageLoadOptions},
     material::{Material, MaterialParameter},
     renderable::{PrimitiveType, RenderableBuilder},
     sys::Viewport,
-    texture::{InternalFormat, PixelDataFormat, PixelDataType, Texture, TextureSampler},
+    texture::TextureSampler,
     AttributeType, Backend, ClearOptions, Engine, FovDirection, IndexBuffer, IndexType, Renderer,
     Scene, SwapChain, SwapChainFlags, VertexAttribute, VertexBuffer, View,
 };
@@ -15,14 +16,7 @@ use winit::{
 };
 
 const MATERIAL_BYTES: &'static [u8] = include_bytes!("texture_unlit.filamat");
-
-#[repr(C)]
-#[derive(Clone, Default)]
-struct RgbColor {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-}
+const TEXTURE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/gradient.png");
 
 #[repr(C)]
 struct Vertex {
@@ -30,48 +24,17 @@ struct Vertex {
     pub uv: [f32; 2],
 }
 
-#[cfg(target_os = "macos")]
-fn get_active_surface(window: &winit::window::Window) -> *mut std::ffi::filament_c_void {
-    use winit::os::macos::WindowExt;
-    window.get_nsview()
-}
-
-#[cfg(target_os = "windows")]
-fn get_active_surface(window: &winit::window::Window) -> *mut std::ffi::filament_c_void {
-    use winit::os::windows::WindowExt;
-    window.get_hwnd()
-}
-
-#[cfg(target_os = "linux")]
-fn get_active_surface(window: &winit::window::Window) -> *mut std::ffi::c_void {
-    use winit::platform::unix::WindowExtUnix;
-    window.xlib_window().unwrap() as *mut std::ffi::c_void
-}
-
-fn init_window() -> (EventLoop<()>, Window, *mut std::ffi::c_void) {
+fn init_window() -> (EventLoop<()>, Window) {
     let _ = env_logger::builder().is_test(true).try_init();
 
     let event_loop = EventLoop::<()>::new();
     let window = WindowBuilder::new().build(&event_loop).unwrap();
     window.set_title("Basic example window");
 
-    let surface = get_active_surface(&window);
-
-    (event_loop, window, surface)
+    (event_loop, window)
 }
 
-fn triangle_data() -> (Vec<Vertex>, Vec<u16>, Vec<RgbColor>) {
-    let mut texture_data = vec![RgbColor::default(); 256 * 256];
-    for y in 0..256 {
-        for x in 0..256 {
-            texture_data[y * 256 + x] = RgbColor {
-                r: x as u8,
-                g: y as u8,
-                b: 0,
-            };
-        }
-    }
-
+fn triangle_data() -> (Vec<Vertex>, Vec<u16>) {
     (
         vec![
             Vertex {
@@ -88,17 +51,24 @@ fn triangle_data() -> (Vec<Vertex>, Vec<u16>, Vec<RgbColor>) {
             },
         ],
         vec![0, 1, 2],
-        texture_data,
     )
 }
 
 fn main() {
     env_logger::init();
 
-    let (event_loop, window, surface) = init_window();
+    let (event_loop, window) = init_window();
 
     let mut engine = Engine::new(Backend::DEFAULT).unwrap();
-    let mut swapchain = SwapChain::new(&mut engine, surface, SwapChainFlags::empty()).unwrap();
+    let size = window.inner_size();
+    let mut swapchain = SwapChain::from_window_with_size(
+        &mut engine,
+        &window,
+        size.width,
+        size.height,
+        SwapChainFlags::empty(),
+    )
+    .unwrap();
     let mut renderer = Renderer::new(&mut engine).unwrap();
     let mut view = View::new(&mut engine).unwrap();
     let mut scene = Scene::new(&mut engine).unwrap();
@@ -124,7 +94,7 @@ fn main() {
     view.set_camera(&camera);
     renderer.set_clear_options(ClearOptions::default());
 
-    let (vertices, indices, texture_data) = triangle_data();
+    let (vertices, indices) = triangle_data();
 
     let mut vertex_buffer = VertexBuffer::builder()
         .vertex_count(3)
@@ -143,14 +113,8 @@ fn main() {
     index_buffer.write(0, indices);
 
     let sampler = TextureSampler::default();
-    let mut texture = Texture::builder()
-        .unwrap()
-        .width(256)
-        .height(256)
-        .format(InternalFormat::RGB8)
-        .build(&mut engine)
-        .unwrap();
-    texture.set(0, texture_data, PixelDataFormat::RGB, PixelDataType::UBYTE);
+    let texture =
+        image_loader::load_texture(&mut engine, TEXTURE_PATH, ImageLoadOptions::default()).unwrap();
 
     let material = Material::new(&mut engine, MATERIAL_BYTES).unwrap();
     let mut instance = material.default_instance();
@@ -203,9 +167,11 @@ fn main() {
                 }
             }
             Event::RedrawRequested(_window_id) => {
-                if renderer.begin_frame(&mut swapchain, 0) {
-                    renderer.render(&view);
-                    renderer.end_frame();
+                if !renderer
+                    .render_frame(&mut swapchain, &[&view])
+                    .is_rendered()
+                {
+                    log::debug!("Frame skipped");
                 }
                 std::thread::sleep(std::time::Duration::from_millis(16));
             }
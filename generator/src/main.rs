@@ -45,10 +45,23 @@ fn main() {
         .header("sys/src/cpp/helpers.h")
         .header("sys/src/cpp/renderable_manager.h")
         .header("sys/src/cpp/materials.h")
+        .header("sys/src/cpp/textures.h")
+        .header("sys/src/cpp/streams.h")
+        .header("sys/src/cpp/entities.h")
+        .header("sys/src/cpp/lights.h")
         .disable_untagged_union()
         .blacklist_type("std::.*")
-        .blacklist_type("filament::math::mat4f")
-        .opaque_type("filament::math::.*")
+        // The math types are defined in `filament_sys::math` with their real layout
+        .blacklist_type("filament::math::.*")
+        .raw_line("pub type filament_math_float2 = crate::math::Float2;")
+        .raw_line("pub type filament_math_float3 = crate::math::Float3;")
+        .raw_line("pub type filament_math_float4 = crate::math::Float4;")
+        .raw_line("pub type filament_math_double3 = crate::math::Double3;")
+        .raw_line("pub type filament_math_double4 = crate::math::Double4;")
+        .raw_line("pub type filament_math_mat3f = crate::math::Mat3f;")
+        .raw_line("pub type filament_math_mat4f = crate::math::Mat4f;")
+        .raw_line("pub type filament_math_mat4 = crate::math::Mat4;")
+        .raw_line("pub type filament_math_quatf = crate::math::Quatf;")
         .blacklist_type("filament::utils::.*")
         .opaque_type("filament::MaterialInstance")
         .opaque_type("filament::Material")
@@ -75,6 +88,7 @@ fn main() {
         .whitelist_type("filament::backend::PixelBufferDescriptor")
         .rustified_enum("filament::Camera::Projection")
         .rustified_enum("filament::Backend")
+        .bitfield_enum("filament::backend::TextureUsage")
         .rustified_enum("filament::backend::.*")
         .rustified_enum("filament::VertexBuffer::AttributeType")
         .rustified_enum("filament::VertexBuffer::QuatType")
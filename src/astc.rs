@@ -0,0 +1,813 @@
+//! A CPU decoder for ASTC blocks, following the LDR profile of the Khronos Data Format
+//! specification.
+//!
+//! Blocks using HDR endpoint modes decode to the error color, magenta, as LDR decoders do. HDR
+//! void-extent blocks are clamped to the 0..1 range instead.
+use std::convert::TryInto;
+
+const ERROR_COLOR: [u8; 4] = [255, 0, 255, 255];
+
+/// Decodes a 16 byte block of `width` by `height` texels into `texels`, in row-major order. With
+/// `srgb`, the color endpoints are expanded as the sRGB formats require.
+pub(crate) fn decode_block(
+    block: &[u8],
+    width: usize,
+    height: usize,
+    srgb: bool,
+    texels: &mut [[u8; 4]],
+) {
+    let bits = u128::from_le_bytes(block[..16].try_into().unwrap());
+    let texels = &mut texels[..width * height];
+    if decode_bits(bits, width, height, srgb, texels).is_none() {
+        for texel in texels.iter_mut() {
+            *texel = ERROR_COLOR;
+        }
+    }
+}
+
+fn read(bits: u128, offset: usize, count: usize) -> u32 {
+    if offset >= 128 {
+        0
+    } else {
+        (bits >> offset) as u32 & ((1u32 << count) - 1)
+    }
+}
+
+fn decode_bits(
+    bits: u128,
+    width: usize,
+    height: usize,
+    srgb: bool,
+    texels: &mut [[u8; 4]],
+) -> Option<()> {
+    if bits & 0x1ff == 0x1fc {
+        let color = void_extent_color(bits)?;
+        for texel in texels.iter_mut() {
+            *texel = color;
+        }
+        return Some(());
+    }
+
+    let mode = BlockMode::decode(read(bits, 0, 11))?;
+    let partitions = read(bits, 11, 2) as usize + 1;
+    let planes = if mode.dual_plane { 2 } else { 1 };
+    let weight_count = mode.grid_width * mode.grid_height * planes;
+    let weight_bits = ise_bit_count(weight_count, mode.weight_range);
+    if mode.grid_width > width
+        || mode.grid_height > height
+        || (mode.dual_plane && partitions == 4)
+        || weight_count > 64
+        || !(24..=96).contains(&weight_bits)
+    {
+        return None;
+    }
+
+    // Configuration bits which do not fit at the start of the block are stored below the weights
+    let mut below_weights = 128 - weight_bits;
+    let mut endpoint_modes = [0u32; 4];
+    let color_start = if partitions == 1 {
+        endpoint_modes[0] = read(bits, 13, 4);
+        17
+    } else {
+        let extra_bits = 3 * partitions - 4;
+        let encoded = read(bits, 23, 6) | (read(bits, below_weights - extra_bits, extra_bits) << 6);
+        let class = encoded & 3;
+        if class == 0 {
+            for endpoint_mode in endpoint_modes[..partitions].iter_mut() {
+                *endpoint_mode = (encoded >> 2) & 15;
+            }
+        } else {
+            below_weights -= extra_bits;
+            for (i, endpoint_mode) in endpoint_modes[..partitions].iter_mut().enumerate() {
+                let high = (encoded >> (2 + i)) & 1;
+                let low = (encoded >> (2 + partitions + 2 * i)) & 3;
+                *endpoint_mode = ((class - 1 + high) << 2) | low;
+            }
+        }
+        29
+    };
+    let endpoint_modes = &endpoint_modes[..partitions];
+    if endpoint_modes.iter().any(|mode| is_hdr(*mode)) {
+        return None;
+    }
+
+    let color_component = if mode.dual_plane {
+        below_weights -= 2;
+        Some(read(bits, below_weights, 2) as usize)
+    } else {
+        None
+    };
+
+    // Colors use the finest range their bits allow, and at least 6 levels
+    let value_count = endpoint_modes
+        .iter()
+        .map(|mode| ((mode >> 2) as usize + 1) * 2)
+        .sum::<usize>();
+    if value_count > 18 {
+        return None;
+    }
+    let color_bits = below_weights.saturating_sub(color_start);
+    let color_range = (4..RANGES.len())
+        .rev()
+        .find(|range| ise_bit_count(value_count, *range) <= color_bits)?;
+    let values = decode_ise(bits, color_start, value_count, color_range)
+        .iter()
+        .map(|(high, low)| unquantize_color(*high, *low, color_range))
+        .collect::<Vec<_>>();
+    let mut endpoints = [([0u8; 4], [0u8; 4]); 4];
+    let mut values = values.as_slice();
+    for (endpoint, mode) in endpoints.iter_mut().zip(endpoint_modes.iter()) {
+        let count = ((mode >> 2) as usize + 1) * 2;
+        *endpoint = decode_endpoints(*mode, &values[..count]);
+        values = &values[count..];
+    }
+
+    // Weights are stored from the top of the block, in reverse bit order
+    let weights = decode_ise(bits.reverse_bits(), 0, weight_count, mode.weight_range)
+        .iter()
+        .map(|(high, low)| unquantize_weight(*high, *low, mode.weight_range))
+        .collect::<Vec<_>>();
+    let plane_weights = |plane: usize| {
+        weights
+            .iter()
+            .skip(plane)
+            .step_by(planes)
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    let plane_weights = [plane_weights(0), plane_weights(planes - 1)];
+
+    let seed = read(bits, 13, 10);
+    let small_block = width * height < 31;
+    for y in 0..height {
+        for x in 0..width {
+            let partition = if partitions == 1 {
+                0
+            } else {
+                select_partition(seed, x as u32, y as u32, partitions as u32, small_block)
+            };
+            let (low, high) = endpoints[partition];
+            let weight = |plane: usize| {
+                infill_weight(
+                    &plane_weights[plane],
+                    mode.grid_width,
+                    mode.grid_height,
+                    width,
+                    height,
+                    x,
+                    y,
+                )
+            };
+            let weights = [weight(0), weight(1)];
+
+            let texel = &mut texels[y * width + x];
+            for component in 0..4 {
+                let plane = (color_component == Some(component)) as usize;
+                let srgb = srgb && component < 3;
+                texel[component] =
+                    interpolate(low[component], high[component], weights[plane], srgb);
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn void_extent_color(bits: u128) -> Option<[u8; 4]> {
+    if read(bits, 10, 2) != 3 {
+        return None;
+    }
+    let hdr = read(bits, 9, 1) == 1;
+    let mut color = [0u8; 4];
+    for (i, component) in color.iter_mut().enumerate() {
+        let value = read(bits, 64 + 16 * i, 16);
+        *component = if hdr {
+            (half_to_f32(value as u16).max(0.0).min(1.0) * 255.0).round() as u8
+        } else {
+            (value >> 8) as u8
+        };
+    }
+    Some(color)
+}
+
+fn half_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 31) as i32;
+    let mantissa = (half & 1023) as f32;
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        31 if mantissa == 0.0 => f32::INFINITY,
+        31 => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct BlockMode {
+    grid_width: usize,
+    grid_height: usize,
+    dual_plane: bool,
+    // An index into `RANGES`
+    weight_range: usize,
+}
+impl BlockMode {
+    fn decode(mode: u32) -> Option<Self> {
+        let bit = |i: u32| (mode >> i) & 1;
+        let a = ((mode >> 5) & 3) as usize;
+        let mut high_precision = bit(9) == 1;
+        let mut dual_plane = bit(10) == 1;
+
+        let (grid_width, grid_height, range);
+        if mode & 3 != 0 {
+            range = bit(4) | ((mode & 3) << 1);
+            let b = ((mode >> 7) & 3) as usize;
+            let (w, h) = match (mode >> 2) & 3 {
+                0 => (b + 4, a + 2),
+                1 => (b + 8, a + 2),
+                2 => (a + 2, b + 8),
+                _ if bit(8) == 1 => ((b & 1) + 2, a + 2),
+                _ => (a + 2, (b & 1) + 6),
+            };
+            grid_width = w;
+            grid_height = h;
+        } else {
+            range = bit(4) | (((mode >> 2) & 3) << 1);
+            if (mode >> 2) & 3 == 0 {
+                return None;
+            }
+            let b = ((mode >> 9) & 3) as usize;
+            let (w, h) = match (mode >> 7) & 3 {
+                0 => (12, a + 2),
+                1 => (a + 2, 12),
+                2 => {
+                    high_precision = false;
+                    dual_plane = false;
+                    (a + 6, b + 6)
+                }
+                _ => match a {
+                    0 => (6, 10),
+                    1 => (10, 6),
+                    _ => return None,
+                },
+            };
+            grid_width = w;
+            grid_height = h;
+        }
+
+        Some(Self {
+            grid_width,
+            grid_height,
+            dual_plane,
+            weight_range: range as usize - 2 + if high_precision { 6 } else { 0 },
+        })
+    }
+}
+
+/// Whether endpoint `mode` encodes HDR colors.
+fn is_hdr(mode: u32) -> bool {
+    matches!(mode, 2 | 3 | 7 | 11 | 14 | 15)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Packing {
+    Bits,
+    Trits,
+    Quints,
+}
+
+/// The integer sequence encodings of each range, from 2 to 256 levels: how values are packed and
+/// how many bits each value stores besides its trit or quint.
+const RANGES: [(Packing, usize); 21] = [
+    (Packing::Bits, 1),
+    (Packing::Trits, 0),
+    (Packing::Bits, 2),
+    (Packing::Quints, 0),
+    (Packing::Trits, 1),
+    (Packing::Bits, 3),
+    (Packing::Quints, 1),
+    (Packing::Trits, 2),
+    (Packing::Bits, 4),
+    (Packing::Quints, 2),
+    (Packing::Trits, 3),
+    (Packing::Bits, 5),
+    (Packing::Quints, 3),
+    (Packing::Trits, 4),
+    (Packing::Bits, 6),
+    (Packing::Quints, 4),
+    (Packing::Trits, 5),
+    (Packing::Bits, 7),
+    (Packing::Quints, 5),
+    (Packing::Trits, 6),
+    (Packing::Bits, 8),
+];
+
+fn ise_bit_count(count: usize, range: usize) -> usize {
+    let (packing, bits) = RANGES[range];
+    count * bits
+        + match packing {
+            Packing::Bits => 0,
+            Packing::Trits => (8 * count + 4) / 5,
+            Packing::Quints => (7 * count + 2) / 3,
+        }
+}
+
+/// Decodes `count` values of an integer sequence starting at bit `start`, as their trit or quint
+/// and their low bits. Bits past the end of the sequence are read as zero.
+fn decode_ise(bits: u128, start: usize, count: usize, range: usize) -> Vec<(u32, u32)> {
+    let length = ise_bit_count(count, range);
+    let mut bits = if start >= 128 { 0 } else { bits >> start };
+    if length < 128 {
+        bits &= (1u128 << length) - 1;
+    }
+
+    let (packing, low_bits) = RANGES[range];
+    // Where the bits of the packed trits or quints sit, after the low bits of each value
+    let (packed_bits, group): (&[usize], usize) = match packing {
+        Packing::Bits => (&[0], 1),
+        Packing::Trits => (&[2, 2, 1, 2, 1], 5),
+        Packing::Quints => (&[3, 2, 2], 3),
+    };
+
+    let mut values = Vec::with_capacity(count + group);
+    let mut offset = 0;
+    while values.len() < count {
+        let mut lows = [0u32; 5];
+        let mut packed = 0u32;
+        let mut packed_offset = 0;
+        for (low, packed_len) in lows.iter_mut().zip(packed_bits.iter()) {
+            *low = read(bits, offset, low_bits);
+            offset += low_bits;
+            packed |= read(bits, offset, *packed_len) << packed_offset;
+            offset += packed_len;
+            packed_offset += packed_len;
+        }
+        match packing {
+            Packing::Bits => values.push((0, lows[0])),
+            Packing::Trits => values.extend(
+                decode_trits(packed)
+                    .iter()
+                    .copied()
+                    .zip(lows.iter().copied()),
+            ),
+            Packing::Quints => values.extend(
+                decode_quints(packed)
+                    .iter()
+                    .copied()
+                    .zip(lows.iter().copied()),
+            ),
+        }
+    }
+    values.truncate(count);
+    values
+}
+
+fn decode_trits(t: u32) -> [u32; 5] {
+    let bit = |value: u32, i: u32| (value >> i) & 1;
+    let (c, t3, t4) = if (t >> 2) & 7 == 7 {
+        ((((t >> 5) & 7) << 2) | (t & 3), 2, 2)
+    } else if (t >> 5) & 3 == 3 {
+        (t & 31, bit(t, 7), 2)
+    } else {
+        (t & 31, (t >> 5) & 3, bit(t, 7))
+    };
+    let (t0, t1, t2) = if c & 3 == 3 {
+        (
+            (bit(c, 3) << 1) | (bit(c, 2) & !bit(c, 3) & 1),
+            bit(c, 4),
+            2,
+        )
+    } else if (c >> 2) & 3 == 3 {
+        (c & 3, 2, 2)
+    } else {
+        (
+            (bit(c, 1) << 1) | (bit(c, 0) & !bit(c, 1) & 1),
+            (c >> 2) & 3,
+            bit(c, 4),
+        )
+    };
+    [t0, t1, t2, t3, t4]
+}
+
+fn decode_quints(q: u32) -> [u32; 3] {
+    let bit = |value: u32, i: u32| (value >> i) & 1;
+    if (q >> 1) & 3 == 3 && (q >> 5) & 3 == 0 {
+        let q2 =
+            (bit(q, 0) << 2) | ((bit(q, 4) & !bit(q, 0) & 1) << 1) | (bit(q, 3) & !bit(q, 0) & 1);
+        return [4, 4, q2];
+    }
+    let (c, q2) = if (q >> 1) & 3 == 3 {
+        (
+            (((q >> 3) & 3) << 3) | ((!(q >> 5) & 3) << 1) | bit(q, 0),
+            4,
+        )
+    } else {
+        (q & 31, (q >> 5) & 3)
+    };
+    if c & 7 == 5 {
+        [(c >> 3) & 3, 4, q2]
+    } else {
+        [c & 7, (c >> 3) & 3, q2]
+    }
+}
+
+fn replicate(value: u32, from: usize, to: usize) -> u32 {
+    if from == 0 {
+        return 0;
+    }
+    let (mut result, mut filled) = (0, 0);
+    while filled < to {
+        result = (result << from) | value;
+        filled += from;
+    }
+    result >> (filled - to)
+}
+
+/// Maps an encoded color value to 0..=255.
+fn unquantize_color(high: u32, low: u32, range: usize) -> i32 {
+    let (packing, bits) = RANGES[range];
+    let bit = |i: u32| (low >> i) & 1;
+    let (b, c) = match (packing, bits) {
+        (Packing::Bits, _) => return replicate(low, bits, 8) as i32,
+        (Packing::Trits, 1) => (0, 204),
+        (Packing::Trits, 2) => (bit(1) * 0x116, 93),
+        (Packing::Trits, 3) => (bit(2) * 0x10a + bit(1) * 0x085, 44),
+        (Packing::Trits, 4) => (bit(3) * 0x104 + bit(2) * 0x082 + bit(1) * 0x041, 22),
+        (Packing::Trits, 5) => (
+            bit(4) * 0x102 + bit(3) * 0x081 + bit(2) * 0x040 + bit(1) * 0x020,
+            11,
+        ),
+        (Packing::Trits, _) => (
+            bit(5) * 0x101 + bit(4) * 0x080 + bit(3) * 0x040 + bit(2) * 0x020 + bit(1) * 0x010,
+            5,
+        ),
+        (Packing::Quints, 1) => (0, 113),
+        (Packing::Quints, 2) => (bit(1) * 0x10c, 54),
+        (Packing::Quints, 3) => (bit(2) * 0x105 + bit(1) * 0x082, 26),
+        (Packing::Quints, 4) => (bit(3) * 0x102 + bit(2) * 0x081 + bit(1) * 0x040, 13),
+        (Packing::Quints, _) => (
+            bit(4) * 0x101 + bit(3) * 0x080 + bit(2) * 0x040 + bit(1) * 0x020,
+            6,
+        ),
+    };
+    let a = if low & 1 == 1 { 0x1ff } else { 0 };
+    let t = (high * c + b) ^ a;
+    ((a & 0x80) | (t >> 2)) as i32
+}
+
+/// Maps an encoded weight to 0..=64.
+fn unquantize_weight(high: u32, low: u32, range: usize) -> u32 {
+    let (packing, bits) = RANGES[range];
+    let bit = |i: u32| (low >> i) & 1;
+    let value = match (packing, bits) {
+        (Packing::Bits, _) => replicate(low, bits, 6),
+        (Packing::Trits, 0) => [0, 32, 63][high as usize],
+        (Packing::Quints, 0) => [0, 16, 32, 47, 63][high as usize],
+        _ => {
+            let (b, c) = match (packing, bits) {
+                (Packing::Trits, 1) => (0, 50),
+                (Packing::Trits, 2) => (bit(1) * 0x45, 23),
+                (Packing::Trits, _) => (bit(2) * 0x42 + bit(1) * 0x21, 11),
+                (_, 1) => (0, 28),
+                _ => (bit(1) * 0x42, 13),
+            };
+            let a = if low & 1 == 1 { 0x7f } else { 0 };
+            let t = (high * c + b) ^ a;
+            (a & 0x20) | (t >> 2)
+        }
+    };
+    if value > 32 {
+        value + 1
+    } else {
+        value
+    }
+}
+
+fn clamp(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+fn color(r: i32, g: i32, b: i32, a: i32) -> [u8; 4] {
+    [clamp(r), clamp(g), clamp(b), clamp(a)]
+}
+
+/// Moves the blue channel towards red and green, used by endpoints stored in swapped order.
+fn blue_contract(r: i32, g: i32, b: i32, a: i32) -> [u8; 4] {
+    color((r + b) >> 1, (g + b) >> 1, b, a)
+}
+
+/// Splits `(offset, base)` where the base lent its lowest bit to the top of the offset, returning
+/// the signed offset and the base.
+fn bit_transfer_signed(offset: i32, base: i32) -> (i32, i32) {
+    let base = (base >> 1) | (offset & 0x80);
+    let mut offset = (offset >> 1) & 0x3f;
+    if offset & 0x20 != 0 {
+        offset -= 0x40;
+    }
+    (offset, base)
+}
+
+fn decode_endpoints(mode: u32, v: &[i32]) -> ([u8; 4], [u8; 4]) {
+    match mode {
+        0 => (color(v[0], v[0], v[0], 255), color(v[1], v[1], v[1], 255)),
+        1 => {
+            let l0 = (v[0] >> 2) | (v[1] & 0xc0);
+            let l1 = (l0 + (v[1] & 0x3f)).min(255);
+            (color(l0, l0, l0, 255), color(l1, l1, l1, 255))
+        }
+        4 => (color(v[0], v[0], v[0], v[2]), color(v[1], v[1], v[1], v[3])),
+        5 => {
+            let (l1, l0) = bit_transfer_signed(v[1], v[0]);
+            let (a1, a0) = bit_transfer_signed(v[3], v[2]);
+            let l1 = l0 + l1;
+            (color(l0, l0, l0, a0), color(l1, l1, l1, a0 + a1))
+        }
+        6 | 10 => {
+            let (a0, a1) = if mode == 6 { (255, 255) } else { (v[4], v[5]) };
+            (
+                color(
+                    (v[0] * v[3]) >> 8,
+                    (v[1] * v[3]) >> 8,
+                    (v[2] * v[3]) >> 8,
+                    a0,
+                ),
+                color(v[0], v[1], v[2], a1),
+            )
+        }
+        8 | 12 => {
+            let (a0, a1) = if mode == 8 { (255, 255) } else { (v[6], v[7]) };
+            if v[1] + v[3] + v[5] >= v[0] + v[2] + v[4] {
+                (color(v[0], v[2], v[4], a0), color(v[1], v[3], v[5], a1))
+            } else {
+                (
+                    blue_contract(v[1], v[3], v[5], a1),
+                    blue_contract(v[0], v[2], v[4], a0),
+                )
+            }
+        }
+        _ => {
+            // 9 and 13, the HDR modes were rejected before
+            let (r1, r0) = bit_transfer_signed(v[1], v[0]);
+            let (g1, g0) = bit_transfer_signed(v[3], v[2]);
+            let (b1, b0) = bit_transfer_signed(v[5], v[4]);
+            let (a1, a0) = if mode == 9 {
+                (0, 255)
+            } else {
+                bit_transfer_signed(v[7], v[6])
+            };
+            if r1 + g1 + b1 >= 0 {
+                (
+                    color(r0, g0, b0, a0),
+                    color(r0 + r1, g0 + g1, b0 + b1, a0 + a1),
+                )
+            } else {
+                (
+                    blue_contract(r0 + r1, g0 + g1, b0 + b1, a0 + a1),
+                    blue_contract(r0, g0, b0, a0),
+                )
+            }
+        }
+    }
+}
+
+fn hash52(mut p: u32) -> u32 {
+    p ^= p >> 15;
+    p = p.wrapping_sub(p << 17);
+    p = p.wrapping_add(p << 7);
+    p = p.wrapping_add(p << 4);
+    p ^= p >> 5;
+    p = p.wrapping_add(p << 16);
+    p ^= p >> 7;
+    p ^= p >> 3;
+    p ^= p << 6;
+    p ^= p >> 17;
+    p
+}
+
+/// The partition of texel (`x`, `y`) of a block with partition index `seed`.
+fn select_partition(
+    seed: u32,
+    mut x: u32,
+    mut y: u32,
+    partitions: u32,
+    small_block: bool,
+) -> usize {
+    if small_block {
+        x <<= 1;
+        y <<= 1;
+    }
+    let seed = seed + (partitions - 1) * 1024;
+    let rnum = hash52(seed);
+
+    let mut seeds = [0u32; 8];
+    for (i, s) in seeds.iter_mut().enumerate() {
+        let value = (rnum >> (4 * i)) & 15;
+        *s = value * value;
+    }
+    let (sh1, sh2) = if seed & 1 != 0 {
+        (
+            if seed & 2 != 0 { 4 } else { 5 },
+            if partitions == 3 { 6 } else { 5 },
+        )
+    } else {
+        (
+            if partitions == 3 { 6 } else { 5 },
+            if seed & 2 != 0 { 4 } else { 5 },
+        )
+    };
+    for (i, s) in seeds.iter_mut().enumerate() {
+        *s >>= if i % 2 == 0 { sh1 } else { sh2 };
+    }
+
+    // The z coordinate is always 0 for 2D blocks, so its seeds do not matter
+    let a = (seeds[0] * x + seeds[1] * y + (rnum >> 14)) & 0x3f;
+    let b = (seeds[2] * x + seeds[3] * y + (rnum >> 10)) & 0x3f;
+    let c = if partitions < 3 {
+        0
+    } else {
+        (seeds[4] * x + seeds[5] * y + (rnum >> 6)) & 0x3f
+    };
+    let d = if partitions < 4 {
+        0
+    } else {
+        (seeds[6] * x + seeds[7] * y + (rnum >> 2)) & 0x3f
+    };
+
+    if a >= b && a >= c && a >= d {
+        0
+    } else if b >= c && b >= d {
+        1
+    } else if c >= d {
+        2
+    } else {
+        3
+    }
+}
+
+/// Bilinearly samples the weight grid at texel (`x`, `y`) of the block.
+fn infill_weight(
+    weights: &[u32],
+    grid_width: usize,
+    grid_height: usize,
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> u32 {
+    let ds = (1024 + width / 2) / (width - 1);
+    let dt = (1024 + height / 2) / (height - 1);
+    let gs = (ds * x * (grid_width - 1) + 32) >> 6;
+    let gt = (dt * y * (grid_height - 1) + 32) >> 6;
+    let (js, fs) = (gs >> 4, (gs & 15) as u32);
+    let (jt, ft) = (gt >> 4, (gt & 15) as u32);
+
+    let w11 = (fs * ft + 8) >> 4;
+    let w10 = ft - w11;
+    let w01 = fs - w11;
+    let w00 = 16 + w11 - fs - ft;
+    let at = |s: usize, t: usize| {
+        if s < grid_width && t < grid_height {
+            weights[t * grid_width + s]
+        } else {
+            0
+        }
+    };
+    (at(js, jt) * w00 + at(js + 1, jt) * w01 + at(js, jt + 1) * w10 + at(js + 1, jt + 1) * w11 + 8)
+        >> 4
+}
+
+fn interpolate(low: u8, high: u8, weight: u32, srgb: bool) -> u8 {
+    let expand = |value: u8| {
+        if srgb {
+            ((value as u32) << 8) | 0x80
+        } else {
+            value as u32 * 257
+        }
+    };
+    ((expand(low) * (64 - weight) + expand(high) * weight + 32) >> 6 >> 8) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_block, decode_quints, decode_trits, select_partition, unquantize_color,
+        unquantize_weight, BlockMode, Packing, ERROR_COLOR, RANGES,
+    };
+
+    fn decode(bits: u128) -> Vec<[u8; 4]> {
+        let mut texels = vec![[0u8; 4]; 16];
+        decode_block(&bits.to_le_bytes(), 4, 4, false, &mut texels);
+        texels
+    }
+
+    #[test]
+    fn unquantization() {
+        // Every range spreads distinct levels over the whole output range
+        let levels = |range: usize| {
+            let (packing, bits) = RANGES[range];
+            let packed = match packing {
+                Packing::Bits => 1,
+                Packing::Trits => 3,
+                Packing::Quints => 5,
+            };
+            (0..packed).flat_map(move |high| (0..1 << bits).map(move |low| (high, low)))
+        };
+        for range in 4..RANGES.len() {
+            let mut values = levels(range)
+                .map(|(high, low)| unquantize_color(high, low, range))
+                .collect::<Vec<_>>();
+            let count = values.len();
+            values.sort_unstable();
+            values.dedup();
+            assert_eq!(values.len(), count, "range {}", range);
+            assert_eq!((values[0], values[count - 1]), (0, 255), "range {}", range);
+        }
+        for range in 0..12 {
+            let mut values = levels(range)
+                .map(|(high, low)| unquantize_weight(high, low, range))
+                .collect::<Vec<_>>();
+            let count = values.len();
+            values.sort_unstable();
+            values.dedup();
+            assert_eq!(values.len(), count, "range {}", range);
+            assert_eq!((values[0], values[count - 1]), (0, 64), "range {}", range);
+        }
+
+        let quant6 = [(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)];
+        let colors = quant6
+            .iter()
+            .map(|(high, low)| unquantize_color(*high, *low, 4))
+            .collect::<Vec<_>>();
+        assert_eq!(colors, vec![0, 51, 102, 153, 204, 255]);
+        let weights = quant6
+            .iter()
+            .map(|(high, low)| unquantize_weight(*high, *low, 4))
+            .collect::<Vec<_>>();
+        assert_eq!(weights, vec![0, 12, 25, 39, 52, 64]);
+        assert_eq!(unquantize_weight(0, 1, 0), 64);
+    }
+
+    #[test]
+    fn trits_and_quints() {
+        assert_eq!(decode_trits(0), [0; 5]);
+        assert_eq!(decode_trits(1), [1, 0, 0, 0, 0]);
+        assert_eq!(decode_trits(0b0111_1110), [2, 2, 2, 2, 2]);
+        assert_eq!(decode_quints(0), [0; 3]);
+        assert_eq!(decode_quints(0b000_0110), [4, 4, 0]);
+        assert_eq!(decode_quints(0b000_0101), [0, 4, 0]);
+
+        // Every packed value maps to a distinct set of trits and quints
+        let mut trits = (0..256).map(decode_trits).collect::<Vec<_>>();
+        trits.sort_unstable();
+        trits.dedup();
+        assert_eq!(trits.len(), 243);
+        let mut quints = (0..128).map(decode_quints).collect::<Vec<_>>();
+        quints.sort_unstable();
+        quints.dedup();
+        assert_eq!(quints.len(), 125);
+    }
+
+    #[test]
+    fn block_modes() {
+        let mode = BlockMode::decode(0x42).unwrap();
+        assert_eq!((mode.grid_width, mode.grid_height), (4, 4));
+        assert_eq!((mode.dual_plane, mode.weight_range), (false, 2));
+        assert!(BlockMode::decode(0).is_none());
+
+        // Partitions only depend on the seed and texel, and the first one is always used
+        let partitions = (0..16)
+            .map(|i| select_partition(5, i % 4, i / 4, 2, true))
+            .collect::<Vec<_>>();
+        assert!(partitions.iter().all(|partition| *partition < 2));
+        assert!(partitions.contains(&0));
+    }
+
+    #[test]
+    fn astc_blocks() {
+        // A void-extent block, orange
+        let bits = 0x1fc | (0x3f_ffff_ffff_ffff << 10) | (0xffff_0000_8000_ffffu128 << 64);
+        assert!(decode(bits)
+            .iter()
+            .all(|texel| *texel == [255, 128, 0, 255]));
+
+        // One partition of direct RGB endpoints from black to white, 4x4 weights with 4 levels
+        let mut bits: u128 = 0x42 | (8 << 13);
+        for (i, value) in [0u128, 255, 0, 255, 0, 255].iter().enumerate() {
+            bits |= value << (17 + 8 * i);
+        }
+        // Weights are reversed from the top, the first is 3 and the sixth is 2
+        bits |= 0b11 << 126 | 1 << 116;
+        let texels = decode(bits);
+        assert_eq!(texels[0], [255, 255, 255, 255]);
+        assert_eq!(texels[5], [171, 171, 171, 255]);
+        assert_eq!(texels[15], [0, 0, 0, 255]);
+
+        // The same with 3 level weights, the first being the middle trit
+        let bits = (bits & !(0x7ff | 0b11 << 126 | 1 << 116)) | 0x51 | 1 << 127;
+        let texels = decode(bits);
+        assert_eq!(texels[0], [128, 128, 128, 255]);
+        assert_eq!(texels[1], [0, 0, 0, 255]);
+
+        // A reserved block mode
+        assert!(decode(0).iter().all(|texel| *texel == ERROR_COLOR));
+    }
+}
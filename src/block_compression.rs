@@ -0,0 +1,543 @@
+//! CPU decoders for block compressed texture data, used when the backend cannot sample a
+//! compressed format directly.
+use std::convert::TryInto;
+
+use crate::{
+    astc,
+    texture::{
+        compressed_block_size, CompressedType, InternalFormat, PixelDataFormat, PixelDataType,
+        Texture,
+    },
+};
+
+/// An image decoded from block compressed data, tightly packed with one byte per channel. Signed
+/// formats store each channel as an `i8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedImage {
+    pub data: Vec<u8>,
+    pub internal_format: InternalFormat,
+    pub format: PixelDataFormat,
+    pub ty: PixelDataType,
+}
+
+/// Whether `decode` can decompress data of type `ty`.
+pub fn can_decode(ty: CompressedType) -> bool {
+    decoded_format(ty).is_some()
+}
+
+/// The uncompressed formats `decode` produces for `ty`.
+pub fn decoded_format(ty: CompressedType) -> Option<(InternalFormat, PixelDataFormat)> {
+    use CompressedType::*;
+    match ty {
+        EAC_R11 => Some((InternalFormat::R8, PixelDataFormat::R)),
+        EAC_R11_SIGNED => Some((InternalFormat::R8_SNORM, PixelDataFormat::R)),
+        EAC_RG11 => Some((InternalFormat::RG8, PixelDataFormat::RG)),
+        EAC_RG11_SIGNED => Some((InternalFormat::RG8_SNORM, PixelDataFormat::RG)),
+        ETC2_RGB8 | ETC2_RGB8_A1 | ETC2_EAC_RGBA8 | DXT1_RGB | DXT1_RGBA | DXT3_RGBA
+        | DXT5_RGBA => Some((InternalFormat::RGBA8, PixelDataFormat::RGBA)),
+        ETC2_SRGB8 | ETC2_SRGB8_A1 | ETC2_EAC_SRGBA8 | DXT1_SRGB | DXT1_SRGBA | DXT3_SRGBA
+        | DXT5_SRGBA => Some((InternalFormat::SRGB8_A8, PixelDataFormat::RGBA)),
+        _ if is_astc_srgb(ty) => Some((InternalFormat::SRGB8_A8, PixelDataFormat::RGBA)),
+        _ => Some((InternalFormat::RGBA8, PixelDataFormat::RGBA)),
+    }
+}
+
+fn is_astc(ty: CompressedType) -> bool {
+    ty as u16 >= CompressedType::RGBA_ASTC_4x4 as u16
+}
+
+fn is_astc_srgb(ty: CompressedType) -> bool {
+    ty as u16 >= CompressedType::SRGB8_ALPHA8_ASTC_4x4 as u16
+}
+
+/// Decompresses a `width` by `height` image. Returns `None` when `ty` has no CPU decoder or
+/// `data` is too short.
+pub fn decode(
+    ty: CompressedType,
+    width: usize,
+    height: usize,
+    data: &[u8],
+) -> Option<DecodedImage> {
+    use CompressedType::*;
+
+    let (internal_format, format) = decoded_format(ty)?;
+    if data.len() < Texture::compute_compressed_data_size(ty, width, height) {
+        return None;
+    }
+    let channels = match format {
+        PixelDataFormat::R => 1,
+        PixelDataFormat::RG => 2,
+        _ => 4,
+    };
+
+    let (block_width, block_height, block_size) = compressed_block_size(ty);
+    let blocks_x = (width + block_width - 1) / block_width;
+    let blocks_y = (height + block_height - 1) / block_height;
+    let mut pixels = vec![0u8; width * height * channels];
+    let mut texels = vec![[0u8; 4]; block_width * block_height];
+
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let offset = (by * blocks_x + bx) * block_size;
+            let block = &data[offset..offset + block_size];
+            if is_astc(ty) {
+                astc::decode_block(
+                    block,
+                    block_width,
+                    block_height,
+                    is_astc_srgb(ty),
+                    &mut texels,
+                );
+            } else {
+                let texels = (&mut texels[..]).try_into().unwrap();
+                match ty {
+                    EAC_R11 => decode_eac_r11(block, 0, texels),
+                    EAC_R11_SIGNED => decode_eac_r11_signed(block, 0, texels),
+                    EAC_RG11 => {
+                        decode_eac_r11(&block[..8], 0, texels);
+                        decode_eac_r11(&block[8..], 1, texels);
+                    }
+                    EAC_RG11_SIGNED => {
+                        decode_eac_r11_signed(&block[..8], 0, texels);
+                        decode_eac_r11_signed(&block[8..], 1, texels);
+                    }
+                    ETC2_RGB8 | ETC2_SRGB8 => decode_etc2(block, false, texels),
+                    ETC2_RGB8_A1 | ETC2_SRGB8_A1 => decode_etc2(block, true, texels),
+                    ETC2_EAC_RGBA8 | ETC2_EAC_SRGBA8 => {
+                        decode_etc2(&block[8..], false, texels);
+                        decode_eac_alpha(&block[..8], texels);
+                    }
+                    DXT1_RGB | DXT1_SRGB => decode_bc1(block, false, texels),
+                    DXT1_RGBA | DXT1_SRGBA => decode_bc1(block, true, texels),
+                    DXT3_RGBA | DXT3_SRGBA => {
+                        decode_bc1_colors(&block[8..], true, false, texels);
+                        decode_bc2_alpha(&block[..8], texels);
+                    }
+                    DXT5_RGBA | DXT5_SRGBA => {
+                        decode_bc1_colors(&block[8..], true, false, texels);
+                        decode_bc3_alpha(&block[..8], texels);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            for row in 0..block_height {
+                let y = by * block_height + row;
+                if y >= height {
+                    break;
+                }
+                for column in 0..block_width {
+                    let x = bx * block_width + column;
+                    if x >= width {
+                        break;
+                    }
+                    let dst = (y * width + x) * channels;
+                    pixels[dst..dst + channels]
+                        .copy_from_slice(&texels[row * block_width + column][..channels]);
+                }
+            }
+        }
+    }
+
+    let ty = match ty {
+        EAC_R11_SIGNED | EAC_RG11_SIGNED => PixelDataType::BYTE,
+        _ => PixelDataType::UBYTE,
+    };
+    Some(DecodedImage {
+        data: pixels,
+        internal_format,
+        format,
+        ty,
+    })
+}
+
+// Texels are written to `texels` in row-major order
+
+fn rgb565(color: u16) -> [i32; 3] {
+    let r = ((color >> 11) & 31) as i32;
+    let g = ((color >> 5) & 63) as i32;
+    let b = (color & 31) as i32;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    ]
+}
+
+fn decode_bc1(block: &[u8], alpha: bool, texels: &mut [[u8; 4]; 16]) {
+    decode_bc1_colors(block, false, alpha, texels)
+}
+
+/// Decodes the color half of a BC1, BC2 or BC3 block. Only BC1 blocks use the three color mode,
+/// whose fourth color is black, and transparent when `alpha` is set.
+fn decode_bc1_colors(block: &[u8], four_colors: bool, alpha: bool, texels: &mut [[u8; 4]; 16]) {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (e0, e1) = (rgb565(c0), rgb565(c1));
+
+    let mut palette = [[0u8, 0, 0, 255]; 4];
+    for i in 0..3 {
+        palette[0][i] = e0[i] as u8;
+        palette[1][i] = e1[i] as u8;
+        if four_colors || c0 > c1 {
+            palette[2][i] = ((2 * e0[i] + e1[i]) / 3) as u8;
+            palette[3][i] = ((e0[i] + 2 * e1[i]) / 3) as u8;
+        } else {
+            palette[2][i] = ((e0[i] + e1[i]) / 2) as u8;
+        }
+    }
+    if !four_colors && c0 <= c1 && alpha {
+        palette[3][3] = 0;
+    }
+
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    for (i, texel) in texels.iter_mut().enumerate() {
+        *texel = palette[((indices >> (2 * i)) & 3) as usize];
+    }
+}
+
+fn decode_bc2_alpha(block: &[u8], texels: &mut [[u8; 4]; 16]) {
+    let alphas = u64::from_le_bytes(block[..8].try_into().unwrap());
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[3] = (((alphas >> (4 * i)) & 15) * 17) as u8;
+    }
+}
+
+fn decode_bc3_alpha(block: &[u8], texels: &mut [[u8; 4]; 16]) {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let mut palette = [a0, a1, 0, 0, 0, 0, 0, 255];
+    if a0 > a1 {
+        for i in 1..7 {
+            palette[i + 1] = ((7 - i as u32) * a0 + i as u32 * a1) / 7;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = ((5 - i as u32) * a0 + i as u32 * a1) / 5;
+        }
+    }
+
+    let mut bytes = [0u8; 8];
+    bytes[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bytes);
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[3] = palette[((indices >> (3 * i)) & 7) as usize] as u8;
+    }
+}
+
+const EAC_MODIFIERS: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+/// Calls `f` with the row-major texel index and the modifier of each texel of an EAC block.
+/// Indices are stored most significant first, in column-major order.
+fn for_each_eac_texel(block: &[u8], mut f: impl FnMut(usize, i32, i32, i32)) {
+    let bits = u64::from_be_bytes(block[..8].try_into().unwrap());
+    let base = ((bits >> 56) & 255) as i32;
+    let multiplier = ((bits >> 52) & 15) as i32;
+    let table = &EAC_MODIFIERS[((bits >> 48) & 15) as usize];
+    for i in 0..16 {
+        let index = ((bits >> (45 - 3 * i)) & 7) as usize;
+        let (x, y) = (i / 4, i % 4);
+        f(y * 4 + x, base, multiplier, table[index]);
+    }
+}
+
+fn decode_eac_alpha(block: &[u8], texels: &mut [[u8; 4]; 16]) {
+    for_each_eac_texel(block, |texel, base, multiplier, modifier| {
+        texels[texel][3] = (base + modifier * multiplier).clamp(0, 255) as u8;
+    });
+}
+
+fn decode_eac_r11(block: &[u8], channel: usize, texels: &mut [[u8; 4]; 16]) {
+    for_each_eac_texel(block, |texel, base, multiplier, modifier| {
+        let modifier = if multiplier == 0 {
+            modifier
+        } else {
+            modifier * multiplier * 8
+        };
+        let value = (base * 8 + 4 + modifier).clamp(0, 2047);
+        texels[texel][channel] = (value >> 3) as u8;
+        texels[texel][3] = 255;
+    });
+}
+
+/// Decodes a signed EAC channel, storing each value as an `i8`.
+fn decode_eac_r11_signed(block: &[u8], channel: usize, texels: &mut [[u8; 4]; 16]) {
+    // The base is a signed byte, and -128 is treated as -127
+    let base = (block[0] as i8).max(-127) as i32;
+    for_each_eac_texel(block, |texel, _, multiplier, modifier| {
+        let modifier = if multiplier == 0 {
+            modifier
+        } else {
+            modifier * multiplier * 8
+        };
+        let value = (base * 8 + modifier).clamp(-1023, 1023);
+        texels[texel][channel] = ((value * 127) as f32 / 1023.0).round() as i8 as u8;
+        texels[texel][3] = 255;
+    });
+}
+
+const ETC_MODIFIERS: [[i32; 4]; 8] = [
+    [2, 8, -2, -8],
+    [5, 17, -5, -17],
+    [9, 29, -9, -29],
+    [13, 42, -13, -42],
+    [18, 60, -18, -60],
+    [24, 80, -24, -80],
+    [33, 106, -33, -106],
+    [47, 183, -47, -183],
+];
+
+const ETC_DISTANCES: [i32; 8] = [3, 6, 11, 16, 20, 23, 32, 64];
+
+fn extend4(value: u64) -> i32 {
+    (value as i32 & 15) * 17
+}
+
+fn extend5(value: i32) -> i32 {
+    (value << 3) | (value >> 2)
+}
+
+fn etc_color(rgb: [i32; 3], offset: i32) -> [u8; 4] {
+    [
+        (rgb[0] + offset).clamp(0, 255) as u8,
+        (rgb[1] + offset).clamp(0, 255) as u8,
+        (rgb[2] + offset).clamp(0, 255) as u8,
+        255,
+    ]
+}
+
+/// Decodes an ETC1 or ETC2 RGB block. With `punchthrough`, the differential bit instead marks
+/// opaque blocks, and texels of non-opaque blocks with index 2 are transparent black.
+fn decode_etc2(block: &[u8], punchthrough: bool, texels: &mut [[u8; 4]; 16]) {
+    let bits = u64::from_be_bytes(block[..8].try_into().unwrap());
+    let flag = (bits >> 33) & 1 == 1;
+    let (differential, opaque) = if punchthrough {
+        (true, flag)
+    } else {
+        (flag, true)
+    };
+
+    // Indices are split in two planes of most and least significant bits, in column-major order
+    let indices = |i: usize| (((bits >> (16 + i)) & 1) << 1 | ((bits >> i) & 1)) as usize;
+    let texel = |i: usize| (i % 4) * 4 + i / 4;
+
+    let mut base = [[0i32; 3]; 2];
+    if differential {
+        for c in 0..3 {
+            let shift = 59 - 8 * c;
+            let value = ((bits >> shift) & 31) as i32;
+            let delta = (((bits >> (shift - 3)) & 7) as i32) << 29 >> 29;
+            let second = value + delta;
+            if !(0..=31).contains(&second) {
+                let mut paint = match c {
+                    0 => etc2_t_mode(bits),
+                    1 => etc2_h_mode(bits),
+                    _ => return decode_etc2_planar(bits, texels),
+                };
+                if !opaque {
+                    paint[2] = [0, 0, 0, 0];
+                }
+                for i in 0..16 {
+                    texels[texel(i)] = paint[indices(i)];
+                }
+                return;
+            }
+            base[0][c] = extend5(value);
+            base[1][c] = extend5(second);
+        }
+    } else {
+        for c in 0..3 {
+            let shift = 60 - 8 * c;
+            base[0][c] = extend4(bits >> shift);
+            base[1][c] = extend4(bits >> (shift - 4));
+        }
+    }
+
+    let tables = [
+        &ETC_MODIFIERS[((bits >> 37) & 7) as usize],
+        &ETC_MODIFIERS[((bits >> 34) & 7) as usize],
+    ];
+    let flip = (bits >> 32) & 1 == 1;
+    for i in 0..16 {
+        let (x, y) = (i / 4, i % 4);
+        let subblock = if flip {
+            (y >= 2) as usize
+        } else {
+            (x >= 2) as usize
+        };
+        let index = indices(i);
+        texels[texel(i)] = if !opaque && index == 2 {
+            [0, 0, 0, 0]
+        } else if !opaque && index == 0 {
+            etc_color(base[subblock], 0)
+        } else {
+            etc_color(base[subblock], tables[subblock][index])
+        };
+    }
+}
+
+fn etc2_t_mode(bits: u64) -> [[u8; 4]; 4] {
+    let r1 = (((bits >> 59) & 3) << 2) | ((bits >> 56) & 3);
+    let c1 = [extend4(r1), extend4(bits >> 52), extend4(bits >> 48)];
+    let c2 = [
+        extend4(bits >> 44),
+        extend4(bits >> 40),
+        extend4(bits >> 36),
+    ];
+    let distance = ETC_DISTANCES[((((bits >> 34) & 3) << 1) | ((bits >> 32) & 1)) as usize];
+    [
+        etc_color(c1, 0),
+        etc_color(c2, distance),
+        etc_color(c2, 0),
+        etc_color(c2, -distance),
+    ]
+}
+
+fn etc2_h_mode(bits: u64) -> [[u8; 4]; 4] {
+    let r1 = (bits >> 59) & 15;
+    let g1 = (((bits >> 56) & 7) << 1) | ((bits >> 52) & 1);
+    let b1 = (((bits >> 51) & 1) << 3) | ((bits >> 47) & 7);
+    let (r2, g2, b2) = ((bits >> 43) & 15, (bits >> 39) & 15, (bits >> 35) & 15);
+    let c1 = [extend4(r1), extend4(g1), extend4(b1)];
+    let c2 = [extend4(r2), extend4(g2), extend4(b2)];
+
+    let order = ((r1 << 8) | (g1 << 4) | b1) >= ((r2 << 8) | (g2 << 4) | b2);
+    let index = (((bits >> 34) & 1) << 2) | (((bits >> 32) & 1) << 1) | order as u64;
+    let distance = ETC_DISTANCES[index as usize];
+    [
+        etc_color(c1, distance),
+        etc_color(c1, -distance),
+        etc_color(c2, distance),
+        etc_color(c2, -distance),
+    ]
+}
+
+fn decode_etc2_planar(bits: u64, texels: &mut [[u8; 4]; 16]) {
+    let field = |shift: u32, len: u32| ((bits >> shift) & ((1 << len) - 1)) as i32;
+    let extend6 = |v: i32| (v << 2) | (v >> 4);
+    let extend7 = |v: i32| (v << 1) | (v >> 6);
+
+    let origin = [
+        extend6(field(57, 6)),
+        extend7((field(56, 1) << 6) | field(49, 6)),
+        extend6((field(48, 1) << 5) | (field(43, 2) << 3) | field(39, 3)),
+    ];
+    let horizontal = [
+        extend6((field(34, 5) << 1) | field(32, 1)),
+        extend7(field(25, 7)),
+        extend6(field(19, 6)),
+    ];
+    let vertical = [
+        extend6(field(13, 6)),
+        extend7(field(6, 7)),
+        extend6(field(0, 6)),
+    ];
+
+    for y in 0..4 {
+        for x in 0..4 {
+            let mut color = [0u8, 0, 0, 255];
+            for c in 0..3 {
+                let value = (x * (horizontal[c] - origin[c])
+                    + y * (vertical[c] - origin[c])
+                    + 4 * origin[c]
+                    + 2)
+                    >> 2;
+                color[c] = value.clamp(0, 255) as u8;
+            }
+            texels[(y * 4 + x) as usize] = color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decoded_format};
+    use crate::texture::{CompressedType, InternalFormat, PixelDataFormat, PixelDataType};
+
+    #[test]
+    fn bc_blocks() {
+        // Solid red, with the three color mode's transparent black in the last texel
+        let mut block = vec![0x00, 0xf8, 0x00, 0xf8, 0, 0, 0, 0xc0];
+        let image = decode(CompressedType::DXT1_RGBA, 4, 4, &block).unwrap();
+        assert_eq!(&image.data[..4], &[255, 0, 0, 255]);
+        assert_eq!(&image.data[60..], &[0, 0, 0, 0]);
+        assert_eq!(image.internal_format, InternalFormat::RGBA8);
+
+        // BC3 with an alpha ramp between 255 and 0
+        block.splice(0..0, vec![255, 0, 0b0000_1000, 0, 0, 0, 0, 0]);
+        let image = decode(CompressedType::DXT5_SRGBA, 2, 1, &block).unwrap();
+        assert_eq!(image.data, vec![255, 0, 0, 255, 255, 0, 0, 0]);
+        assert_eq!(image.internal_format, InternalFormat::SRGB8_A8);
+
+        assert!(decode(CompressedType::DXT3_RGBA, 4, 4, &block[..8]).is_none());
+    }
+
+    #[test]
+    fn etc_blocks() {
+        // Individual mode, base colors (0x88, 0x44, 0x22) and (0xff, 0, 0x11), table 0, side by
+        // side sub-blocks. The first texel uses +2 and the last -8.
+        let block = [0x8f, 0x40, 0x21, 0x00, 0x80, 0x00, 0x80, 0x00];
+        let image = decode(CompressedType::ETC2_RGB8, 4, 4, &block).unwrap();
+        assert_eq!(&image.data[..4], &[0x8a, 0x46, 0x24, 255]);
+        assert_eq!(&image.data[60..], &[0xf7, 0, 0x09, 255]);
+
+        // EAC alpha with base 128, multiplier 1, table 13 and all indices 4 (modifier 0)
+        let mut block = vec![128, 0x1d, 0x92, 0x49, 0x24, 0x92, 0x49, 0x24];
+        block.extend_from_slice(&[0x8f, 0x40, 0x21, 0, 0, 0, 0, 0]);
+        let image = decode(CompressedType::ETC2_EAC_RGBA8, 4, 4, &block).unwrap();
+        assert!(image.data.chunks(4).all(|texel| texel[3] == 128));
+
+        let image = decode(CompressedType::EAC_R11, 1, 1, &block[..8]).unwrap();
+        assert_eq!(image.data, vec![128]);
+        assert_eq!(
+            decoded_format(CompressedType::EAC_RG11),
+            Some((InternalFormat::RG8, PixelDataFormat::RG))
+        );
+
+        // Signed EAC with base 64, multiplier 1, table 13 and all indices 4 (modifier 0)
+        block[0] = 64;
+        let image = decode(CompressedType::EAC_R11_SIGNED, 2, 1, &block[..8]).unwrap();
+        assert_eq!(image.data, vec![64, 64]);
+        assert_eq!(image.ty, PixelDataType::BYTE);
+        block[0] = 0xc0;
+        let image = decode(CompressedType::EAC_RG11_SIGNED, 1, 1, &block).unwrap();
+        assert_eq!(image.data[0] as i8, -64);
+        assert_eq!(image.internal_format, InternalFormat::RG8_SNORM);
+    }
+
+    #[test]
+    fn astc_blocks() {
+        // A void-extent block covering two 6x5 blocks, orange
+        let bits = 0x1fc | (0x3f_ffff_ffff_ffff << 10) | (0xffff_0000_8000_ffffu128 << 64);
+        let mut data = bits.to_le_bytes().to_vec();
+        data.extend_from_slice(&bits.to_le_bytes());
+        let image = decode(CompressedType::SRGB8_ALPHA8_ASTC_6x5, 7, 5, &data).unwrap();
+        assert_eq!(image.data.len(), 7 * 5 * 4);
+        assert!(image
+            .data
+            .chunks(4)
+            .all(|texel| texel == [255, 128, 0, 255]));
+        assert_eq!(image.internal_format, InternalFormat::SRGB8_A8);
+        assert_eq!(
+            decoded_format(CompressedType::RGBA_ASTC_12x12),
+            Some((InternalFormat::RGBA8, PixelDataFormat::RGBA))
+        );
+        assert!(decode(CompressedType::RGBA_ASTC_12x12, 13, 1, &data[..16]).is_none());
+    }
+}
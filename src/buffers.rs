@@ -6,6 +6,7 @@ use std::{convert::TryInto, sync::Arc};
 use crate::{
     engine::{Engine, EngineError},
     impl_handle,
+    renderable::{MorphError, MorphTargets},
 };
 
 pub use ffi::filament_VertexAttribute as VertexAttribute;
@@ -67,6 +68,50 @@ impl VertexBufferBuilder {
         self
     }
 
+    /// Declares the `BONE_INDICES` attribute, four bone indices per vertex stored as `u16`.
+    pub fn bone_indices(self, index: usize, offset: usize, stride: usize) -> Self {
+        self.attribute(
+            VertexAttribute::BONE_INDICES,
+            index,
+            AttributeType::USHORT4,
+            offset,
+            stride,
+        )
+    }
+
+    /// Declares the `BONE_WEIGHTS` attribute, four bone weights per vertex stored as `f32`.
+    pub fn bone_weights(self, index: usize, offset: usize, stride: usize) -> Self {
+        self.attribute(
+            VertexAttribute::BONE_WEIGHTS,
+            index,
+            AttributeType::FLOAT4,
+            offset,
+            stride,
+        )
+    }
+
+    /// Declares the position and tangent attributes of every morph target in `targets`. The buffer
+    /// count must account for `MorphTargets::buffer_count`.
+    pub fn morph_targets(self, targets: &MorphTargets) -> Self {
+        (0..targets.count()).fold(self, |builder, target| {
+            builder
+                .attribute(
+                    MorphTargets::position_attribute(target),
+                    targets.positions_buffer(target),
+                    AttributeType::FLOAT3,
+                    0,
+                    std::mem::size_of::<[f32; 3]>(),
+                )
+                .attribute(
+                    MorphTargets::tangents_attribute(target),
+                    targets.tangents_buffer(target),
+                    AttributeType::FLOAT4,
+                    0,
+                    std::mem::size_of::<[f32; 4]>(),
+                )
+        })
+    }
+
     pub fn normalized(mut self, attribute: VertexAttribute, normalized: bool) -> Self {
         unsafe {
             self.inner.normalized(attribute, normalized);
@@ -121,6 +166,33 @@ impl VertexBuffer {
         unsafe { self.as_raw_ref().getVertexCount().try_into().unwrap() }
     }
 
+    /// Uploads the positions and tangent quaternions of a single morph target, which must hold one
+    /// entry per vertex.
+    pub fn write_morph_target(
+        &mut self,
+        targets: &MorphTargets,
+        target: usize,
+        positions: Vec<[f32; 3]>,
+        tangents: Vec<[f32; 4]>,
+    ) -> Result<(), MorphError> {
+        targets.check_target(target)?;
+
+        let expected = self.vertex_count();
+        for actual in [positions.len(), tangents.len()].iter() {
+            if *actual != expected {
+                return Err(MorphError::VertexCountMismatch {
+                    expected,
+                    actual: *actual,
+                });
+            }
+        }
+
+        self.write_at(targets.positions_buffer(target), 0, positions);
+        self.write_at(targets.tangents_buffer(target), 0, tangents);
+
+        Ok(())
+    }
+
     pub fn populate_tangent_quaternions(ctx: &QuatTangentContext) {
         unsafe { ffi::filament_VertexBuffer::populateTangentQuaternions(ctx as *const _) }
     }
@@ -1,4 +1,4 @@
-use filament_sys::ffi;
+use filament_sys::{ffi, Float3, Mat4, Mat4f};
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::ToPrimitive;
 use std::sync::Arc;
@@ -32,7 +32,7 @@ impl Drop for Camera {
     fn drop(&mut self) {
         if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
             log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
-            unsafe { self.engine.as_raw_mut().destroy17(*ptr) }
+            unsafe { crate::entity_manager::destroy_camera(&mut self.engine, *ptr) }
         }
     }
 }
@@ -73,6 +73,54 @@ impl Camera {
         }
     }
 
+    /// Sets a custom projection matrix, with `near` and `far` the distances used for culling.
+    pub fn set_custom_projection<M: Into<Mat4>>(&mut self, projection: M, near: f64, far: f64) {
+        let projection = projection.into();
+        unsafe {
+            self.as_raw_mut()
+                .setCustomProjection(&projection as *const _, near, far)
+        }
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        unsafe { *self.as_raw_ref().getProjectionMatrix() }
+    }
+
+    /// Sets the position and orientation of the camera, which must be a rigid transform. The camera
+    /// looks towards its -Z axis.
+    pub fn set_model_matrix<M: Into<Mat4f>>(&mut self, model: M) {
+        let model = model.into();
+        unsafe { self.as_raw_mut().setModelMatrix(&model as *const _) }
+    }
+
+    pub fn look_at<V: Into<Float3>>(&mut self, eye: V, center: V, up: V) {
+        let (eye, center, up) = (eye.into(), center.into(), up.into());
+        unsafe {
+            self.as_raw_mut()
+                .lookAt(&eye as *const _, &center as *const _, &up as *const _)
+        }
+    }
+
+    pub fn model_matrix(&self) -> Mat4f {
+        unsafe { self.as_raw_ref().getModelMatrix() }
+    }
+
+    pub fn view_matrix(&self) -> Mat4f {
+        unsafe { self.as_raw_ref().getViewMatrix() }
+    }
+
+    pub fn position(&self) -> Float3 {
+        unsafe { self.as_raw_ref().getPosition() }
+    }
+
+    pub fn forward_vector(&self) -> Float3 {
+        unsafe { self.as_raw_ref().getForwardVector() }
+    }
+
+    pub fn up_vector(&self) -> Float3 {
+        unsafe { self.as_raw_ref().getUpVector() }
+    }
+
     pub fn new(engine: &mut Engine, entity: crate::Entity) -> Result<Self, EngineError> {
         let ptr = unsafe { engine.as_raw_mut().createCamera(entity) };
 
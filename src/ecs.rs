@@ -0,0 +1,716 @@
+//! Components and systems keeping Filament in sync with an ECS, enabled by the `ecs` feature.
+//!
+//! Nothing here depends on a particular ECS. A `SceneSync` is keyed by the ECS's own entity type,
+//! and each `sync_*` system is handed every entity holding that component, once per frame. With
+//! hecs, for example:
+//!
+//! ```ignore
+//! sync.sync_transforms(world.query::<&Transform>().iter());
+//! sync.sync_renderables(world.query::<&Renderable>().iter());
+//! sync.sync_lights(world.query::<&Light>().iter());
+//! for (entity, error) in sync.sync_cameras(world.query::<&Camera>().iter()).errors {
+//!     log::warn!("Failed to sync the camera of {:?}: {}", entity, error);
+//! }
+//! ```
+//!
+//! Components are compared with the values last applied, so only entities whose components were
+//! added, changed or removed call into Filament. An entity missing from a system's input loses that
+//! component, and its Filament entity is destroyed once it has none left. A component that fails to
+//! apply is reported in the `SyncReport` and applied again next frame, without holding back the
+//! others.
+use filament_sys::{Float3, Mat4f};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+use thiserror::Error;
+
+use crate::{
+    buffers::{IndexBuffer, VertexBuffer},
+    engine::{Engine, EngineError},
+    entity_manager::OwnedEntity,
+    light::{LightBuildError, LightBuilder, LightType},
+    material::MaterialInstance,
+    renderable::{BoundingBox, PrimitiveType, RenderableBuildError, RenderableBuilder},
+    scene::Scene,
+    surface::Perspective,
+    Entity,
+};
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("Failed to build a renderable: {0}")]
+    Renderable(#[from] RenderableBuildError),
+    #[error("Failed to build a light: {0}")]
+    Light(#[from] LightBuildError),
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+}
+
+/// What a `SceneSync::sync_*` system did in one frame.
+#[derive(Debug)]
+pub struct SyncReport<K> {
+    /// The number of entities whose component was applied or removed.
+    pub changed: usize,
+    /// The entities whose component failed to apply, with the reason.
+    pub errors: Vec<(K, SyncError)>,
+}
+impl<K> SyncReport<K> {
+    /// Whether every component was applied.
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+impl<K> Default for SyncReport<K> {
+    fn default() -> Self {
+        Self {
+            changed: 0,
+            errors: Vec::new(),
+        }
+    }
+}
+
+/// The transform of an entity, relative to the world.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Transform {
+    pub matrix: Mat4f,
+}
+impl Transform {
+    #[inline]
+    pub fn new<M: Into<Mat4f>>(matrix: M) -> Self {
+        Self {
+            matrix: matrix.into(),
+        }
+    }
+}
+
+/// One piece of geometry of a `Renderable`, drawn with a single material.
+#[derive(Clone, PartialEq)]
+pub struct Primitive {
+    pub primitive_type: PrimitiveType,
+    pub vertex_buffer: VertexBuffer,
+    pub index_buffer: IndexBuffer,
+    pub material: MaterialInstance,
+}
+
+/// Geometry drawn at the entity's transform.
+#[derive(Clone, PartialEq)]
+pub struct Renderable {
+    pub primitives: Vec<Primitive>,
+    pub bounding_box: BoundingBox,
+    pub layer_mask: u8,
+    pub cast_shadows: bool,
+    pub receive_shadows: bool,
+}
+impl Renderable {
+    pub fn new(primitives: Vec<Primitive>, bounding_box: BoundingBox) -> Self {
+        Self {
+            primitives,
+            bounding_box,
+            layer_mask: 0x1,
+            cast_shadows: false,
+            receive_shadows: true,
+        }
+    }
+}
+
+/// A light placed at the entity's transform.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Light {
+    pub light_type: LightType,
+    /// The linear RGB color of the light.
+    pub color: Float3,
+    /// In lux for directional lights and in lumens for point and spot lights.
+    pub intensity: f32,
+    /// The direction of directional and spot lights, relative to the entity's transform.
+    pub direction: Float3,
+    /// The distance at which point and spot lights stop having an effect.
+    pub falloff: f32,
+    /// The inner and outer angles of a spot light's cone, in radians.
+    pub spot_cone: (f32, f32),
+    pub cast_shadows: bool,
+}
+impl Light {
+    pub fn new(light_type: LightType) -> Self {
+        Self {
+            light_type,
+            color: Float3::new(1.0, 1.0, 1.0),
+            intensity: 100_000.0,
+            direction: Float3::new(0.0, -1.0, 0.0),
+            falloff: 1.0,
+            spot_cone: (std::f32::consts::FRAC_PI_8, std::f32::consts::FRAC_PI_4),
+            cast_shadows: false,
+        }
+    }
+}
+
+/// A camera looking from the entity's transform. The Filament camera is available from
+/// `SceneSync::camera` to be set on a `View`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    pub perspective: Perspective,
+    pub aspect: f64,
+}
+impl Camera {
+    #[inline]
+    pub fn new(perspective: Perspective, aspect: f64) -> Self {
+        Self {
+            perspective,
+            aspect,
+        }
+    }
+}
+
+struct Synced {
+    transform: Option<Transform>,
+    renderable: Option<Renderable>,
+    light: Option<Light>,
+    camera: Option<Camera>,
+    filament_camera: Option<crate::Camera>,
+    in_scene: bool,
+    entity: OwnedEntity,
+}
+impl Synced {
+    fn new(engine: &mut Engine) -> Self {
+        Self {
+            transform: None,
+            renderable: None,
+            light: None,
+            camera: None,
+            filament_camera: None,
+            in_scene: false,
+            entity: OwnedEntity::new(engine),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.transform.is_none()
+            && self.renderable.is_none()
+            && self.light.is_none()
+            && self.camera.is_none()
+    }
+
+    fn update_scene(&mut self, scene: &mut Scene) {
+        let in_scene = self.renderable.is_some() || self.light.is_some();
+        if in_scene != self.in_scene {
+            if in_scene {
+                scene.push(self.entity.entity());
+            } else {
+                scene.remove(self.entity.entity());
+            }
+            self.in_scene = in_scene;
+        }
+    }
+}
+
+/// Mirrors the components of an ECS into Filament entities, adding the ones with a renderable or
+/// a light to a `Scene`.
+///
+/// `K` is the entity type of the ECS. Each ECS entity gets its own Filament entity, which is
+/// created and destroyed along with its components.
+pub struct SceneSync<K> {
+    engine: Engine,
+    scene: Scene,
+    entities: HashMap<K, Synced>,
+}
+impl<K: Copy + Eq + Hash> SceneSync<K> {
+    pub fn new(engine: &mut Engine, scene: &Scene) -> Self {
+        Self {
+            engine: engine.clone(),
+            scene: scene.clone(),
+            entities: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    /// Returns the Filament entity mirroring `key`, if it has any synced component.
+    pub fn entity(&self, key: K) -> Option<Entity> {
+        self.entities.get(&key).map(|synced| synced.entity.entity())
+    }
+
+    /// Returns the Filament camera created for the `Camera` component of `key`.
+    pub fn camera(&self, key: K) -> Option<&crate::Camera> {
+        self.entities
+            .get(&key)
+            .and_then(|synced| synced.filament_camera.as_ref())
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Destroys every synced Filament entity, removing them from the scene.
+    pub fn clear(&mut self) {
+        for synced in self.entities.values() {
+            if synced.in_scene {
+                self.scene.remove(synced.entity.entity());
+            }
+        }
+        self.entities.clear();
+    }
+
+    /// Applies the transforms of this frame.
+    pub fn sync_transforms<'c, I>(&mut self, transforms: I) -> SyncReport<K>
+    where
+        I: IntoIterator<Item = (K, &'c Transform)>,
+    {
+        self.sync_components(
+            transforms,
+            |synced| &mut synced.transform,
+            |engine, synced, transform| {
+                let entity = synced.entity.entity();
+                let mut transform_manager = engine.transform_manager()?;
+                match (transform, transform_manager.get(entity)) {
+                    (Some(transform), Some(instance)) => {
+                        transform_manager.set_transform(instance, transform.matrix)
+                    }
+                    (Some(transform), None) => {
+                        transform_manager.create(entity, None, transform.matrix)
+                    }
+                    // Cameras need a transform, so theirs is only reset
+                    (None, Some(instance)) if synced.filament_camera.is_some() => {
+                        transform_manager.set_transform(instance, Mat4f::IDENTITY)
+                    }
+                    (None, _) => transform_manager.remove(entity),
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Applies the renderables of this frame.
+    ///
+    /// Changing the number of primitives rebuilds the renderable, anything else is updated in
+    /// place.
+    pub fn sync_renderables<'c, I>(&mut self, renderables: I) -> SyncReport<K>
+    where
+        I: IntoIterator<Item = (K, &'c Renderable)>,
+    {
+        self.sync_components(
+            renderables,
+            |synced| &mut synced.renderable,
+            |engine, synced, renderable| {
+                let entity = synced.entity.entity();
+                let previous = synced.renderable.as_ref();
+                let renderable = match renderable {
+                    Some(renderable) => renderable,
+                    None => {
+                        engine.renderable_manager()?.destroy(entity);
+                        return Ok(());
+                    }
+                };
+
+                let previous = match previous {
+                    Some(previous) if previous.primitives.len() == renderable.primitives.len() => {
+                        previous
+                    }
+                    _ => return build_renderable(engine, entity, renderable),
+                };
+
+                let mut renderable_manager = engine.renderable_manager()?;
+                let instance = match renderable_manager.get_instance(entity) {
+                    Some(instance) => instance,
+                    None => return build_renderable(engine, entity, renderable),
+                };
+                for (index, (old, new)) in previous
+                    .primitives
+                    .iter()
+                    .zip(&renderable.primitives)
+                    .enumerate()
+                {
+                    if old.primitive_type != new.primitive_type
+                        || old.vertex_buffer != new.vertex_buffer
+                        || old.index_buffer != new.index_buffer
+                    {
+                        renderable_manager.set_geometry_at(
+                            instance,
+                            index,
+                            new.primitive_type,
+                            &new.vertex_buffer,
+                            &new.index_buffer,
+                            0..new.index_buffer.index_count(),
+                        );
+                    }
+                    if old.material != new.material {
+                        renderable_manager.set_material_instance_at(instance, index, &new.material);
+                    }
+                }
+                if previous.bounding_box != renderable.bounding_box {
+                    renderable_manager
+                        .set_axis_aligned_bounding_box(instance, renderable.bounding_box);
+                }
+                if previous.layer_mask != renderable.layer_mask {
+                    renderable_manager.set_layer_mask(instance, 0xff, renderable.layer_mask);
+                }
+                if previous.cast_shadows != renderable.cast_shadows {
+                    renderable_manager.set_cast_shadows(instance, renderable.cast_shadows);
+                }
+                if previous.receive_shadows != renderable.receive_shadows {
+                    renderable_manager.set_receive_shadows(instance, renderable.receive_shadows);
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Applies the lights of this frame.
+    ///
+    /// Changing the type of a light rebuilds it, anything else is updated in place.
+    pub fn sync_lights<'c, I>(&mut self, lights: I) -> SyncReport<K>
+    where
+        I: IntoIterator<Item = (K, &'c Light)>,
+    {
+        self.sync_components(
+            lights,
+            |synced| &mut synced.light,
+            |engine, synced, light| {
+                let entity = synced.entity.entity();
+                let light = match light {
+                    Some(light) => light,
+                    None => {
+                        engine.light_manager()?.destroy(entity);
+                        return Ok(());
+                    }
+                };
+
+                let mut light_manager = engine.light_manager()?;
+                let (previous, instance) = match (synced.light, light_manager.get_instance(entity))
+                {
+                    (Some(previous), Some(instance)) if previous.light_type == light.light_type => {
+                        (previous, instance)
+                    }
+                    (_, instance) => {
+                        if instance.is_some() {
+                            light_manager.destroy(entity);
+                        }
+                        return build_light(engine, entity, light);
+                    }
+                };
+
+                if previous.color != light.color {
+                    light_manager.set_color(instance, light.color);
+                }
+                if previous.intensity != light.intensity {
+                    light_manager.set_intensity(instance, light.intensity);
+                }
+                if previous.direction != light.direction {
+                    light_manager.set_direction(instance, light.direction);
+                }
+                if previous.falloff != light.falloff {
+                    light_manager.set_falloff(instance, light.falloff);
+                }
+                if previous.spot_cone != light.spot_cone {
+                    light_manager.set_spot_light_cone(
+                        instance,
+                        light.spot_cone.0,
+                        light.spot_cone.1,
+                    );
+                }
+                if previous.cast_shadows != light.cast_shadows {
+                    light_manager.set_shadow_caster(instance, light.cast_shadows);
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Applies the cameras of this frame.
+    pub fn sync_cameras<'c, I>(&mut self, cameras: I) -> SyncReport<K>
+    where
+        I: IntoIterator<Item = (K, &'c Camera)>,
+    {
+        self.sync_components(
+            cameras,
+            |synced| &mut synced.camera,
+            |engine, synced, camera| {
+                let camera = match camera {
+                    Some(camera) => camera,
+                    None => {
+                        synced.filament_camera = None;
+                        return Ok(());
+                    }
+                };
+
+                if synced.filament_camera.is_none() {
+                    synced.filament_camera =
+                        Some(crate::Camera::new(engine, synced.entity.entity())?);
+                }
+                if let Some(filament_camera) = &mut synced.filament_camera {
+                    let perspective = camera.perspective;
+                    filament_camera.set_projection_fov(
+                        perspective.fov,
+                        camera.aspect,
+                        perspective.near,
+                        perspective.far,
+                        perspective.direction,
+                    );
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Diffs one kind of component against what was last applied. `apply` is called with the
+    /// component's previous value still in place and the new one, or `None` when it was removed.
+    fn sync_components<'c, C, I, S, A>(
+        &mut self,
+        components: I,
+        slot: S,
+        mut apply: A,
+    ) -> SyncReport<K>
+    where
+        C: Clone + PartialEq + 'c,
+        I: IntoIterator<Item = (K, &'c C)>,
+        S: Fn(&mut Synced) -> &mut Option<C>,
+        A: FnMut(&mut Engine, &mut Synced, Option<&C>) -> Result<(), SyncError>,
+    {
+        let mut seen = HashSet::new();
+        let mut report = SyncReport::default();
+
+        for (key, component) in components {
+            seen.insert(key);
+
+            let engine = &mut self.engine;
+            let synced = self
+                .entities
+                .entry(key)
+                .or_insert_with(|| Synced::new(engine));
+            if slot(synced).as_ref() == Some(component) {
+                continue;
+            }
+
+            let result = apply(engine, synced, Some(component));
+            // On failure the component counts as missing, so it's applied again next frame
+            *slot(synced) = result.as_ref().ok().map(|_| component.clone());
+            synced.update_scene(&mut self.scene);
+            match result {
+                Ok(()) => report.changed += 1,
+                Err(error) => report.errors.push((key, error)),
+            }
+        }
+
+        for (key, synced) in &mut self.entities {
+            if seen.contains(key) || slot(synced).is_none() {
+                continue;
+            }
+
+            // A removed component is forgotten even if tearing it down fails
+            let result = apply(&mut self.engine, synced, None);
+            *slot(synced) = None;
+            synced.update_scene(&mut self.scene);
+            match result {
+                Ok(()) => report.changed += 1,
+                Err(error) => report.errors.push((*key, error)),
+            }
+        }
+
+        self.entities.retain(|_, synced| !synced.is_empty());
+
+        report
+    }
+}
+impl<K> Drop for SceneSync<K> {
+    fn drop(&mut self) {
+        for synced in self.entities.values() {
+            if synced.in_scene {
+                self.scene.remove(synced.entity.entity());
+            }
+        }
+    }
+}
+
+fn build_renderable(
+    engine: &mut Engine,
+    entity: Entity,
+    renderable: &Renderable,
+) -> Result<(), SyncError> {
+    let mut builder = RenderableBuilder::new(renderable.primitives.len())?
+        .bounding_box(renderable.bounding_box)
+        .layer_mask(0xff, renderable.layer_mask)
+        .cast_shadows(renderable.cast_shadows)
+        .receive_shadows(renderable.receive_shadows);
+    for (index, primitive) in renderable.primitives.iter().enumerate() {
+        builder = builder
+            .geometry(
+                index,
+                primitive.primitive_type,
+                &primitive.vertex_buffer,
+                &primitive.index_buffer,
+            )
+            .material(index, &primitive.material);
+    }
+    builder.build(engine, entity)?;
+    Ok(())
+}
+
+fn build_light(engine: &mut Engine, entity: Entity, light: &Light) -> Result<(), SyncError> {
+    LightBuilder::new(light.light_type)?
+        .color(light.color)
+        .intensity(light.intensity)
+        .direction(light.direction)
+        .falloff(light.falloff)
+        .spot_light_cone(light.spot_cone.0, light.spot_cone.1)
+        .cast_shadows(light.cast_shadows)
+        .build(engine, entity)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Camera, Light, Primitive, Renderable, SceneSync, SyncError, Transform};
+    use crate::{
+        buffers::{IndexBuffer, IndexType, VertexBuffer},
+        engine::{Engine, EngineError},
+        light::LightType,
+        material::Material,
+        renderable::{BoundingBox, PrimitiveType},
+        scene::Scene,
+        surface::Perspective,
+        AttributeType, Backend, VertexAttribute,
+    };
+    use filament_sys::{Float3, Mat4f};
+
+    #[test]
+    fn scene_sync() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let scene = Scene::new(&mut engine).unwrap();
+        let mut sync = SceneSync::<u32>::new(&mut engine, &scene);
+
+        let mut transforms = vec![(1, Transform::default()), (2, Transform::default())];
+        let light = Light::new(LightType::POINT);
+
+        let sync_transforms = |sync: &mut SceneSync<u32>, transforms: &[(u32, Transform)]| {
+            let report = sync.sync_transforms(transforms.iter().map(|(key, t)| (*key, t)));
+            assert!(report.is_ok());
+            report.changed
+        };
+
+        assert_eq!(sync_transforms(&mut sync, &transforms), 2);
+        assert_eq!(sync_transforms(&mut sync, &transforms), 0);
+        transforms[1].1 = Transform::new(Mat4f::from_cols_array(&[2.0; 16]));
+        assert_eq!(sync_transforms(&mut sync, &transforms), 1);
+
+        assert_eq!(sync.sync_lights(vec![(1, &light)]).changed, 1);
+        assert_eq!(sync.sync_lights(vec![(1, &light)]).changed, 0);
+        let entity = sync.entity(1).unwrap();
+        assert!(scene.contains(entity));
+        assert_eq!(scene.light_count(), 1);
+
+        let camera = Camera::new(Perspective::default(), 1.5);
+        assert_eq!(sync.sync_cameras(vec![(3, &camera)]).changed, 1);
+        assert!(sync.camera(3).is_some());
+        assert_eq!(sync.len(), 3);
+
+        // Dropping every component of an entity destroys it
+        assert_eq!(sync.sync_lights(Vec::new()).changed, 1);
+        assert!(!scene.contains(entity));
+        transforms.remove(0);
+        assert_eq!(sync_transforms(&mut sync, &transforms), 1);
+        assert!(sync.entity(1).is_none());
+        assert_eq!(sync.len(), 2);
+
+        sync.clear();
+        assert!(sync.is_empty());
+    }
+
+    #[test]
+    fn renderable_sync() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let scene = Scene::new(&mut engine).unwrap();
+        let mut sync = SceneSync::<u32>::new(&mut engine, &scene);
+
+        let vertex_buffer = VertexBuffer::builder()
+            .vertex_count(3)
+            .buffer_count(1)
+            .attribute(VertexAttribute::POSITION, 0, AttributeType::FLOAT3, 0, 12)
+            .build(&mut engine)
+            .unwrap();
+        let index_buffer = IndexBuffer::builder()
+            .index_count(3)
+            .ty(IndexType::USHORT)
+            .build(&mut engine)
+            .unwrap();
+        let material = Material::new(
+            &mut engine,
+            include_bytes!("../examples/texture_unlit.filamat"),
+        )
+        .unwrap();
+        let primitive = Primitive {
+            primitive_type: PrimitiveType::TRIANGLES,
+            vertex_buffer,
+            index_buffer,
+            material: material.default_instance(),
+        };
+        let bounding_box = BoundingBox::new(Float3::new(0.0, 0.0, 0.0), Float3::new(1.0, 1.0, 1.0));
+        let mut renderable = Renderable::new(vec![primitive.clone()], bounding_box);
+
+        let report = sync.sync_renderables(vec![(1, &renderable)]);
+        assert!(report.is_ok());
+        assert_eq!(report.changed, 1);
+        let entity = sync.entity(1).unwrap();
+        assert!(scene.contains(entity));
+        assert_eq!(scene.renderable_count(), 1);
+        assert_eq!(sync.sync_renderables(vec![(1, &renderable)]).changed, 0);
+
+        // Updated in place, then rebuilt with another primitive
+        renderable.cast_shadows = true;
+        assert_eq!(sync.sync_renderables(vec![(1, &renderable)]).changed, 1);
+        renderable.primitives.push(primitive);
+        assert_eq!(sync.sync_renderables(vec![(1, &renderable)]).changed, 1);
+        assert_eq!(sync.entity(1), Some(entity));
+        assert!(engine
+            .renderable_manager()
+            .unwrap()
+            .get_instance(entity)
+            .is_some());
+
+        assert_eq!(sync.sync_renderables(Vec::new()).changed, 1);
+        assert!(!scene.contains(entity));
+        assert!(sync.is_empty());
+    }
+
+    #[test]
+    fn sync_errors() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let scene = Scene::new(&mut engine).unwrap();
+        let mut sync = SceneSync::<u32>::new(&mut engine, &scene);
+        let good = Transform::default();
+        let bad = Transform::new(Mat4f::from_cols_array(&[2.0; 16]));
+
+        // Applying `bad` always fails, which holds back neither the other entities nor removals
+        let sync_transforms = |sync: &mut SceneSync<u32>, transforms: Vec<(u32, &Transform)>| {
+            sync.sync_components(
+                transforms,
+                |synced| &mut synced.transform,
+                |_, _, transform| match transform {
+                    Some(transform) if *transform == bad => {
+                        Err(SyncError::Engine(EngineError::CreationFailed))
+                    }
+                    _ => Ok(()),
+                },
+            )
+        };
+
+        let report = sync_transforms(&mut sync, vec![(1, &bad), (2, &good), (3, &good)]);
+        assert_eq!(report.changed, 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, 1);
+
+        let report = sync_transforms(&mut sync, vec![(1, &bad), (2, &good)]);
+        assert_eq!(report.changed, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert!(sync.entity(2).is_some());
+        assert!(sync.entity(3).is_none());
+    }
+}
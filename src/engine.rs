@@ -32,6 +32,16 @@ impl Engine {
         crate::transform::TransformManager::new(self)
     }
 
+    pub fn renderable_manager(
+        &self,
+    ) -> Result<crate::renderable::RenderableManager<'_>, EngineError> {
+        crate::renderable::RenderableManager::new(self)
+    }
+
+    pub fn light_manager(&self) -> Result<crate::light::LightManager<'_>, EngineError> {
+        crate::light::LightManager::new(self)
+    }
+
     pub fn new(backend: crate::Backend) -> Result<Self, EngineError> {
         let ptr = unsafe {
             ffi::filament_Engine::create(backend, std::ptr::null_mut(), std::ptr::null_mut())
@@ -54,6 +64,20 @@ impl Engine {
     pub fn backend(&self) -> crate::Backend {
         unsafe { self.as_raw_ref().getBackend() }
     }
+
+    /// Destroys the renderable, light and transform components of `entity`. The entity itself
+    /// stays alive, as does its camera component which belongs to its `Camera`.
+    pub fn destroy_entity_components(&mut self, entity: crate::Entity) {
+        if let Ok(mut renderable_manager) = self.renderable_manager() {
+            renderable_manager.destroy(entity);
+        }
+        if let Ok(mut light_manager) = self.light_manager() {
+            light_manager.destroy(entity);
+        }
+        if let Ok(mut transform_manager) = self.transform_manager() {
+            transform_manager.remove(entity);
+        }
+    }
 }
 impl Drop for Engine {
     fn drop(&mut self) {
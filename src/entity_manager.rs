@@ -1,5 +1,13 @@
 use filament_sys::ffi;
-use std::convert::TryInto;
+use std::{
+    cell::RefCell,
+    convert::TryInto,
+    os::raw::c_void,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex, Once},
+};
+
+use crate::{engine::Engine, Entity};
 
 pub struct EntityManager<'a> {
     inner: *mut ffi::utils_EntityManager,
@@ -23,6 +31,38 @@ impl<'a> EntityManager<'a> {
         }
     }
 
+    /// Whether `entity` was created and not destroyed since. Entities are recycled, so this is
+    /// false for a destroyed entity even once its index is reused.
+    #[inline]
+    pub fn is_alive(&self, entity: crate::Entity) -> bool {
+        unsafe { ffi::helpers_entity_manager_isAlive(self.inner, entity) }
+    }
+
+    /// Registers `listener` to be notified of destroyed entities, until the returned registration
+    /// is dropped.
+    ///
+    /// Filament may still call a listener it was asked to unregister from another thread, so it
+    /// is only given a single listener, registered on first use for the life of the process. That
+    /// listener calls the ones registered here, which are freed once their registration drops.
+    pub fn register_listener<L>(&self, listener: L) -> EntityListenerRegistration
+    where
+        L: EntityListener + 'static,
+    {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| unsafe {
+            let listener =
+                ffi::helpers_entity_listener_create(Some(entities_destroyed), std::ptr::null_mut());
+            ffi::utils_EntityManager_registerListener(self.inner, listener);
+        });
+
+        let slot: Arc<ListenerSlot> = Arc::new(Mutex::new(Some(Box::new(listener))));
+        LISTENERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(slot.clone());
+        EntityListenerRegistration { slot }
+    }
+
     pub fn create(&self) -> crate::Entity {
         let mut buffer: [crate::Entity; 1] = [crate::Entity::default(); 1];
 
@@ -56,3 +96,326 @@ impl<'a> EntityManager<'a> {
         }
     }
 }
+
+/// Notified by the `EntityManager` when entities are destroyed, on the thread destroying them.
+///
+/// Entities destroyed from within `on_entities_destroyed` are not reported to the same listener
+/// again, and a panic in it is caught and logged rather than unwinding into Filament.
+pub trait EntityListener: Send {
+    fn on_entities_destroyed(&mut self, entities: &[Entity]);
+}
+impl<F> EntityListener for F
+where
+    F: FnMut(&[Entity]) + Send,
+{
+    #[inline]
+    fn on_entities_destroyed(&mut self, entities: &[Entity]) {
+        self(entities)
+    }
+}
+
+type ListenerSlot = Mutex<Option<Box<dyn EntityListener>>>;
+
+// The listeners registered through `EntityManager::register_listener`
+static LISTENERS: Mutex<Vec<Arc<ListenerSlot>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    // The listeners being called on this thread, to skip them when they destroy entities themselves
+    static CALLING: RefCell<Vec<*const ListenerSlot>> = RefCell::new(Vec::new());
+}
+
+/// Keeps an `EntityListener` registered, unregistering it on drop.
+pub struct EntityListenerRegistration {
+    slot: Arc<ListenerSlot>,
+}
+impl Drop for EntityListenerRegistration {
+    fn drop(&mut self) {
+        log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+        LISTENERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|slot| !Arc::ptr_eq(slot, &self.slot));
+
+        // Another thread may already be calling the listener, so it's dropped once that call
+        // returns. When dropped from the listener itself, it's freed with the last reference.
+        let slot = Arc::as_ptr(&self.slot);
+        let calling = CALLING.with(|calling| calling.borrow().contains(&slot));
+        if !calling {
+            let listener = self
+                .slot
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .take();
+            drop(listener);
+        }
+    }
+}
+
+unsafe extern "C" fn entities_destroyed(
+    n: ffi::size_t,
+    entities: *const Entity,
+    _user: *mut c_void,
+) {
+    let listeners = LISTENERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if listeners.is_empty() {
+        return;
+    }
+
+    let entities = if n == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(entities, n.try_into().unwrap())
+    };
+    for slot in &listeners {
+        call_listener(slot, entities);
+    }
+}
+
+fn call_listener(slot: &ListenerSlot, entities: &[Entity]) {
+    let slot = slot as *const ListenerSlot;
+    let reentrant = CALLING.with(|calling| {
+        let mut calling = calling.borrow_mut();
+        if calling.contains(&slot) {
+            true
+        } else {
+            calling.push(slot);
+            false
+        }
+    });
+    if reentrant {
+        return;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // The slot is kept alive by the caller's reference
+        if let Some(listener) = unsafe { &*slot }
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+        {
+            listener.on_entities_destroyed(entities);
+        }
+    }));
+    CALLING.with(|calling| calling.borrow_mut().retain(|calling| *calling != slot));
+    if result.is_err() {
+        log::error!("An entity listener panicked, the panic was not propagated to Filament");
+    }
+}
+
+// Entities whose owner was dropped while a `Camera` still used them, destroyed along with it
+static CAMERA_ENTITIES: Mutex<Vec<Entity>> = Mutex::new(Vec::new());
+
+/// Destroys `entities` and their renderable, light and transform components. An entity still
+/// having a camera component is only destroyed once its `Camera` is dropped.
+pub(crate) fn destroy_entities(engine: &mut Engine, entities: &[Entity]) {
+    let mut destroyed = Vec::with_capacity(entities.len());
+    {
+        let mut camera_entities = CAMERA_ENTITIES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for &entity in entities {
+            engine.destroy_entity_components(entity);
+            if unsafe { engine.as_raw_mut().getCameraComponent(entity) }.is_null() {
+                destroyed.push(entity);
+            } else {
+                camera_entities.push(entity);
+            }
+        }
+    }
+    // Listeners may destroy entities themselves, so the lock is released first
+    EntityManager::get().destroy(&destroyed);
+}
+
+/// Destroys the camera component of `camera`, and its entity if its owner was already dropped.
+pub(crate) unsafe fn destroy_camera(engine: &mut Engine, camera: *mut ffi::filament_Camera) {
+    let entity = (*camera).getEntity();
+    let owner_dropped = {
+        let mut camera_entities = CAMERA_ENTITIES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        engine.as_raw_mut().destroy17(camera);
+        match camera_entities
+            .iter()
+            .position(|owned| owned.mIdentity == entity.mIdentity)
+        {
+            Some(index) => {
+                camera_entities.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    };
+    if owner_dropped {
+        EntityManager::get().destroy(&[entity]);
+    }
+}
+
+/// An entity which is destroyed on drop, along with its renderable, light and transform
+/// components.
+///
+/// A `Camera` created for the entity keeps it alive, it is then destroyed along with the camera.
+pub struct OwnedEntity {
+    engine: Engine,
+    entity: Entity,
+}
+impl OwnedEntity {
+    pub fn new(engine: &mut Engine) -> Self {
+        Self::from_entity(engine, EntityManager::get().create())
+    }
+
+    /// Takes ownership of `entity`, which will be destroyed when this is dropped.
+    pub fn from_entity(engine: &mut Engine, entity: Entity) -> Self {
+        Self {
+            engine: engine.clone(),
+            entity,
+        }
+    }
+
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Gives up ownership of the entity without destroying it.
+    pub fn release(self) -> Entity {
+        let entity = self.entity;
+        // The engine handle still needs to be released
+        let _ = unsafe { std::ptr::read(&self.engine) };
+        std::mem::forget(self);
+        entity
+    }
+}
+impl Drop for OwnedEntity {
+    fn drop(&mut self) {
+        log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+        if EntityManager::get().is_alive(self.entity) {
+            destroy_entities(&mut self.engine, &[self.entity]);
+        }
+    }
+}
+impl From<&OwnedEntity> for Entity {
+    #[inline]
+    fn from(owned: &OwnedEntity) -> Self {
+        owned.entity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntityManager, OwnedEntity};
+    use crate::{camera::Projection, engine::Engine, Backend, Camera, Entity};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn owned_entity() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let entity_manager = EntityManager::get();
+        assert!(!entity_manager.is_alive(Entity::default()));
+
+        let owned = OwnedEntity::new(&mut engine);
+        let entity = owned.entity();
+        assert!(entity_manager.is_alive(entity));
+        drop(owned);
+        assert!(!entity_manager.is_alive(entity));
+
+        let released = OwnedEntity::new(&mut engine).release();
+        assert!(entity_manager.is_alive(released));
+        entity_manager.destroy(&[released]);
+        assert!(!entity_manager.is_alive(released));
+    }
+
+    #[test]
+    fn owned_entity_outlived_by_camera() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let entity_manager = EntityManager::get();
+
+        let owned = OwnedEntity::new(&mut engine);
+        let entity = owned.entity();
+        let mut camera = Camera::new(&mut engine, entity).unwrap();
+        drop(owned);
+        assert!(entity_manager.is_alive(entity));
+        camera.set_projection(Projection::ORTHOGRAPHIC, -1.0, 1.0, -1.0, 1.0, 0.1, 10.0);
+        engine.flush_and_wait();
+
+        drop(camera);
+        assert!(!entity_manager.is_alive(entity));
+    }
+
+    #[test]
+    fn entity_listener() {
+        let entity_manager = EntityManager::get();
+        let destroyed = Arc::new(Mutex::new(Vec::<Entity>::new()));
+
+        let registration = entity_manager.register_listener({
+            let destroyed = destroyed.clone();
+            move |entities: &[Entity]| destroyed.lock().unwrap().extend_from_slice(entities)
+        });
+        let entities = entity_manager.create_vec(2);
+        entity_manager.destroy(&entities);
+        // Other tests may destroy entities concurrently
+        assert!(entities
+            .iter()
+            .all(|entity| destroyed.lock().unwrap().contains(entity)));
+
+        drop(registration);
+        let entity = entity_manager.create();
+        entity_manager.destroy(&[entity]);
+        assert!(!destroyed.lock().unwrap().contains(&entity));
+    }
+
+    #[test]
+    fn entity_listener_reentrant_panicking() {
+        let entity_manager = EntityManager::get();
+        let calls = Arc::new(Mutex::new(0));
+        let entity = entity_manager.create();
+
+        // Listeners see every destroyed entity, including those of tests running alongside
+        let _registration = entity_manager.register_listener({
+            let calls = calls.clone();
+            move |entities: &[Entity]| {
+                if !entities.contains(&entity) {
+                    return;
+                }
+                *calls.lock().unwrap() += 1;
+                // Neither reported to this listener again nor deadlocking
+                let entity_manager = EntityManager::get();
+                entity_manager.destroy(&[entity_manager.create()]);
+                panic!("listener panic");
+            }
+        });
+        entity_manager.destroy(&[entity]);
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn entity_listener_dropped_by_itself() {
+        let entity_manager = EntityManager::get();
+        let entity = entity_manager.create();
+        let registration = Arc::new(Mutex::new(None));
+
+        let dropped = Arc::new(());
+        *registration.lock().unwrap() = Some(entity_manager.register_listener({
+            let registration = registration.clone();
+            let dropped = dropped.clone();
+            move |entities: &[Entity]| {
+                let _ = &dropped;
+                if entities.contains(&entity) {
+                    registration.lock().unwrap().take();
+                }
+            }
+        }));
+        entity_manager.destroy(&[entity]);
+        assert!(registration.lock().unwrap().is_none());
+        // The listener is freed once its last call returns, which may be on another thread
+        for _ in 0..1000 {
+            if Arc::strong_count(&dropped) == 1 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(Arc::strong_count(&dropped), 1);
+    }
+}
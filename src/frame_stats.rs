@@ -0,0 +1,208 @@
+//! CPU timings and counters of the frames submitted by a `Renderer`.
+use std::{collections::VecDeque, time::Duration};
+
+/// What happened in one frame.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FrameStats {
+    /// The index of the frame since the history was last reset, counting skipped frames.
+    pub frame: u64,
+    /// CPU time spent in `begin_frame`.
+    pub begin: Duration,
+    /// CPU time spent in all `render` calls of the frame.
+    pub render: Duration,
+    /// CPU time spent in `end_frame`.
+    pub end: Duration,
+    /// The number of views rendered.
+    pub views: usize,
+    /// The number of renderables in the scenes of the views rendered, visible or not.
+    pub scene_renderables: usize,
+    /// Whether Filament skipped the frame because the GPU was falling behind. Skipped frames only
+    /// have a `begin` time.
+    pub skipped: bool,
+    /// The frame interval requested with `FrameRateOptions` when the frame was submitted, in
+    /// display refresh periods.
+    pub frame_interval: u8,
+}
+impl FrameStats {
+    /// The total CPU time spent submitting the frame.
+    #[inline]
+    pub fn cpu_time(&self) -> Duration {
+        self.begin + self.render + self.end
+    }
+}
+
+/// The stats of the most recent frames of a `Renderer`, along with totals since it was last reset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameHistory {
+    frames: VecDeque<FrameStats>,
+    capacity: usize,
+    frame_count: u64,
+    skipped_count: u64,
+}
+impl Default for FrameHistory {
+    fn default() -> Self {
+        Self::new(FrameHistory::DEFAULT_CAPACITY)
+    }
+}
+impl FrameHistory {
+    pub const DEFAULT_CAPACITY: usize = 120;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+            frame_count: 0,
+            skipped_count: 0,
+        }
+    }
+
+    /// Records a frame, dropping the oldest one if the history is full.
+    pub fn push(&mut self, mut stats: FrameStats) {
+        stats.frame = self.frame_count;
+        self.frame_count += 1;
+        if stats.skipped {
+            self.skipped_count += 1;
+        }
+
+        if self.capacity == 0 {
+            return;
+        }
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(stats);
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+        self.capacity = capacity;
+    }
+
+    /// Forgets every frame, including the totals.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.frame_count = 0;
+        self.skipped_count = 0;
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The frames in the history, oldest first.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &FrameStats> {
+        self.frames.iter()
+    }
+
+    #[inline]
+    pub fn latest(&self) -> Option<&FrameStats> {
+        self.frames.back()
+    }
+
+    /// The number of frames recorded since the history was last reset, including frames no longer
+    /// in the history.
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// The number of frames skipped since the history was last reset.
+    #[inline]
+    pub fn skipped_count(&self) -> u64 {
+        self.skipped_count
+    }
+
+    /// The fraction of frames in the history which were skipped.
+    pub fn skip_ratio(&self) -> f32 {
+        if self.frames.is_empty() {
+            0.0
+        } else {
+            let skipped = self.frames.iter().filter(|stats| stats.skipped).count();
+            skipped as f32 / self.frames.len() as f32
+        }
+    }
+
+    /// The average CPU time of the rendered frames in the history.
+    pub fn average_cpu_time(&self) -> Duration {
+        let (count, total) = self
+            .rendered()
+            .fold((0u32, Duration::default()), |(count, total), stats| {
+                (count + 1, total + stats.cpu_time())
+            });
+        total.checked_div(count).unwrap_or_default()
+    }
+
+    /// The highest CPU time of the rendered frames in the history.
+    pub fn max_cpu_time(&self) -> Duration {
+        self.rendered()
+            .map(FrameStats::cpu_time)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn rendered(&self) -> impl Iterator<Item = &FrameStats> {
+        self.frames.iter().filter(|stats| !stats.skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameHistory, FrameStats};
+    use std::time::Duration;
+
+    fn frame(millis: u64, skipped: bool) -> FrameStats {
+        FrameStats {
+            begin: Duration::from_millis(millis),
+            render: Duration::from_millis(millis),
+            skipped,
+            ..FrameStats::default()
+        }
+    }
+
+    #[test]
+    fn rolling_history() {
+        let mut history = FrameHistory::new(3);
+        assert_eq!(history.average_cpu_time(), Duration::default());
+        assert_eq!(history.skip_ratio(), 0.0);
+
+        history.push(frame(1, false));
+        history.push(frame(100, true));
+        history.push(frame(2, false));
+        history.push(frame(3, false));
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.frame_count(), 4);
+        assert_eq!(history.skipped_count(), 1);
+        assert_eq!(history.latest().unwrap().frame, 3);
+        assert_eq!(
+            history.iter().map(|stats| stats.frame).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // Skipped frames only count towards the skip ratio
+        assert_eq!(history.skip_ratio(), 1.0 / 3.0);
+        assert_eq!(history.average_cpu_time(), Duration::from_millis(5));
+        assert_eq!(history.max_cpu_time(), Duration::from_millis(6));
+
+        history.set_capacity(1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.skip_ratio(), 0.0);
+
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.frame_count(), 0);
+    }
+}
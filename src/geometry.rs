@@ -0,0 +1,580 @@
+use filament_sys::ffi;
+use std::{convert::TryInto, f32::consts::PI};
+use thiserror::Error;
+
+use crate::{
+    buffers::{
+        AttributeType, IndexBuffer, IndexType, QuatTangentContext, VertexAttribute, VertexBuffer,
+    },
+    engine::{Engine, EngineError},
+    renderable::BoundingBox,
+};
+
+#[derive(Error, Debug)]
+pub enum GeometryError {
+    #[error("Index {index} is out of range for {vertex_count} vertices")]
+    IndexOutOfRange { index: u32, vertex_count: usize },
+}
+
+/// CPU side mesh data for a procedurally generated primitive.
+///
+/// Tangents are stored as a float4 where `w` is the handedness of the bitangent, and are converted
+/// into Filament tangent quaternions on upload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Geometry {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub tangents: Vec<[f32; 4]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+impl Geometry {
+    /// A flat plane on the XZ axis facing +Y, centered on the origin.
+    pub fn plane(width: f32, depth: f32, segments_x: usize, segments_z: usize) -> Self {
+        let mut geometry = Self::default();
+        geometry.push_grid(
+            [-width / 2.0, 0.0, depth / 2.0],
+            [width, 0.0, 0.0],
+            [0.0, 0.0, -depth],
+            [0.0, 1.0, 0.0],
+            segments_x.max(1),
+            segments_z.max(1),
+        );
+        geometry
+    }
+
+    /// An axis aligned cube centered on the origin, with each face split into `segments` x `segments` quads.
+    pub fn cube(size: f32, segments: usize) -> Self {
+        let h = size / 2.0;
+        let segments = segments.max(1);
+
+        // (normal, u axis, v axis), where normal = u x v
+        #[rustfmt::skip]
+        let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+            ([ 1.0,  0.0,  0.0], [ 0.0, 0.0, -1.0], [0.0, 1.0,  0.0]),
+            ([-1.0,  0.0,  0.0], [ 0.0, 0.0,  1.0], [0.0, 1.0,  0.0]),
+            ([ 0.0,  1.0,  0.0], [ 1.0, 0.0,  0.0], [0.0, 0.0, -1.0]),
+            ([ 0.0, -1.0,  0.0], [ 1.0, 0.0,  0.0], [0.0, 0.0,  1.0]),
+            ([ 0.0,  0.0,  1.0], [ 1.0, 0.0,  0.0], [0.0, 1.0,  0.0]),
+            ([ 0.0,  0.0, -1.0], [-1.0, 0.0,  0.0], [0.0, 1.0,  0.0]),
+        ];
+
+        let mut geometry = Self::default();
+        for (normal, u, v) in faces.iter() {
+            let origin = [
+                (normal[0] - u[0] - v[0]) * h,
+                (normal[1] - u[1] - v[1]) * h,
+                (normal[2] - u[2] - v[2]) * h,
+            ];
+            geometry.push_grid(
+                origin,
+                scale(*u, size),
+                scale(*v, size),
+                *normal,
+                segments,
+                segments,
+            );
+        }
+        geometry
+    }
+
+    /// A UV sphere centered on the origin. `segments` is the number of longitudinal slices and
+    /// `rings` the number of latitudinal bands.
+    pub fn sphere(radius: f32, segments: usize, rings: usize) -> Self {
+        let segments = segments.max(3);
+        let rings = rings.max(2);
+
+        let mut geometry = Self::default();
+        for j in 0..=rings {
+            let theta = j as f32 / rings as f32 * PI;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            for i in 0..=segments {
+                let phi = i as f32 / segments as f32 * 2.0 * PI;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+
+                let normal = [sin_theta * sin_phi, cos_theta, sin_theta * cos_phi];
+                geometry.positions.push(scale(normal, radius));
+                geometry.normals.push(normal);
+                geometry.tangents.push([cos_phi, 0.0, -sin_phi, 1.0]);
+                geometry
+                    .uvs
+                    .push([i as f32 / segments as f32, 1.0 - j as f32 / rings as f32]);
+            }
+        }
+
+        let stride = (segments + 1) as u32;
+        for j in 0..rings as u32 {
+            for i in 0..segments as u32 {
+                let a = j * stride + i;
+                let b = a + stride;
+                let c = b + 1;
+                let d = a + 1;
+
+                // Skip the degenerate triangles that collapse onto the poles
+                if j != 0 {
+                    geometry.indices.extend_from_slice(&[a, b, d]);
+                }
+                if j != rings as u32 - 1 {
+                    geometry.indices.extend_from_slice(&[d, b, c]);
+                }
+            }
+        }
+        geometry
+    }
+
+    /// A capped cylinder centered on the origin, with its axis along Y.
+    pub fn cylinder(radius: f32, height: f32, segments: usize, height_segments: usize) -> Self {
+        let segments = segments.max(3);
+        let height_segments = height_segments.max(1);
+        let half_height = height / 2.0;
+
+        let mut geometry = Self::default();
+
+        // Sides
+        for j in 0..=height_segments {
+            let y = -half_height + j as f32 / height_segments as f32 * height;
+            for i in 0..=segments {
+                let phi = i as f32 / segments as f32 * 2.0 * PI;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+
+                geometry
+                    .positions
+                    .push([radius * sin_phi, y, radius * cos_phi]);
+                geometry.normals.push([sin_phi, 0.0, cos_phi]);
+                geometry.tangents.push([cos_phi, 0.0, -sin_phi, 1.0]);
+                geometry.uvs.push([
+                    i as f32 / segments as f32,
+                    j as f32 / height_segments as f32,
+                ]);
+            }
+        }
+
+        let stride = (segments + 1) as u32;
+        for j in 0..height_segments as u32 {
+            for i in 0..segments as u32 {
+                let a = j * stride + i;
+                let b = a + 1;
+                let c = b + stride;
+                let d = a + stride;
+                geometry.indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+
+        // Caps
+        for &(y, sign) in [(half_height, 1.0), (-half_height, -1.0)].iter() {
+            let center = geometry.positions.len() as u32;
+            geometry.positions.push([0.0, y, 0.0]);
+            geometry.normals.push([0.0, sign, 0.0]);
+            geometry.tangents.push([1.0, 0.0, 0.0, 1.0]);
+            geometry.uvs.push([0.5, 0.5]);
+
+            for i in 0..=segments {
+                let phi = i as f32 / segments as f32 * 2.0 * PI;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+
+                geometry
+                    .positions
+                    .push([radius * sin_phi, y, radius * cos_phi]);
+                geometry.normals.push([0.0, sign, 0.0]);
+                geometry.tangents.push([1.0, 0.0, 0.0, 1.0]);
+                geometry
+                    .uvs
+                    .push([0.5 + 0.5 * sin_phi, 0.5 - 0.5 * sign * cos_phi]);
+            }
+
+            for i in 0..segments as u32 {
+                let current = center + 1 + i;
+                let next = current + 1;
+                if sign > 0.0 {
+                    geometry.indices.extend_from_slice(&[center, current, next]);
+                } else {
+                    geometry.indices.extend_from_slice(&[center, next, current]);
+                }
+            }
+        }
+
+        geometry
+    }
+
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    #[inline]
+    pub fn index_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    #[inline]
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_positions(&self.positions)
+    }
+
+    /// Encodes the normals and tangents of this geometry into Filament tangent quaternions.
+    pub fn tangent_quaternions(&self) -> Vec<[f32; 4]> {
+        let mut quats = vec![[0.0f32; 4]; self.vertex_count()];
+
+        let ctx = QuatTangentContext {
+            quatType: ffi::filament_VertexBuffer_QuatType_FLOAT4,
+            quatCount: quats.len().try_into().unwrap(),
+            outBuffer: quats.as_mut_ptr() as *mut _,
+            outStride: std::mem::size_of::<[f32; 4]>().try_into().unwrap(),
+            normals: self.normals.as_ptr() as *const _,
+            normalsStride: std::mem::size_of::<[f32; 3]>().try_into().unwrap(),
+            tangents: self.tangents.as_ptr() as *const _,
+            tangentsStride: std::mem::size_of::<[f32; 4]>().try_into().unwrap(),
+        };
+        VertexBuffer::populate_tangent_quaternions(&ctx);
+
+        quats
+    }
+
+    /// Uploads this geometry into a new vertex and index buffer pair, ready for `RenderableBuilder::geometry`.
+    ///
+    /// Positions, tangent quaternions and UVs are placed in buffers 0, 1 and 2 respectively. 16-bit
+    /// indices are used whenever the vertex count allows it.
+    pub fn build(&self, engine: &mut Engine) -> Result<(VertexBuffer, IndexBuffer), EngineError> {
+        let mut vertex_buffer = VertexBuffer::builder()
+            .vertex_count(self.vertex_count())
+            .buffer_count(3)
+            .attribute(VertexAttribute::POSITION, 0, AttributeType::FLOAT3, 0, 12)
+            .attribute(VertexAttribute::TANGENTS, 1, AttributeType::FLOAT4, 0, 16)
+            .attribute(VertexAttribute::UV0, 2, AttributeType::FLOAT2, 0, 8)
+            .build(engine)?;
+        vertex_buffer.write_at(0, 0, self.positions.clone());
+        vertex_buffer.write_at(1, 0, self.tangent_quaternions());
+        vertex_buffer.write_at(2, 0, self.uvs.clone());
+
+        let index_buffer = build_index_buffer(engine, &self.indices, self.vertex_count())?;
+
+        Ok((vertex_buffer, index_buffer))
+    }
+
+    /// Computes smooth normals from the triangles, each face contributing in proportion to its area.
+    pub fn generate_normals(&mut self) -> Result<(), GeometryError> {
+        self.check_indices()?;
+
+        let mut normals = vec![[0.0f32; 3]; self.vertex_count()];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ];
+            let p = &self.positions;
+            let face = cross(sub(p[b], p[a]), sub(p[c], p[a]));
+            for i in [a, b, c].iter() {
+                normals[*i] = add(normals[*i], face);
+            }
+        }
+
+        self.normals = normals
+            .into_iter()
+            .map(|n| normalize_or(n, [0.0, 1.0, 0.0]))
+            .collect();
+        Ok(())
+    }
+
+    /// Computes tangents following the UVs, orthogonal to the normals and with the handedness of
+    /// the bitangent in `w`. Normals are generated first if missing.
+    ///
+    /// Vertices without usable UVs get an arbitrary tangent, which is fine as long as they are not
+    /// normal mapped.
+    pub fn generate_tangents(&mut self) -> Result<(), GeometryError> {
+        self.check_indices()?;
+
+        let count = self.vertex_count();
+        if self.normals.len() != count {
+            self.generate_normals()?;
+        }
+
+        let mut tangents = vec![[0.0f32; 3]; count];
+        let mut bitangents = vec![[0.0f32; 3]; count];
+        if self.uvs.len() == count {
+            for triangle in self.indices.chunks_exact(3) {
+                let [a, b, c] = [
+                    triangle[0] as usize,
+                    triangle[1] as usize,
+                    triangle[2] as usize,
+                ];
+                let (p, uv) = (&self.positions, &self.uvs);
+                let (e1, e2) = (sub(p[b], p[a]), sub(p[c], p[a]));
+                let (du1, dv1) = (uv[b][0] - uv[a][0], uv[b][1] - uv[a][1]);
+                let (du2, dv2) = (uv[c][0] - uv[a][0], uv[c][1] - uv[a][1]);
+
+                let det = du1 * dv2 - du2 * dv1;
+                if det.abs() <= f32::EPSILON {
+                    continue;
+                }
+                let tangent = scale(sub(scale(e1, dv2), scale(e2, dv1)), 1.0 / det);
+                let bitangent = scale(sub(scale(e2, du1), scale(e1, du2)), 1.0 / det);
+                for i in [a, b, c].iter() {
+                    tangents[*i] = add(tangents[*i], tangent);
+                    bitangents[*i] = add(bitangents[*i], bitangent);
+                }
+            }
+        }
+
+        self.tangents = self
+            .normals
+            .iter()
+            .zip(tangents.iter().zip(bitangents.iter()))
+            .map(|(n, (t, b))| {
+                let t = sub(*t, scale(*n, dot(*n, *t)));
+                let t = normalize_or(t, perpendicular(*n));
+                let w = if dot(cross(*n, t), *b) < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                [t[0], t[1], t[2], w]
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn check_indices(&self) -> Result<(), GeometryError> {
+        let vertex_count = self.vertex_count();
+        match self
+            .indices
+            .iter()
+            .find(|index| **index as usize >= vertex_count)
+        {
+            Some(index) => Err(GeometryError::IndexOutOfRange {
+                index: *index,
+                vertex_count,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn push_grid(
+        &mut self,
+        origin: [f32; 3],
+        u: [f32; 3],
+        v: [f32; 3],
+        normal: [f32; 3],
+        segments_u: usize,
+        segments_v: usize,
+    ) {
+        let base = self.positions.len() as u32;
+        let tangent = normalize(u);
+
+        for j in 0..=segments_v {
+            let fv = j as f32 / segments_v as f32;
+            for i in 0..=segments_u {
+                let fu = i as f32 / segments_u as f32;
+                self.positions.push([
+                    origin[0] + u[0] * fu + v[0] * fv,
+                    origin[1] + u[1] * fu + v[1] * fv,
+                    origin[2] + u[2] * fu + v[2] * fv,
+                ]);
+                self.normals.push(normal);
+                self.tangents
+                    .push([tangent[0], tangent[1], tangent[2], 1.0]);
+                self.uvs.push([fu, fv]);
+            }
+        }
+
+        let stride = (segments_u + 1) as u32;
+        for j in 0..segments_v as u32 {
+            for i in 0..segments_u as u32 {
+                let a = base + j * stride + i;
+                let b = a + 1;
+                let c = b + stride;
+                let d = a + stride;
+                self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+    }
+}
+
+/// Uploads `indices` into a new index buffer, using 16-bit indices whenever `vertex_count` allows it.
+pub(crate) fn build_index_buffer(
+    engine: &mut Engine,
+    indices: &[u32],
+    vertex_count: usize,
+) -> Result<IndexBuffer, EngineError> {
+    let index_type = if vertex_count <= (u16::MAX as usize) + 1 {
+        IndexType::USHORT
+    } else {
+        IndexType::UINT
+    };
+    let mut index_buffer = IndexBuffer::builder()
+        .index_count(indices.len())
+        .ty(index_type)
+        .build(engine)?;
+    match index_type {
+        IndexType::USHORT => {
+            index_buffer.write(0, indices.iter().map(|i| *i as u16).collect::<Vec<_>>())
+        }
+        IndexType::UINT => index_buffer.write(0, indices.to_vec()),
+    }
+    Ok(index_buffer)
+}
+
+#[inline]
+fn scale(v: [f32; 3], s: f32) -> [f32; 3] {
+    [v[0] * s, v[1] * s, v[2] * s]
+}
+
+#[inline]
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    scale(v, 1.0 / len)
+}
+
+#[inline]
+fn normalize_or(v: [f32; 3], fallback: [f32; 3]) -> [f32; 3] {
+    if dot(v, v) > f32::EPSILON * f32::EPSILON {
+        normalize(v)
+    } else {
+        fallback
+    }
+}
+
+#[inline]
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+#[inline]
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+#[inline]
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Any unit vector perpendicular to the unit vector `n`.
+fn perpendicular(n: [f32; 3]) -> [f32; 3] {
+    let axis = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(cross(n, axis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cross, dot, sub, Geometry, GeometryError};
+
+    /// Every triangle must wind counter-clockwise when seen from the side its normals point to.
+    fn assert_valid(geometry: &Geometry) {
+        let count = geometry.vertex_count();
+        assert_eq!(geometry.normals.len(), count);
+        assert_eq!(geometry.tangents.len(), count);
+        assert_eq!(geometry.uvs.len(), count);
+        assert_eq!(geometry.index_count() % 3, 0);
+
+        for triangle in geometry.indices.chunks(3) {
+            let [a, b, c] = [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ];
+            assert!(a < count && b < count && c < count);
+
+            let p = &geometry.positions;
+            let face = cross(sub(p[b], p[a]), sub(p[c], p[a]));
+            assert!(dot(face, geometry.normals[a]) > 0.0);
+        }
+
+        for (n, t) in geometry.normals.iter().zip(geometry.tangents.iter()) {
+            assert!((dot(*n, *n) - 1.0).abs() < 1e-5);
+            assert!(dot(*n, [t[0], t[1], t[2]]).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn plane_counts() {
+        let plane = Geometry::plane(2.0, 2.0, 4, 3);
+        assert_eq!(plane.vertex_count(), 5 * 4);
+        assert_eq!(plane.index_count(), 4 * 3 * 6);
+        assert_valid(&plane);
+    }
+
+    #[test]
+    fn cube_counts() {
+        let cube = Geometry::cube(1.0, 2);
+        assert_eq!(cube.vertex_count(), 6 * 3 * 3);
+        assert_eq!(cube.index_count(), 6 * 2 * 2 * 6);
+        assert_valid(&cube);
+
+        for position in cube.positions.iter() {
+            assert!(position.iter().all(|v| v.abs() <= 0.5 + 1e-6));
+        }
+    }
+
+    #[test]
+    fn sphere_counts() {
+        let sphere = Geometry::sphere(2.0, 16, 8);
+        assert_eq!(sphere.vertex_count(), 17 * 9);
+        assert_eq!(sphere.index_count(), (16 * 8 * 2 - 16 * 2) * 3);
+        assert_valid(&sphere);
+
+        for position in sphere.positions.iter() {
+            assert!((dot(*position, *position).sqrt() - 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn generated_normals_and_tangents() {
+        let cube = Geometry::cube(1.0, 2);
+        let mut generated = Geometry {
+            positions: cube.positions.clone(),
+            uvs: cube.uvs.clone(),
+            indices: cube.indices.clone(),
+            ..Geometry::default()
+        };
+        generated.generate_tangents().unwrap();
+        assert_valid(&generated);
+
+        for (a, b) in generated.normals.iter().zip(cube.normals.iter()) {
+            assert!(dot(*a, *b) > 1.0 - 1e-5);
+        }
+        for (a, b) in generated.tangents.iter().zip(cube.tangents.iter()) {
+            assert!(dot([a[0], a[1], a[2]], [b[0], b[1], b[2]]) > 1.0 - 1e-5);
+            assert_eq!(a[3], b[3]);
+        }
+    }
+
+    #[test]
+    fn generation_checks_indices() {
+        let mut triangle = Geometry {
+            positions: vec![[0.0; 3]; 3],
+            indices: vec![0, 1, 3],
+            ..Geometry::default()
+        };
+        assert!(matches!(
+            triangle.generate_normals(),
+            Err(GeometryError::IndexOutOfRange {
+                index: 3,
+                vertex_count: 3
+            })
+        ));
+        assert!(triangle.generate_tangents().is_err());
+        assert!(triangle.normals.is_empty() && triangle.tangents.is_empty());
+    }
+
+    #[test]
+    fn cylinder_counts() {
+        let cylinder = Geometry::cylinder(1.0, 2.0, 8, 2);
+        assert_eq!(cylinder.vertex_count(), 9 * 3 + 2 * (1 + 9));
+        assert_eq!(cylinder.index_count(), (8 * 2 * 6) + 2 * 8 * 3);
+        assert_valid(&cylinder);
+    }
+}
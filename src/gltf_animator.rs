@@ -0,0 +1,533 @@
+//! Playback of the animations of a `GltfAsset`.
+use gltf::animation::{util::ReadOutputs, Interpolation, Property};
+use std::collections::HashMap;
+
+use crate::gltf_loader::{GltfAsset, GltfError};
+use filament_sys::Mat4f;
+
+/// Samples the animations of an asset and applies them to its transforms, morph weights and skins.
+///
+/// The animations are read from the document when the animator is created, so it should be kept
+/// around rather than created every frame.
+pub struct GltfAnimator<'a> {
+    asset: &'a GltfAsset,
+    animations: Vec<Animation>,
+    // The translation, rotation and scale of every node as found in the document
+    rest_poses: Vec<([f32; 3], [f32; 4], [f32; 3])>,
+}
+impl<'a> GltfAnimator<'a> {
+    pub fn new(asset: &'a GltfAsset) -> Self {
+        let document = asset.document();
+        let buffers = asset.buffers();
+
+        let animations = document
+            .animations()
+            .map(|animation| {
+                let channels = animation
+                    .channels()
+                    .filter_map(|channel| {
+                        let reader = channel.reader(|buffer| Some(&*buffers[buffer.index()]));
+                        let times = reader.read_inputs()?.collect::<Vec<_>>();
+                        let (width, values) = match reader.read_outputs()? {
+                            ReadOutputs::Translations(values) => {
+                                (Some(3), values.flatten().collect())
+                            }
+                            ReadOutputs::Rotations(values) => {
+                                (Some(4), values.into_f32().flatten().collect())
+                            }
+                            ReadOutputs::Scales(values) => (Some(3), values.flatten().collect()),
+                            ReadOutputs::MorphTargetWeights(values) => {
+                                (None, values.into_f32().collect())
+                            }
+                        };
+                        Some(Channel {
+                            node: channel.target().node().index(),
+                            property: channel.target().property(),
+                            sampler: Sampler::new(
+                                times,
+                                values,
+                                width,
+                                channel.sampler().interpolation(),
+                                channel.target().property() == Property::Rotation,
+                            )?,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                Animation {
+                    name: animation.name().map(String::from),
+                    duration: channels
+                        .iter()
+                        .filter_map(|channel| channel.sampler.times.last())
+                        .fold(0.0, |duration, time| f32::max(duration, *time)),
+                    channels,
+                }
+            })
+            .collect();
+
+        Self {
+            asset,
+            animations,
+            rest_poses: document
+                .nodes()
+                .map(|node| node.transform().decomposed())
+                .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn animation_count(&self) -> usize {
+        self.animations.len()
+    }
+
+    /// The name of the animation, `None` if it is unnamed or does not exist.
+    pub fn animation_name(&self, index: usize) -> Option<&str> {
+        self.animations.get(index)?.name.as_deref()
+    }
+
+    /// The time of the last keyframe of the animation, in seconds.
+    pub fn animation_duration(&self, index: usize) -> Option<f32> {
+        self.animations
+            .get(index)
+            .map(|animation| animation.duration)
+    }
+
+    /// Poses the asset at `time` seconds into the animation at `index`. Times outside of the
+    /// animation are clamped to its first or last keyframe.
+    ///
+    /// Nodes are set to their rest pose with the animated properties replaced, so several
+    /// animations cannot be blended together. Skinned meshes are updated to follow their joints.
+    pub fn apply_animation(&self, index: usize, time: f32) -> Result<(), GltfError> {
+        let animation = self
+            .animations
+            .get(index)
+            .ok_or(GltfError::AnimationOutOfRange {
+                index,
+                count: self.animations.len(),
+            })?;
+        let engine = self.asset.engine();
+
+        let mut poses = HashMap::new();
+        let mut renderable_manager = engine.renderable_manager()?;
+        for channel in animation.channels.iter() {
+            let value = channel.sampler.sample(time);
+            // Only nodes with animated transforms are posed, morph weights leave them alone
+            let rest = self.rest_poses[channel.node];
+            match channel.property {
+                Property::Translation => {
+                    poses.entry(channel.node).or_insert(rest).0 = [value[0], value[1], value[2]];
+                }
+                Property::Rotation => {
+                    poses.entry(channel.node).or_insert(rest).1 =
+                        normalize([value[0], value[1], value[2], value[3]]);
+                }
+                Property::Scale => {
+                    poses.entry(channel.node).or_insert(rest).2 = [value[0], value[1], value[2]];
+                }
+                Property::MorphTargetWeights => {
+                    let (entity, targets) = match self.asset.morph_targets(channel.node) {
+                        Some(morph) => morph,
+                        None => continue,
+                    };
+                    let mut weights = value;
+                    weights.resize(targets.count(), 0.0);
+                    if let Some(instance) = renderable_manager.get_instance(entity) {
+                        renderable_manager.set_morph_weights(instance, &targets, &weights)?;
+                    }
+                }
+            }
+        }
+
+        let mut transform_manager = engine.transform_manager()?;
+        for (node, (translation, rotation, scale)) in poses.into_iter() {
+            let instance = self
+                .asset
+                .node_entity(node)
+                .and_then(|entity| transform_manager.get(entity));
+            if let Some(instance) = instance {
+                transform_manager.set_transform(instance, trs_matrix(translation, rotation, scale));
+            }
+        }
+
+        self.update_skins()
+    }
+
+    /// Moves every node back to the transform it has in the document.
+    pub fn reset_pose(&self) -> Result<(), GltfError> {
+        let mut transform_manager = self.asset.engine().transform_manager()?;
+        for (node, (translation, rotation, scale)) in self.rest_poses.iter().enumerate() {
+            let instance = self
+                .asset
+                .node_entity(node)
+                .and_then(|entity| transform_manager.get(entity));
+            if let Some(instance) = instance {
+                transform_manager
+                    .set_transform(instance, trs_matrix(*translation, *rotation, *scale));
+            }
+        }
+
+        self.update_skins()
+    }
+
+    /// Updates skinned renderables from the current transforms of their joints, see
+    /// `GltfAsset::update_bone_matrices`.
+    pub fn update_bone_matrices(&self) -> Result<(), GltfError> {
+        self.asset.update_bone_matrices()
+    }
+
+    fn update_skins(&self) -> Result<(), GltfError> {
+        if self.asset.has_skins() {
+            self.asset.update_bone_matrices()?;
+        }
+        Ok(())
+    }
+}
+
+struct Animation {
+    name: Option<String>,
+    duration: f32,
+    channels: Vec<Channel>,
+}
+
+struct Channel {
+    node: usize,
+    property: Property,
+    sampler: Sampler,
+}
+
+/// Keyframes of a single channel, with `width` floats per value. Cubic spline samplers store an
+/// in-tangent, the value and an out-tangent for each keyframe.
+struct Sampler {
+    times: Vec<f32>,
+    values: Vec<f32>,
+    width: usize,
+    interpolation: Interpolation,
+    // Rotations are linearly interpolated along the arc between the quaternions
+    spherical: bool,
+}
+impl Sampler {
+    /// Checks the outputs hold a value per keyframe, or an in-tangent, value and out-tangent for
+    /// cubic splines. Without a `width`, as for morph weights, it is derived from the outputs.
+    fn new(
+        times: Vec<f32>,
+        values: Vec<f32>,
+        width: Option<usize>,
+        interpolation: Interpolation,
+        spherical: bool,
+    ) -> Option<Self> {
+        let outputs = match interpolation {
+            Interpolation::CubicSpline => times.len() * 3,
+            _ => times.len(),
+        };
+        let width = match width {
+            Some(width) => width,
+            None if outputs == 0 => 0,
+            None => values.len() / outputs,
+        };
+        if values.len() != outputs * width {
+            return None;
+        }
+
+        Some(Self {
+            times,
+            values,
+            width,
+            interpolation,
+            spherical,
+        })
+    }
+
+    fn value(&self, keyframe: usize) -> &[f32] {
+        let keyframe = match self.interpolation {
+            Interpolation::CubicSpline => keyframe * 3 + 1,
+            _ => keyframe,
+        };
+        &self.values[keyframe * self.width..(keyframe + 1) * self.width]
+    }
+
+    fn sample(&self, time: f32) -> Vec<f32> {
+        let last = match self.times.len() {
+            0 => return vec![0.0; self.width],
+            count => count - 1,
+        };
+        if last == 0 || time <= self.times[0] {
+            return self.value(0).to_vec();
+        }
+        if time >= self.times[last] {
+            return self.value(last).to_vec();
+        }
+
+        // The keyframe starting the interval containing `time`
+        let keyframe = self.times.iter().rposition(|t| *t <= time).unwrap_or(0);
+        let delta = self.times[keyframe + 1] - self.times[keyframe];
+        let s = (time - self.times[keyframe]) / delta;
+        let (from, to) = (self.value(keyframe), self.value(keyframe + 1));
+
+        match self.interpolation {
+            Interpolation::Step => from.to_vec(),
+            Interpolation::Linear if self.spherical => slerp(from, to, s),
+            Interpolation::Linear => from
+                .iter()
+                .zip(to.iter())
+                .map(|(a, b)| a + (b - a) * s)
+                .collect(),
+            Interpolation::CubicSpline => {
+                let width = self.width;
+                let out_tangent = &self.values[(keyframe * 3 + 2) * width..][..width];
+                let in_tangent = &self.values[(keyframe + 1) * 3 * width..][..width];
+                let (s2, s3) = (s * s, s * s * s);
+                (0..width)
+                    .map(|i| {
+                        (2.0 * s3 - 3.0 * s2 + 1.0) * from[i]
+                            + (s3 - 2.0 * s2 + s) * delta * out_tangent[i]
+                            + (-2.0 * s3 + 3.0 * s2) * to[i]
+                            + (s3 - s2) * delta * in_tangent[i]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn slerp(from: &[f32], to: &[f32], s: f32) -> Vec<f32> {
+    let mut cos = from.iter().zip(to.iter()).map(|(a, b)| a * b).sum::<f32>();
+    // Take the shortest path
+    let sign = if cos < 0.0 {
+        cos = -cos;
+        -1.0
+    } else {
+        1.0
+    };
+    let (a, b) = if cos > 0.9995 {
+        (1.0 - s, s)
+    } else {
+        let angle = cos.acos();
+        let sin = angle.sin();
+        (((1.0 - s) * angle).sin() / sin, (s * angle).sin() / sin)
+    };
+    let q = [
+        a * from[0] + b * sign * to[0],
+        a * from[1] + b * sign * to[1],
+        a * from[2] + b * sign * to[2],
+        a * from[3] + b * sign * to[3],
+    ];
+    normalize(q).to_vec()
+}
+
+fn normalize(q: [f32; 4]) -> [f32; 4] {
+    let len = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    if len > std::f32::EPSILON {
+        [q[0] / len, q[1] / len, q[2] / len, q[3] / len]
+    } else {
+        [0.0, 0.0, 0.0, 1.0]
+    }
+}
+
+/// Composes a translation, an `[x, y, z, w]` rotation quaternion and a scale, as glTF does.
+fn trs_matrix(translation: [f32; 3], rotation: [f32; 4], scale: [f32; 3]) -> Mat4f {
+    let [x, y, z, w] = rotation;
+    let (x2, y2, z2) = (x + x, y + y, z + z);
+    let (xx, yy, zz) = (x * x2, y * y2, z * z2);
+    let (xy, xz, yz) = (x * y2, x * z2, y * z2);
+    let (wx, wy, wz) = (w * x2, w * y2, w * z2);
+
+    Mat4f::from_cols([
+        [
+            (1.0 - (yy + zz)) * scale[0],
+            (xy + wz) * scale[0],
+            (xz - wy) * scale[0],
+            0.0,
+        ],
+        [
+            (xy - wz) * scale[1],
+            (1.0 - (xx + zz)) * scale[1],
+            (yz + wx) * scale[1],
+            0.0,
+        ],
+        [
+            (xz + wy) * scale[2],
+            (yz - wx) * scale[2],
+            (1.0 - (xx + yy)) * scale[2],
+            0.0,
+        ],
+        [translation[0], translation[1], translation[2], 1.0],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{trs_matrix, Sampler};
+    use crate::{
+        engine::Engine,
+        gltf_loader::{GltfError, GltfLoader},
+        material::Material,
+        Backend,
+    };
+    use filament_sys::Mat4f;
+    use gltf::animation::Interpolation;
+
+    // A node resting at (0, 3, 0), moved from the origin to (2, 0, 0) over a second
+    const SLIDE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "name": "slider", "translation": [0.0, 3.0, 0.0] }],
+        "animations": [{
+            "name": "slide",
+            "channels": [{ "sampler": 0, "target": { "node": 0, "path": "translation" } }],
+            "samplers": [{ "input": 0, "output": 1 }]
+        }],
+        "buffers": [{
+            "byteLength": 32,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAgD8AAAAAAAAAAAAAAAAAAABAAAAAAAAAAAA="
+        }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 8 },
+            { "buffer": 0, "byteOffset": 8, "byteLength": 24 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 2, "type": "SCALAR",
+              "min": [0.0], "max": [1.0] },
+            { "bufferView": 1, "componentType": 5126, "count": 2, "type": "VEC3" }
+        ]
+    }"#;
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn sampler_interpolation() {
+        let mut sampler = Sampler {
+            times: vec![0.0, 1.0, 3.0],
+            values: vec![0.0, 0.0, 0.0, 2.0, 4.0, 6.0, 4.0, 8.0, 12.0],
+            width: 3,
+            interpolation: Interpolation::Linear,
+            spherical: false,
+        };
+        assert_close(&sampler.sample(-1.0), &[0.0, 0.0, 0.0]);
+        assert_close(&sampler.sample(0.5), &[1.0, 2.0, 3.0]);
+        assert_close(&sampler.sample(2.0), &[3.0, 6.0, 9.0]);
+        assert_close(&sampler.sample(5.0), &[4.0, 8.0, 12.0]);
+
+        sampler.interpolation = Interpolation::Step;
+        assert_close(&sampler.sample(0.9), &[0.0, 0.0, 0.0]);
+        assert_close(&sampler.sample(1.5), &[2.0, 4.0, 6.0]);
+
+        // Flat tangents ease in and out, reaching the midpoint halfway
+        let sampler = Sampler {
+            times: vec![0.0, 1.0],
+            values: vec![0.0, 0.0, 0.0, 0.0, 10.0, 0.0],
+            width: 1,
+            interpolation: Interpolation::CubicSpline,
+            spherical: false,
+        };
+        assert_close(&sampler.sample(0.5), &[5.0]);
+        assert!(sampler.sample(0.25)[0] < 2.5);
+    }
+
+    #[test]
+    fn rotation_slerp() {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let sampler = Sampler {
+            times: vec![0.0, 1.0],
+            // The identity, then a half turn around Y
+            values: vec![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0],
+            width: 4,
+            interpolation: Interpolation::Linear,
+            spherical: true,
+        };
+        assert_close(&sampler.sample(0.5), &[0.0, half, 0.0, half]);
+    }
+
+    #[test]
+    fn cubic_spline_weights() {
+        // Two targets, with an in-tangent, value and out-tangent per keyframe
+        #[rustfmt::skip]
+        let values = vec![
+            0.0, 0.0, 1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0,
+        ];
+        let sampler = Sampler::new(
+            vec![0.0, 1.0],
+            values,
+            None,
+            Interpolation::CubicSpline,
+            false,
+        )
+        .unwrap();
+        assert_eq!(sampler.width, 2);
+        assert_close(&sampler.sample(0.5), &[0.5, 0.5]);
+        assert_close(&sampler.sample(2.0), &[0.0, 1.0]);
+
+        // A value missing from the outputs
+        let values = vec![0.0; 5];
+        assert!(Sampler::new(
+            vec![0.0, 1.0],
+            values,
+            Some(3),
+            Interpolation::Linear,
+            false
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn trs_composition() {
+        assert_eq!(
+            trs_matrix([0.0; 3], [0.0, 0.0, 0.0, 1.0], [1.0; 3]),
+            Mat4f::IDENTITY
+        );
+
+        // A quarter turn around Z maps X onto Y
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let matrix: [[f32; 4]; 4] =
+            trs_matrix([1.0, 2.0, 3.0], [0.0, 0.0, half, half], [2.0; 3]).into();
+        assert_close(&matrix[0], &[0.0, 2.0, 0.0, 0.0]);
+        assert_close(&matrix[1], &[-2.0, 0.0, 0.0, 0.0]);
+        assert_close(&matrix[3], &[1.0, 2.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    fn animation_playback() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let material = Material::new(
+            &mut engine,
+            include_bytes!("../examples/texture_unlit.filamat"),
+        )
+        .unwrap();
+        let loader = GltfLoader::new(&mut engine, &material);
+        let asset = loader.load_from_memory(SLIDE.as_bytes()).unwrap();
+        let animator = asset.animator();
+
+        assert_eq!(animator.animation_count(), 1);
+        assert_eq!(animator.animation_name(0), Some("slide"));
+        assert_eq!(animator.animation_duration(0), Some(1.0));
+        assert_eq!(animator.animation_name(1), None);
+        assert_eq!(animator.animation_duration(1), None);
+        assert!(matches!(
+            animator.apply_animation(1, 0.0),
+            Err(GltfError::AnimationOutOfRange { index: 1, count: 1 })
+        ));
+
+        let translation = || {
+            let transform_manager = engine.transform_manager().unwrap();
+            let instance = transform_manager
+                .get(asset.node_by_name("slider").unwrap())
+                .unwrap();
+            let matrix: [[f32; 4]; 4] = transform_manager.get_transform(instance).unwrap().into();
+            matrix[3]
+        };
+
+        animator.apply_animation(0, 0.5).unwrap();
+        assert_close(&translation(), &[1.0, 0.0, 0.0, 1.0]);
+        animator.apply_animation(0, 2.0).unwrap();
+        assert_close(&translation(), &[2.0, 0.0, 0.0, 1.0]);
+
+        animator.reset_pose().unwrap();
+        assert_close(&translation(), &[0.0, 3.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,1202 @@
+//! Loading of glTF 2.0 and GLB files through the `gltf` crate.
+//!
+//! Materials are created from ubershader packages following the parameter names of Filament's
+//! gltfio (`baseColorFactor`, `baseColorMap`, `metallicFactor`, ...). Parameters the ubershader does
+//! not declare are skipped, so simpler materials can be used as well.
+use gltf::{
+    buffer, image,
+    khr_lights_punctual::Kind,
+    material::AlphaMode,
+    mesh::Mode,
+    texture::{MagFilter, MinFilter, WrappingMode},
+    Document,
+};
+use std::{collections::HashMap, f32::consts::PI, path::Path};
+use thiserror::Error;
+
+use crate::{
+    buffers::{AttributeType, IndexBuffer, VertexAttribute, VertexBuffer},
+    camera::{Camera, FovDirection, Projection},
+    engine::{Engine, EngineError},
+    entity_manager::{self, EntityManager},
+    geometry::{build_index_buffer, Geometry, GeometryError},
+    gltf_animator::GltfAnimator,
+    light::{LightBuildError, LightBuilder, LightType},
+    material::{Material, MaterialInstance, MaterialParameter},
+    renderable::{
+        BoundingBox, MorphError, MorphTargets, PrimitiveType, RenderableBuildError,
+        RenderableBuilder, MAX_MORPH_TARGETS,
+    },
+    scene::Scene,
+    texture::{
+        InternalFormat, PixelDataFormat, PixelDataType, SamplerMagFilter, SamplerMinFilter,
+        SamplerType, SamplerWrapMode, Texture, TextureError, TextureRegion, TextureSampler,
+        TextureSamplerBuilder,
+    },
+    Entity,
+};
+use filament_sys::Mat4f;
+
+#[derive(Error, Debug)]
+pub enum GltfError {
+    #[error("Failed to import glTF: {0}")]
+    Gltf(#[from] gltf::Error),
+    #[error("Failed to create a Filament object: {0}")]
+    Engine(#[from] EngineError),
+    #[error("Failed to upload texture: {0}")]
+    Texture(#[from] TextureError),
+    #[error("Failed to build renderable: {0}")]
+    Renderable(#[from] RenderableBuildError),
+    #[error("Failed to build light: {0}")]
+    Light(#[from] LightBuildError),
+    #[error("Failed to set up morph targets: {0}")]
+    Morph(#[from] MorphError),
+    #[error("Failed to generate normals or tangents: {0}")]
+    Geometry(#[from] GeometryError),
+    #[error("Image {0} has format {1:?}, which is not supported")]
+    UnsupportedImageFormat(usize, image::Format),
+    #[error("Animation {index} does not exist, the asset has {count}")]
+    AnimationOutOfRange { index: usize, count: usize },
+    #[error("Primitive {primitive} of mesh {mesh} has no positions")]
+    MissingPositions { mesh: usize, primitive: usize },
+    #[error(
+        "Primitive {primitive} of mesh {mesh} refers to vertex {index}, but has only {vertex_count}"
+    )]
+    IndexOutOfRange {
+        mesh: usize,
+        primitive: usize,
+        index: u32,
+        vertex_count: usize,
+    },
+}
+
+/// Creates `GltfAsset`s, using the given ubershaders for their materials.
+pub struct GltfLoader {
+    engine: Engine,
+    opaque: Material,
+    masked: Option<Material>,
+    blend: Option<Material>,
+}
+impl GltfLoader {
+    /// Creates a loader using `ubershader` for every material. Use `masked_material` and
+    /// `blend_material` to provide dedicated materials for the other alpha modes.
+    pub fn new(engine: &mut Engine, ubershader: &Material) -> Self {
+        Self {
+            engine: engine.clone(),
+            opaque: ubershader.clone(),
+            masked: None,
+            blend: None,
+        }
+    }
+
+    /// Sets the material used for glTF materials with the `MASK` alpha mode.
+    pub fn masked_material(mut self, material: &Material) -> Self {
+        self.masked = Some(material.clone());
+        self
+    }
+
+    /// Sets the material used for glTF materials with the `BLEND` alpha mode.
+    pub fn blend_material(mut self, material: &Material) -> Self {
+        self.blend = Some(material.clone());
+        self
+    }
+
+    /// Imports the `.gltf` or `.glb` file at `path`, along with the buffers and images it references.
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<GltfAsset, GltfError> {
+        let (document, buffers, images) = gltf::import(path)?;
+        self.instantiate(document, buffers, images)
+    }
+
+    /// Imports an in-memory `.gltf` or `.glb` file. External buffers and images are not supported.
+    pub fn load_from_memory(&self, bytes: &[u8]) -> Result<GltfAsset, GltfError> {
+        let (document, buffers, images) = gltf::import_slice(bytes)?;
+        self.instantiate(document, buffers, images)
+    }
+
+    fn instantiate(
+        &self,
+        document: Document,
+        buffers: Vec<buffer::Data>,
+        images: Vec<image::Data>,
+    ) -> Result<GltfAsset, GltfError> {
+        let entities = EntityManager::get().create_vec(document.nodes().len() + 1);
+        let mut asset = GltfAsset {
+            engine: self.engine.clone(),
+            document,
+            buffers,
+            entities,
+            instances: Instances::default(),
+        };
+
+        // Anything created before an error is released by the asset's drop
+        let GltfAsset {
+            engine,
+            document,
+            buffers,
+            entities,
+            instances,
+        } = &mut asset;
+        Instantiator {
+            engine,
+            loader: self,
+            document,
+            buffers,
+            images: &images,
+            entities,
+            instances,
+        }
+        .run()?;
+
+        Ok(asset)
+    }
+
+    fn material(&self, alpha_mode: AlphaMode) -> &Material {
+        match alpha_mode {
+            AlphaMode::Opaque => &self.opaque,
+            AlphaMode::Mask => self.masked.as_ref().unwrap_or(&self.opaque),
+            AlphaMode::Blend => self.blend.as_ref().unwrap_or(&self.opaque),
+        }
+    }
+}
+
+/// The Filament objects created for a glTF document. Every entity, component, buffer, texture
+/// and material instance is destroyed when the asset is dropped.
+///
+/// Each node is instantiated as its own entity with a transform, parented to a root entity.
+pub struct GltfAsset {
+    engine: Engine,
+    document: Document,
+    buffers: Vec<buffer::Data>,
+    // The root first, followed by one entity per node
+    entities: Vec<Entity>,
+    instances: Instances,
+}
+impl Drop for GltfAsset {
+    fn drop(&mut self) {
+        log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+        self.instances.cameras.clear();
+        entity_manager::destroy_entities(&mut self.engine, &self.entities);
+    }
+}
+impl GltfAsset {
+    /// The entity every parentless node is attached to, which can be used to move the whole asset.
+    #[inline]
+    pub fn root(&self) -> Entity {
+        self.entities[0]
+    }
+
+    /// Every entity of the asset, the root first followed by the nodes in document order.
+    #[inline]
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// The entity created for the node at `index` in the document.
+    #[inline]
+    pub fn node_entity(&self, index: usize) -> Option<Entity> {
+        self.entities.get(index + 1).copied()
+    }
+
+    /// The entity of the first node called `name`.
+    pub fn node_by_name(&self, name: &str) -> Option<Entity> {
+        self.document
+            .nodes()
+            .find(|node| node.name() == Some(name))
+            .and_then(|node| self.node_entity(node.index()))
+    }
+
+    /// The entities holding a renderable component, one per node referencing a mesh.
+    #[inline]
+    pub fn renderables(&self) -> &[Entity] {
+        &self.instances.renderables
+    }
+
+    #[inline]
+    pub fn lights(&self) -> &[Entity] {
+        &self.instances.lights
+    }
+
+    #[inline]
+    pub fn cameras(&self) -> &[Camera] {
+        &self.instances.cameras
+    }
+
+    #[inline]
+    pub fn material_instances(&self) -> &[MaterialInstance] {
+        &self.instances.material_instances
+    }
+
+    #[inline]
+    pub fn textures(&self) -> &[Texture] {
+        &self.instances.textures
+    }
+
+    #[inline]
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Adds the renderables and lights of the asset to `scene`.
+    pub fn add_to_scene(&self, scene: &mut Scene) {
+        scene.extend_from_slice(&self.instances.renderables);
+        scene.extend_from_slice(&self.instances.lights);
+    }
+
+    pub fn remove_from_scene(&self, scene: &mut Scene) {
+        for entity in self
+            .instances
+            .renderables
+            .iter()
+            .chain(self.instances.lights.iter())
+        {
+            scene.remove(*entity);
+        }
+    }
+
+    /// Reads the animations of the document, to play them on this asset.
+    pub fn animator(&self) -> GltfAnimator<'_> {
+        GltfAnimator::new(self)
+    }
+
+    /// Recomputes the bones of skinned renderables from the current transforms of their joints.
+    /// Call this after moving joints, for example when playing an animation.
+    pub fn update_bone_matrices(&self) -> Result<(), GltfError> {
+        let transform_manager = self.engine.transform_manager()?;
+        let mut renderable_manager = self.engine.renderable_manager()?;
+        let world_transform = |entity: Entity| {
+            transform_manager
+                .get(entity)
+                .and_then(|instance| transform_manager.get_world_transform(instance))
+                .unwrap_or(Mat4f::IDENTITY)
+        };
+
+        for skin in self.instances.skins.iter() {
+            let joints = skin
+                .joints
+                .iter()
+                .zip(skin.inverse_bind_matrices.iter())
+                .map(|(joint, inverse_bind)| world_transform(*joint) * *inverse_bind)
+                .collect::<Vec<_>>();
+
+            for target in skin.targets.iter() {
+                let instance = match renderable_manager.get_instance(*target) {
+                    Some(instance) => instance,
+                    None => continue,
+                };
+                let inverse_world = world_transform(*target)
+                    .inverse()
+                    .unwrap_or(Mat4f::IDENTITY);
+                let bones = joints
+                    .iter()
+                    .map(|joint| inverse_world * *joint)
+                    .collect::<Vec<_>>();
+                // The renderable was built with a bone per joint of the skin
+                renderable_manager.set_bone_matrices(instance, &bones, 0);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GltfAsset {
+    pub(crate) fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    pub(crate) fn buffers(&self) -> &[buffer::Data] {
+        &self.buffers
+    }
+
+    pub(crate) fn has_skins(&self) -> bool {
+        !self.instances.skins.is_empty()
+    }
+
+    /// The renderable entity and morph targets created for the mesh of the node at `node`.
+    pub(crate) fn morph_targets(&self, node: usize) -> Option<(Entity, MorphTargets)> {
+        self.instances
+            .morphs
+            .iter()
+            .find(|morph| morph.node == node)
+            .map(|morph| (morph.entity, morph.targets))
+    }
+}
+
+#[derive(Default)]
+struct Instances {
+    renderables: Vec<Entity>,
+    lights: Vec<Entity>,
+    cameras: Vec<Camera>,
+    skins: Vec<SkinInstance>,
+    morphs: Vec<MorphInstance>,
+    // Dropped in declaration order, instances before the textures they reference
+    material_instances: Vec<MaterialInstance>,
+    textures: Vec<Texture>,
+    vertex_buffers: Vec<VertexBuffer>,
+    index_buffers: Vec<IndexBuffer>,
+}
+
+struct SkinInstance {
+    joints: Vec<Entity>,
+    inverse_bind_matrices: Vec<Mat4f>,
+    // The renderables deformed by this skin
+    targets: Vec<Entity>,
+}
+
+struct MorphInstance {
+    node: usize,
+    entity: Entity,
+    targets: MorphTargets,
+}
+
+struct Instantiator<'a> {
+    engine: &'a mut Engine,
+    loader: &'a GltfLoader,
+    document: &'a Document,
+    buffers: &'a [buffer::Data],
+    images: &'a [image::Data],
+    entities: &'a [Entity],
+    instances: &'a mut Instances,
+}
+
+/// The buffers of a single primitive, waiting to be attached to a renderable.
+struct LoadedPrimitive {
+    vertex_buffer: VertexBuffer,
+    index_buffer: IndexBuffer,
+    primitive_type: PrimitiveType,
+    morph_targets: MorphTargets,
+}
+
+impl<'a> Instantiator<'a> {
+    fn run(mut self) -> Result<(), GltfError> {
+        self.create_transforms()?;
+
+        let mut materials = MaterialCache::default();
+        for node in self.document.nodes() {
+            let entity = self.entities[node.index() + 1];
+            if let Some(mesh) = node.mesh() {
+                self.create_renderable(&node, &mesh, entity, &mut materials)?;
+            }
+            if let Some(light) = node.light() {
+                self.create_light(&light, entity)?;
+            }
+            if let Some(camera) = node.camera() {
+                self.create_camera(&camera, entity)?;
+            }
+        }
+
+        let (document, buffers, entities) = (self.document, self.buffers, self.entities);
+        for skin in document.skins() {
+            let reader = skin.reader(|buffer| Some(&*buffers[buffer.index()]));
+            let inverse_bind_matrices = match reader.read_inverse_bind_matrices() {
+                Some(matrices) => matrices.map(Mat4f::from).collect(),
+                None => vec![Mat4f::IDENTITY; skin.joints().len()],
+            };
+            self.instances.skins.push(SkinInstance {
+                joints: skin
+                    .joints()
+                    .map(|joint| entities[joint.index() + 1])
+                    .collect(),
+                inverse_bind_matrices,
+                targets: document
+                    .nodes()
+                    .filter(|node| {
+                        node.mesh().is_some()
+                            && node.skin().map(|s| s.index()) == Some(skin.index())
+                    })
+                    .map(|node| entities[node.index() + 1])
+                    .collect(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn create_transforms(&mut self) -> Result<(), GltfError> {
+        let mut parents = vec![None; self.document.nodes().len()];
+        for node in self.document.nodes() {
+            for child in node.children() {
+                parents[child.index()] = Some(node.index());
+            }
+        }
+
+        let mut transform_manager = self.engine.transform_manager()?;
+        transform_manager.create(self.entities[0], None, Mat4f::IDENTITY);
+        let root = transform_manager.get(self.entities[0]);
+
+        // Parents are created before their children
+        let mut stack = self
+            .document
+            .nodes()
+            .filter(|node| parents[node.index()].is_none())
+            .map(|node| (node, root))
+            .collect::<Vec<_>>();
+        while let Some((node, parent)) = stack.pop() {
+            let entity = self.entities[node.index() + 1];
+            transform_manager.create(entity, parent, node.transform().matrix());
+            let instance = transform_manager.get(entity);
+            stack.extend(node.children().map(|child| (child, instance)));
+        }
+        Ok(())
+    }
+
+    fn create_renderable(
+        &mut self,
+        node: &gltf::Node,
+        mesh: &gltf::Mesh,
+        entity: Entity,
+        materials: &mut MaterialCache,
+    ) -> Result<(), GltfError> {
+        let skinned = node.skin().is_some();
+        let mut primitives = Vec::new();
+        let mut positions = Vec::new();
+        for primitive in mesh.primitives() {
+            let loaded = self.load_primitive(mesh, &primitive, skinned, &mut positions)?;
+            let material = materials.get(self, &primitive.material())?;
+            primitives.push((loaded, material));
+        }
+
+        let mut builder = RenderableBuilder::new(primitives.len())?
+            .bounding_box(BoundingBox::from_positions(&positions));
+        for (i, (primitive, material)) in primitives.iter().enumerate() {
+            builder = builder
+                .geometry(
+                    i,
+                    primitive.primitive_type,
+                    &primitive.vertex_buffer,
+                    &primitive.index_buffer,
+                )
+                .material(i, material);
+        }
+        if let Some(skin) = node.skin() {
+            builder = builder.skinning(skin.joints().len());
+        }
+        let morph_targets = primitives
+            .first()
+            .map(|(primitive, _)| primitive.morph_targets)
+            .filter(|targets| targets.count() > 0);
+        if let Some(targets) = morph_targets.as_ref() {
+            builder = builder.morph_targets(targets);
+        }
+        builder.build(self.engine, entity)?;
+        self.instances.renderables.push(entity);
+
+        if let Some(targets) = morph_targets {
+            let weights = node
+                .weights()
+                .or_else(|| mesh.weights())
+                .map(|weights| weights.iter().copied().take(targets.count()).collect())
+                .unwrap_or_else(|| vec![0.0; targets.count()]);
+            if weights.len() == targets.count() {
+                let mut renderable_manager = self.engine.renderable_manager()?;
+                if let Some(instance) = renderable_manager.get_instance(entity) {
+                    renderable_manager.set_morph_weights(instance, &targets, &weights)?;
+                }
+            }
+            self.instances.morphs.push(MorphInstance {
+                node: node.index(),
+                entity,
+                targets,
+            });
+        }
+
+        for (primitive, _) in primitives {
+            self.instances.vertex_buffers.push(primitive.vertex_buffer);
+            self.instances.index_buffers.push(primitive.index_buffer);
+        }
+        Ok(())
+    }
+
+    /// Uploads the vertices and indices of `primitive`, extending `positions` with its positions
+    /// for the bounding box of the renderable.
+    fn load_primitive(
+        &mut self,
+        mesh: &gltf::Mesh,
+        primitive: &gltf::Primitive,
+        skinned: bool,
+        positions: &mut Vec<[f32; 3]>,
+    ) -> Result<LoadedPrimitive, GltfError> {
+        let buffers = self.buffers;
+        let reader = primitive.reader(|buffer| Some(&*buffers[buffer.index()]));
+
+        let mut geometry = Geometry {
+            positions: reader
+                .read_positions()
+                .ok_or(GltfError::MissingPositions {
+                    mesh: mesh.index(),
+                    primitive: primitive.index(),
+                })?
+                .collect(),
+            ..Geometry::default()
+        };
+        let vertex_count = geometry.vertex_count();
+        let indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..vertex_count as u32).collect(),
+        };
+        if let Some(&index) = indices
+            .iter()
+            .find(|&&index| index as usize >= vertex_count)
+        {
+            return Err(GltfError::IndexOutOfRange {
+                mesh: mesh.index(),
+                primitive: primitive.index(),
+                index,
+                vertex_count,
+            });
+        }
+        let (primitive_type, indices) = triangulate(primitive.mode(), indices);
+
+        // Normals and tangents are only generated from triangles
+        if primitive_type == PrimitiveType::TRIANGLES {
+            geometry.indices = indices.clone();
+        }
+        geometry.uvs = match reader.read_tex_coords(0) {
+            Some(uvs) => uvs.into_f32().collect(),
+            None => vec![[0.0; 2]; vertex_count],
+        };
+        if let Some(normals) = reader.read_normals() {
+            geometry.normals = normals.collect();
+        } else {
+            geometry.generate_normals()?;
+        }
+        if let Some(tangents) = reader.read_tangents() {
+            geometry.tangents = tangents.collect();
+        } else {
+            geometry.generate_tangents()?;
+        }
+        positions.extend_from_slice(&geometry.positions);
+
+        let uv1 = reader
+            .read_tex_coords(1)
+            .map(|uvs| uvs.into_f32().collect::<Vec<_>>());
+        let colors = reader
+            .read_colors(0)
+            .map(|colors| colors.into_rgba_f32().collect::<Vec<_>>());
+        let (joints, weights) = if skinned {
+            (
+                reader
+                    .read_joints(0)
+                    .map(|joints| joints.into_u16().collect::<Vec<_>>())
+                    .unwrap_or_else(|| vec![[0; 4]; vertex_count]),
+                reader
+                    .read_weights(0)
+                    .map(|weights| weights.into_f32().collect::<Vec<_>>())
+                    .unwrap_or_else(|| vec![[1.0, 0.0, 0.0, 0.0]; vertex_count]),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        let mut targets = reader.read_morph_targets().collect::<Vec<_>>();
+        if targets.len() > MAX_MORPH_TARGETS {
+            log::warn!(
+                "Mesh {} has {} morph targets, only the first {} are used",
+                mesh.index(),
+                targets.len(),
+                MAX_MORPH_TARGETS
+            );
+            targets.truncate(MAX_MORPH_TARGETS);
+        }
+
+        // Positions, tangents and UV0 always come first, as with `Geometry::build`
+        let mut builder = VertexBuffer::builder()
+            .vertex_count(vertex_count)
+            .attribute(VertexAttribute::POSITION, 0, AttributeType::FLOAT3, 0, 12)
+            .attribute(VertexAttribute::TANGENTS, 1, AttributeType::FLOAT4, 0, 16)
+            .attribute(VertexAttribute::UV0, 2, AttributeType::FLOAT2, 0, 8);
+        let mut buffer_count = 3;
+        let uv1_buffer = uv1.as_ref().map(|_| {
+            buffer_count += 1;
+            buffer_count - 1
+        });
+        if let Some(index) = uv1_buffer {
+            builder = builder.attribute(VertexAttribute::UV1, index, AttributeType::FLOAT2, 0, 8);
+        }
+        let color_buffer = colors.as_ref().map(|_| {
+            buffer_count += 1;
+            buffer_count - 1
+        });
+        if let Some(index) = color_buffer {
+            builder =
+                builder.attribute(VertexAttribute::COLOR, index, AttributeType::FLOAT4, 0, 16);
+        }
+        let bone_buffer = if skinned {
+            buffer_count += 2;
+            builder =
+                builder
+                    .bone_indices(buffer_count - 2, 0, 8)
+                    .bone_weights(buffer_count - 1, 0, 16);
+            Some(buffer_count - 2)
+        } else {
+            None
+        };
+        let morph_targets = MorphTargets::new(targets.len(), buffer_count)?;
+        buffer_count += morph_targets.buffer_count();
+
+        let mut vertex_buffer = builder
+            .buffer_count(buffer_count)
+            .morph_targets(&morph_targets)
+            .build(self.engine)?;
+        vertex_buffer.write_at(0, 0, geometry.positions.clone());
+        vertex_buffer.write_at(1, 0, geometry.tangent_quaternions());
+        vertex_buffer.write_at(2, 0, geometry.uvs.clone());
+        if let (Some(index), Some(uv1)) = (uv1_buffer, uv1) {
+            vertex_buffer.write_at(index, 0, uv1);
+        }
+        if let (Some(index), Some(colors)) = (color_buffer, colors) {
+            vertex_buffer.write_at(index, 0, colors);
+        }
+        if let Some(index) = bone_buffer {
+            vertex_buffer.write_at(index, 0, joints);
+            vertex_buffer.write_at(index + 1, 0, weights);
+        }
+
+        // Targets hold displacements, the tangent frame is taken from the displaced normals
+        for (i, (target_positions, target_normals, _)) in targets.into_iter().enumerate() {
+            let displacements = target_positions
+                .map(|positions| positions.collect())
+                .unwrap_or_else(|| vec![[0.0; 3]; vertex_count]);
+            let mut morphed = geometry.clone();
+            if let Some(normals) = target_normals {
+                for (normal, delta) in morphed.normals.iter_mut().zip(normals) {
+                    *normal = [
+                        normal[0] + delta[0],
+                        normal[1] + delta[1],
+                        normal[2] + delta[2],
+                    ];
+                }
+            }
+            vertex_buffer.write_morph_target(
+                &morph_targets,
+                i,
+                displacements,
+                morphed.tangent_quaternions(),
+            )?;
+        }
+
+        let index_buffer = build_index_buffer(self.engine, &indices, vertex_count)?;
+
+        Ok(LoadedPrimitive {
+            vertex_buffer,
+            index_buffer,
+            primitive_type,
+            morph_targets,
+        })
+    }
+
+    fn create_light(
+        &mut self,
+        light: &gltf::khr_lights_punctual::Light,
+        entity: Entity,
+    ) -> Result<(), GltfError> {
+        // glTF uses lux for directional lights and candela otherwise, Filament wants lumens
+        let (light_type, intensity) = match light.kind() {
+            Kind::Directional => (LightType::DIRECTIONAL, light.intensity()),
+            Kind::Point => (LightType::POINT, light.intensity() * 4.0 * PI),
+            Kind::Spot { .. } => (LightType::SPOT, light.intensity() * 4.0 * PI),
+        };
+
+        let mut builder = LightBuilder::new(light_type)?
+            .color(light.color())
+            .intensity(intensity)
+            .direction([0.0, 0.0, -1.0]);
+        if let Some(range) = light.range() {
+            builder = builder.falloff(range);
+        }
+        if let Kind::Spot {
+            inner_cone_angle,
+            outer_cone_angle,
+        } = light.kind()
+        {
+            builder = builder.spot_light_cone(inner_cone_angle, outer_cone_angle);
+        }
+        builder.build(self.engine, entity)?;
+        self.instances.lights.push(entity);
+        Ok(())
+    }
+
+    fn create_camera(&mut self, camera: &gltf::Camera, entity: Entity) -> Result<(), GltfError> {
+        let mut filament_camera = Camera::new(self.engine, entity)?;
+        match camera.projection() {
+            gltf::camera::Projection::Perspective(perspective) => filament_camera
+                .set_projection_fov(
+                    perspective.yfov().to_degrees() as f64,
+                    perspective.aspect_ratio().unwrap_or(1.0) as f64,
+                    perspective.znear() as f64,
+                    perspective.zfar().unwrap_or(1000.0) as f64,
+                    FovDirection::VERTICAL,
+                ),
+            gltf::camera::Projection::Orthographic(orthographic) => {
+                let (x, y) = (orthographic.xmag() as f64, orthographic.ymag() as f64);
+                filament_camera.set_projection(
+                    Projection::ORTHOGRAPHIC,
+                    -x,
+                    x,
+                    -y,
+                    y,
+                    orthographic.znear() as f64,
+                    orthographic.zfar() as f64,
+                )
+            }
+        }
+        self.instances.cameras.push(filament_camera);
+        Ok(())
+    }
+
+    /// Uploads the image at `index`, converting it to 8 bits per channel.
+    fn create_texture(&mut self, index: usize, srgb: bool) -> Result<Texture, GltfError> {
+        let images = self.images;
+        let image = &images[index];
+        let (internal_format, format, pixels) = match image.format {
+            image::Format::R8 => (InternalFormat::R8, PixelDataFormat::R, image.pixels.clone()),
+            image::Format::R8G8 => (
+                InternalFormat::RG8,
+                PixelDataFormat::RG,
+                image.pixels.clone(),
+            ),
+            image::Format::R8G8B8 => (
+                if srgb {
+                    InternalFormat::SRGB8
+                } else {
+                    InternalFormat::RGB8
+                },
+                PixelDataFormat::RGB,
+                image.pixels.clone(),
+            ),
+            image::Format::R8G8B8A8 => (
+                if srgb {
+                    InternalFormat::SRGB8_A8
+                } else {
+                    InternalFormat::RGBA8
+                },
+                PixelDataFormat::RGBA,
+                image.pixels.clone(),
+            ),
+            image::Format::R16 => (
+                InternalFormat::R8,
+                PixelDataFormat::R,
+                to_8_bits(&image.pixels),
+            ),
+            image::Format::R16G16 => (
+                InternalFormat::RG8,
+                PixelDataFormat::RG,
+                to_8_bits(&image.pixels),
+            ),
+            image::Format::R16G16B16 => (
+                if srgb {
+                    InternalFormat::SRGB8
+                } else {
+                    InternalFormat::RGB8
+                },
+                PixelDataFormat::RGB,
+                to_8_bits(&image.pixels),
+            ),
+            image::Format::R16G16B16A16 => (
+                if srgb {
+                    InternalFormat::SRGB8_A8
+                } else {
+                    InternalFormat::RGBA8
+                },
+                PixelDataFormat::RGBA,
+                to_8_bits(&image.pixels),
+            ),
+            format => return Err(GltfError::UnsupportedImageFormat(index, format)),
+        };
+
+        // glTF texture coordinates start at the top left, like the rows of the image, so
+        // neither needs flipping
+        self.upload_texture(image.width, image.height, internal_format, format, pixels)
+    }
+
+    fn upload_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        internal_format: InternalFormat,
+        format: PixelDataFormat,
+        pixels: Vec<u8>,
+    ) -> Result<Texture, GltfError> {
+        let levels = if Texture::is_format_mipmappable(internal_format) {
+            Texture::max_level_count(width as usize, height as usize)
+        } else {
+            1
+        };
+        let mut texture = Texture::builder()?
+            .width(width as usize)
+            .height(height as usize)
+            .levels(levels)
+            .sampler(SamplerType::SAMPLER_2D)
+            .format(internal_format)
+            .build(self.engine)?;
+        texture.set_region(
+            0,
+            TextureRegion::new(0, 0, width, height),
+            pixels,
+            format,
+            PixelDataType::UBYTE,
+        )?;
+        if levels > 1 {
+            texture.generate_mipmaps()?;
+        }
+        Ok(texture)
+    }
+}
+
+/// Material instances and textures already created for the document, as indices into `Instances`.
+#[derive(Default)]
+struct MaterialCache {
+    instances: HashMap<Option<usize>, usize>,
+    textures: HashMap<(usize, bool), usize>,
+    white: Option<usize>,
+    flat_normal: Option<usize>,
+}
+impl MaterialCache {
+    fn get(
+        &mut self,
+        instantiator: &mut Instantiator,
+        material: &gltf::Material,
+    ) -> Result<MaterialInstance, GltfError> {
+        if let Some(index) = self.instances.get(&material.index()) {
+            return Ok(instantiator.instances.material_instances[*index].clone());
+        }
+
+        let loader = instantiator.loader;
+        let ubershader = loader.material(material.alpha_mode());
+        let mut instance = ubershader.create_instance()?;
+        let mut set = |name: &str, parameter: MaterialParameter| {
+            if ubershader.has_parameter(name) {
+                instance.set(name, parameter);
+            }
+        };
+
+        let pbr = material.pbr_metallic_roughness();
+        set(
+            "baseColorFactor",
+            MaterialParameter::Float4(pbr.base_color_factor().into()),
+        );
+        set("metallicFactor", pbr.metallic_factor().into());
+        set("roughnessFactor", pbr.roughness_factor().into());
+        set(
+            "emissiveFactor",
+            MaterialParameter::Float3(material.emissive_factor().into()),
+        );
+        if material.alpha_mode() == AlphaMode::Mask {
+            set(
+                "maskThreshold",
+                material.alpha_cutoff().unwrap_or(0.5).into(),
+            );
+        }
+        if let Some(normal) = material.normal_texture() {
+            set("normalScale", normal.scale().into());
+        }
+        if let Some(occlusion) = material.occlusion_texture() {
+            set("aoStrength", occlusion.strength().into());
+        }
+
+        let maps = [
+            (
+                "baseColor",
+                pbr.base_color_texture()
+                    .map(|info| (info.texture(), info.tex_coord())),
+                true,
+            ),
+            (
+                "metallicRoughness",
+                pbr.metallic_roughness_texture()
+                    .map(|info| (info.texture(), info.tex_coord())),
+                false,
+            ),
+            (
+                "normal",
+                material
+                    .normal_texture()
+                    .map(|info| (info.texture(), info.tex_coord())),
+                false,
+            ),
+            (
+                "occlusion",
+                material
+                    .occlusion_texture()
+                    .map(|info| (info.texture(), info.tex_coord())),
+                false,
+            ),
+            (
+                "emissive",
+                material
+                    .emissive_texture()
+                    .map(|info| (info.texture(), info.tex_coord())),
+                true,
+            ),
+        ];
+        for (name, map, srgb) in maps.iter() {
+            let map_name = format!("{}Map", name);
+            let index_name = format!("{}Index", name);
+            if !ubershader.has_parameter(&map_name) {
+                continue;
+            }
+
+            let (texture, sampler, uv_set) = match map {
+                Some((texture, uv_set)) => (
+                    self.texture(instantiator, texture.source().index(), *srgb)?,
+                    sampler_from_gltf(&texture.sampler()).build(),
+                    *uv_set as i32,
+                ),
+                None => (
+                    self.fallback_texture(instantiator, *name == "normal")?,
+                    TextureSampler::default(),
+                    -1,
+                ),
+            };
+            let texture = &instantiator.instances.textures[texture];
+            instance.set(&map_name, MaterialParameter::Texture(texture, &sampler));
+            if ubershader.has_parameter(&index_name) {
+                instance.set(&index_name, uv_set);
+            }
+        }
+
+        self.instances.insert(
+            material.index(),
+            instantiator.instances.material_instances.len(),
+        );
+        instantiator
+            .instances
+            .material_instances
+            .push(instance.clone());
+        Ok(instance)
+    }
+
+    fn texture(
+        &mut self,
+        instantiator: &mut Instantiator,
+        image: usize,
+        srgb: bool,
+    ) -> Result<usize, GltfError> {
+        if let Some(index) = self.textures.get(&(image, srgb)) {
+            return Ok(*index);
+        }
+        let texture = instantiator.create_texture(image, srgb)?;
+        let index = instantiator.instances.textures.len();
+        instantiator.instances.textures.push(texture);
+        self.textures.insert((image, srgb), index);
+        Ok(index)
+    }
+
+    /// A 1x1 texture bound to maps the material does not provide, white or a flat normal.
+    fn fallback_texture(
+        &mut self,
+        instantiator: &mut Instantiator,
+        normal: bool,
+    ) -> Result<usize, GltfError> {
+        let (cached, pixel) = if normal {
+            (&mut self.flat_normal, vec![128, 128, 255, 255])
+        } else {
+            (&mut self.white, vec![255, 255, 255, 255])
+        };
+        if let Some(index) = *cached {
+            return Ok(index);
+        }
+        let texture = instantiator.upload_texture(
+            1,
+            1,
+            InternalFormat::RGBA8,
+            PixelDataFormat::RGBA,
+            pixel,
+        )?;
+        let index = instantiator.instances.textures.len();
+        instantiator.instances.textures.push(texture);
+        *cached = Some(index);
+        Ok(index)
+    }
+}
+
+/// Converts strips, fans and loops into plain lists of lines or triangles.
+fn triangulate(mode: Mode, indices: Vec<u32>) -> (PrimitiveType, Vec<u32>) {
+    match mode {
+        Mode::Points => (PrimitiveType::POINTS, indices),
+        Mode::Lines => (PrimitiveType::LINES, indices),
+        Mode::LineStrip => (
+            PrimitiveType::LINES,
+            indices.windows(2).flatten().copied().collect(),
+        ),
+        Mode::LineLoop => {
+            let mut lines = indices.windows(2).flatten().copied().collect::<Vec<_>>();
+            if let (Some(first), Some(last)) = (indices.first(), indices.last()) {
+                if indices.len() > 2 {
+                    lines.extend_from_slice(&[*last, *first]);
+                }
+            }
+            (PrimitiveType::LINES, lines)
+        }
+        Mode::Triangles => (PrimitiveType::TRIANGLES, indices),
+        Mode::TriangleStrip => (
+            PrimitiveType::TRIANGLES,
+            indices
+                .windows(3)
+                .enumerate()
+                .flat_map(|(i, t)| {
+                    // Every other triangle is flipped to keep a consistent winding
+                    if i % 2 == 0 {
+                        [t[0], t[1], t[2]]
+                    } else {
+                        [t[1], t[0], t[2]]
+                    }
+                })
+                .collect(),
+        ),
+        Mode::TriangleFan => (
+            PrimitiveType::TRIANGLES,
+            indices
+                .iter()
+                .skip(1)
+                .zip(indices.iter().skip(2))
+                .flat_map(|(b, c)| [indices[0], *b, *c])
+                .collect(),
+        ),
+    }
+}
+
+fn sampler_from_gltf(sampler: &gltf::texture::Sampler) -> TextureSamplerBuilder {
+    let wrap = |mode: WrappingMode| match mode {
+        WrappingMode::ClampToEdge => SamplerWrapMode::ClampToEdge,
+        WrappingMode::MirroredRepeat => SamplerWrapMode::MirroredRepeat,
+        WrappingMode::Repeat => SamplerWrapMode::Repeat,
+    };
+
+    TextureSampler::builder()
+        .filter_mag(match sampler.mag_filter() {
+            Some(MagFilter::Nearest) => SamplerMagFilter::Nearest,
+            Some(MagFilter::Linear) | None => SamplerMagFilter::Linear,
+        })
+        .filter_min(match sampler.min_filter() {
+            Some(MinFilter::Nearest) => SamplerMinFilter::Nearest,
+            Some(MinFilter::Linear) => SamplerMinFilter::Linear,
+            Some(MinFilter::NearestMipmapNearest) => SamplerMinFilter::NearestMipmapNearest,
+            Some(MinFilter::LinearMipmapNearest) => SamplerMinFilter::LinearMipmapNearest,
+            Some(MinFilter::NearestMipmapLinear) => SamplerMinFilter::NearestMipmapLinear,
+            Some(MinFilter::LinearMipmapLinear) | None => SamplerMinFilter::LinearMipmapLinear,
+        })
+        .wrap_s(wrap(sampler.wrap_s()))
+        .wrap_t(wrap(sampler.wrap_t()))
+}
+
+/// Keeps the most significant byte of each native endian 16-bit channel.
+fn to_8_bits(pixels: &[u8]) -> Vec<u8> {
+    pixels
+        .chunks_exact(2)
+        .map(|c| (u16::from_ne_bytes([c[0], c[1]]) >> 8) as u8)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sampler_from_gltf, triangulate, GltfLoader};
+    use crate::{
+        engine::Engine,
+        material::Material,
+        renderable::PrimitiveType,
+        texture::{SamplerMagFilter, SamplerMinFilter, SamplerWrapMode},
+        Backend,
+    };
+    use gltf::mesh::Mode;
+
+    // A named triangle node with a camera child and a point light child
+    const TRIANGLE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "triangle", "mesh": 0, "children": [1, 2] },
+            { "camera": 0, "translation": [0.0, 0.0, 5.0] },
+            { "extensions": { "KHR_lights_punctual": { "light": 0 } } }
+        ],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+        "cameras": [{ "type": "perspective", "perspective": { "yfov": 0.8, "znear": 0.1 } }],
+        "extensionsUsed": ["KHR_lights_punctual"],
+        "extensions": { "KHR_lights_punctual": { "lights": [{ "type": "point", "intensity": 10.0 }] } },
+        "buffers": [{
+            "byteLength": 42,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIA"
+        }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+              "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+            { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+        ]
+    }"#;
+
+    #[test]
+    fn triangulate_modes() {
+        assert_eq!(
+            triangulate(Mode::TriangleStrip, vec![0, 1, 2, 3]),
+            (PrimitiveType::TRIANGLES, vec![0, 1, 2, 2, 1, 3])
+        );
+        assert_eq!(
+            triangulate(Mode::TriangleFan, vec![0, 1, 2, 3]),
+            (PrimitiveType::TRIANGLES, vec![0, 1, 2, 0, 2, 3])
+        );
+        assert_eq!(
+            triangulate(Mode::LineLoop, vec![0, 1, 2]),
+            (PrimitiveType::LINES, vec![0, 1, 1, 2, 2, 0])
+        );
+        assert_eq!(
+            triangulate(Mode::LineStrip, vec![0, 1, 2]),
+            (PrimitiveType::LINES, vec![0, 1, 1, 2])
+        );
+    }
+
+    #[test]
+    fn sampler_conversion() {
+        let document = gltf::Gltf::from_slice(
+            br#"{
+                "asset": { "version": "2.0" },
+                "samplers": [{ "magFilter": 9728, "minFilter": 9985, "wrapS": 33071 }, {}]
+            }"#,
+        )
+        .unwrap();
+        let samplers = document.samplers().collect::<Vec<_>>();
+
+        let sampler = sampler_from_gltf(&samplers[0]);
+        assert_eq!(sampler.filter_mag, SamplerMagFilter::Nearest);
+        assert_eq!(sampler.filter_min, SamplerMinFilter::LinearMipmapNearest);
+        assert_eq!(sampler.wrap_s, SamplerWrapMode::ClampToEdge);
+        assert_eq!(sampler.wrap_t, SamplerWrapMode::Repeat);
+
+        let sampler = sampler_from_gltf(&samplers[1]);
+        assert_eq!(sampler.filter_mag, SamplerMagFilter::Linear);
+        assert_eq!(sampler.filter_min, SamplerMinFilter::LinearMipmapLinear);
+    }
+
+    #[test]
+    fn gltf_load() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let material = Material::new(
+            &mut engine,
+            include_bytes!("../examples/texture_unlit.filamat"),
+        )
+        .unwrap();
+        let loader = GltfLoader::new(&mut engine, &material);
+        let asset = loader.load_from_memory(TRIANGLE.as_bytes()).unwrap();
+
+        assert_eq!(asset.entities().len(), 4);
+        assert_eq!(
+            asset.renderables(),
+            &[asset.node_by_name("triangle").unwrap()]
+        );
+        assert_eq!(asset.lights().len(), 1);
+        assert_eq!(asset.cameras().len(), 1);
+        assert_eq!(asset.material_instances().len(), 1);
+
+        let transform_manager = engine.transform_manager().unwrap();
+        let triangle = transform_manager
+            .get(asset.node_entity(0).unwrap())
+            .unwrap();
+        assert_eq!(
+            transform_manager.get_parent(triangle),
+            transform_manager.get(asset.root())
+        );
+    }
+}
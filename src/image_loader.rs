@@ -0,0 +1,296 @@
+//! Loading of PNG, JPEG, TGA, HDR and EXR files into textures through the `image` crate.
+use image::{DynamicImage, GenericImageView};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::{
+    engine::{Engine, EngineError},
+    texture::{
+        InternalFormat, PixelDataFormat, PixelDataType, SamplerType, Texture, TextureError,
+        TextureRegion,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum ImageLoadError {
+    #[error("Failed to decode image: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("Failed to create texture: {0}")]
+    Engine(#[from] EngineError),
+    #[error("Failed to upload texture: {0}")]
+    Texture(#[from] TextureError),
+    #[error("Texture format {0:?} is not supported by this backend")]
+    UnsupportedFormat(InternalFormat),
+}
+
+/// How the color channels of 8 and 16-bit images are encoded. Floating point images are always
+/// linear, and 16-bit sRGB images are linearized when loaded, as they are stored in half floats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// Color data, such as albedo or emissive maps
+    Srgb,
+    /// Non-color data, such as normal or roughness maps
+    Linear,
+}
+impl Default for ColorSpace {
+    fn default() -> Self {
+        Self::Srgb
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ImageLoadOptions {
+    pub color_space: ColorSpace,
+    /// Flip rows so the first row of the file ends up at the bottom, as Filament expects.
+    pub flip_y: bool,
+    /// Allocate a full mip chain and generate it on the GPU, when the format allows it.
+    pub generate_mipmaps: bool,
+}
+impl Default for ImageLoadOptions {
+    fn default() -> Self {
+        Self {
+            color_space: ColorSpace::default(),
+            flip_y: true,
+            generate_mipmaps: true,
+        }
+    }
+}
+
+/// Decodes the image file at `path` and uploads it into a new 2D texture.
+pub fn load_texture<P: AsRef<Path>>(
+    engine: &mut Engine,
+    path: P,
+    options: ImageLoadOptions,
+) -> Result<Texture, ImageLoadError> {
+    let image = image::open(path)?;
+    texture_from_image(engine, image, options)
+}
+
+/// Decodes an in-memory image file, guessing its format from its contents, and uploads it into a new 2D texture.
+pub fn load_texture_from_memory(
+    engine: &mut Engine,
+    bytes: &[u8],
+    options: ImageLoadOptions,
+) -> Result<Texture, ImageLoadError> {
+    let image = image::load_from_memory(bytes)?;
+    texture_from_image(engine, image, options)
+}
+
+/// Uploads an already decoded image into a new 2D texture.
+pub fn texture_from_image(
+    engine: &mut Engine,
+    image: DynamicImage,
+    options: ImageLoadOptions,
+) -> Result<Texture, ImageLoadError> {
+    let image = if options.flip_y { image.flipv() } else { image };
+    let (width, height) = image.dimensions();
+
+    let (pixels, internal_format, format, ty) = convert(image, options);
+    if !Texture::is_format_supported(engine, internal_format) {
+        return Err(ImageLoadError::UnsupportedFormat(internal_format));
+    }
+
+    let generate_mipmaps =
+        options.generate_mipmaps && Texture::is_format_mipmappable(internal_format);
+    let levels = if generate_mipmaps {
+        Texture::max_level_count(width as usize, height as usize)
+    } else {
+        1
+    };
+
+    let mut texture = Texture::builder()?
+        .width(width as usize)
+        .height(height as usize)
+        .levels(levels)
+        .sampler(SamplerType::SAMPLER_2D)
+        .format(internal_format)
+        .build(engine)?;
+
+    let region = TextureRegion::new(0, 0, width, height);
+    match pixels {
+        Pixels::U8(data) => texture.set_region(0, region, data, format, ty)?,
+        Pixels::F32(data) => texture.set_region(0, region, data, format, ty)?,
+    }
+
+    if levels > 1 {
+        texture.generate_mipmaps()?;
+    }
+
+    Ok(texture)
+}
+
+enum Pixels {
+    U8(Vec<u8>),
+    F32(Vec<f32>),
+}
+
+fn convert(
+    image: DynamicImage,
+    options: ImageLoadOptions,
+) -> (Pixels, InternalFormat, PixelDataFormat, PixelDataType) {
+    let srgb = options.color_space == ColorSpace::Srgb;
+
+    match image {
+        // There are no sRGB formats with fewer than three channels, so gray is expanded to color
+        image @ DynamicImage::ImageLuma8(_) | image @ DynamicImage::ImageLumaA8(_) if srgb => (
+            Pixels::U8(image.into_rgba8().into_raw()),
+            InternalFormat::SRGB8_A8,
+            PixelDataFormat::RGBA,
+            PixelDataType::UBYTE,
+        ),
+        DynamicImage::ImageLuma8(image) => (
+            Pixels::U8(image.into_raw()),
+            InternalFormat::R8,
+            PixelDataFormat::R,
+            PixelDataType::UBYTE,
+        ),
+        DynamicImage::ImageLumaA8(image) => (
+            Pixels::U8(image.into_raw()),
+            InternalFormat::RG8,
+            PixelDataFormat::RG,
+            PixelDataType::UBYTE,
+        ),
+        // SRGB8 cannot be mipmapped, so expand it to SRGB8_A8 when mipmaps are needed
+        DynamicImage::ImageRgb8(image) if srgb && options.generate_mipmaps => (
+            Pixels::U8(DynamicImage::ImageRgb8(image).into_rgba8().into_raw()),
+            InternalFormat::SRGB8_A8,
+            PixelDataFormat::RGBA,
+            PixelDataType::UBYTE,
+        ),
+        DynamicImage::ImageRgb8(image) => (
+            Pixels::U8(image.into_raw()),
+            if srgb {
+                InternalFormat::SRGB8
+            } else {
+                InternalFormat::RGB8
+            },
+            PixelDataFormat::RGB,
+            PixelDataType::UBYTE,
+        ),
+        DynamicImage::ImageRgba8(image) => (
+            Pixels::U8(image.into_raw()),
+            if srgb {
+                InternalFormat::SRGB8_A8
+            } else {
+                InternalFormat::RGBA8
+            },
+            PixelDataFormat::RGBA,
+            PixelDataType::UBYTE,
+        ),
+        // 16-bit and floating point images are kept in half floats to preserve their range
+        image @ DynamicImage::ImageRgb16(_) | image @ DynamicImage::ImageRgb32F(_) => {
+            let linearize = srgb && !is_float(&image);
+            let mut data = image.into_rgb32f().into_raw();
+            if linearize {
+                data.iter_mut().for_each(|c| *c = srgb_to_linear(*c));
+            }
+            (
+                Pixels::F32(data),
+                InternalFormat::RGB16F,
+                PixelDataFormat::RGB,
+                PixelDataType::FLOAT,
+            )
+        }
+        image => {
+            let linearize = srgb && !is_float(&image);
+            let mut data = image.into_rgba32f().into_raw();
+            if linearize {
+                for pixel in data.chunks_exact_mut(4) {
+                    pixel[..3].iter_mut().for_each(|c| *c = srgb_to_linear(*c));
+                }
+            }
+            (
+                Pixels::F32(data),
+                InternalFormat::RGBA16F,
+                PixelDataFormat::RGBA,
+                PixelDataType::FLOAT,
+            )
+        }
+    }
+}
+
+fn is_float(image: &DynamicImage) -> bool {
+    matches!(
+        image,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    )
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, ColorSpace, ImageLoadOptions, Pixels};
+    use crate::texture::{InternalFormat, PixelDataFormat, PixelDataType};
+    use image::{DynamicImage, GrayImage, ImageBuffer, Rgb, Rgb32FImage, RgbImage};
+
+    #[test]
+    fn format_selection() {
+        let rgb = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+
+        let linear = ImageLoadOptions {
+            color_space: ColorSpace::Linear,
+            generate_mipmaps: false,
+            ..Default::default()
+        };
+        let (_, internal_format, format, ty) = convert(rgb.clone(), linear);
+        assert_eq!(internal_format, InternalFormat::RGB8);
+        assert_eq!(format, PixelDataFormat::RGB);
+        assert_eq!(ty, PixelDataType::UBYTE);
+
+        let srgb = ImageLoadOptions {
+            generate_mipmaps: false,
+            ..Default::default()
+        };
+        let (_, internal_format, _, _) = convert(rgb.clone(), srgb);
+        assert_eq!(internal_format, InternalFormat::SRGB8);
+
+        let (pixels, internal_format, format, _) = convert(rgb, ImageLoadOptions::default());
+        assert_eq!(internal_format, InternalFormat::SRGB8_A8);
+        assert_eq!(format, PixelDataFormat::RGBA);
+        assert!(matches!(pixels, Pixels::U8(data) if data.len() == 2 * 2 * 4));
+
+        let hdr = DynamicImage::ImageRgb32F(Rgb32FImage::new(2, 2));
+        let (pixels, internal_format, format, ty) = convert(hdr, ImageLoadOptions::default());
+        assert_eq!(internal_format, InternalFormat::RGB16F);
+        assert_eq!(format, PixelDataFormat::RGB);
+        assert_eq!(ty, PixelDataType::FLOAT);
+        assert!(matches!(pixels, Pixels::F32(data) if data.len() == 2 * 2 * 3));
+
+        let gray = DynamicImage::ImageLuma8(GrayImage::new(2, 2));
+        let (_, internal_format, format, _) = convert(gray.clone(), srgb);
+        assert_eq!(internal_format, InternalFormat::SRGB8_A8);
+        assert_eq!(format, PixelDataFormat::RGBA);
+        let (_, internal_format, _, _) = convert(gray, linear);
+        assert_eq!(internal_format, InternalFormat::R8);
+    }
+
+    #[test]
+    fn srgb_16_bit() {
+        // sRGB mid gray is about 21.4% in linear light
+        let image = DynamicImage::ImageRgb16(ImageBuffer::from_pixel(1, 1, Rgb([0x8080u16; 3])));
+        let linear = ImageLoadOptions {
+            color_space: ColorSpace::Linear,
+            ..Default::default()
+        };
+
+        let (pixels, internal_format, _, _) = convert(image.clone(), ImageLoadOptions::default());
+        assert_eq!(internal_format, InternalFormat::RGB16F);
+        match pixels {
+            Pixels::F32(data) => assert!(data.iter().all(|c| (c - 0.2158).abs() < 1e-3)),
+            Pixels::U8(_) => panic!("16-bit images are loaded as floats"),
+        }
+
+        let (pixels, _, _, _) = convert(image, linear);
+        match pixels {
+            Pixels::F32(data) => assert!(data.iter().all(|c| (c - 0.502).abs() < 1e-3)),
+            Pixels::U8(_) => panic!("16-bit images are loaded as floats"),
+        }
+    }
+}
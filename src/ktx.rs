@@ -0,0 +1,804 @@
+//! Loading of KTX1 and KTX2 containers, including block compressed ETC2, ASTC and BC data.
+//!
+//! Compressed formats the backend cannot sample are decompressed on the CPU when a decoder is
+//! available in `block_compression`.
+use std::{convert::TryInto, path::Path};
+use thiserror::Error;
+
+use crate::{
+    block_compression,
+    engine::{Engine, EngineError},
+    texture::{
+        compressed_block_size, CompressedType, InternalFormat, PixelDataFormat, PixelDataType,
+        SamplerType, Texture, TextureError, TextureRegion,
+    },
+};
+
+const KTX1_IDENTIFIER: [u8; 12] = [
+    0xab, 0x4b, 0x54, 0x58, 0x20, 0x31, 0x31, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a,
+];
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a,
+];
+
+#[derive(Error, Debug)]
+pub enum KtxError {
+    #[error("Failed to read file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a KTX1 or KTX2 file")]
+    InvalidIdentifier,
+    #[error("File ends before the data it describes")]
+    Truncated,
+    #[error("Invalid header: {0}")]
+    InvalidHeader(&'static str),
+    #[error("Unknown texture format {0:#x}")]
+    UnknownFormat(u32),
+    #[error("Supercompression scheme {0} is not supported")]
+    Supercompressed(u32),
+    #[error("Unsupported texture layout: {0}")]
+    UnsupportedLayout(&'static str),
+    #[error("Texture format {0:?} is not supported by this backend and cannot be decompressed")]
+    UnsupportedFormat(InternalFormat),
+    #[error("Failed to create texture: {0}")]
+    Engine(#[from] EngineError),
+    #[error("Failed to upload texture: {0}")]
+    Texture(#[from] TextureError),
+}
+
+/// The format of the images stored in a KTX container.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KtxFormat {
+    Compressed(CompressedType),
+    Uncompressed {
+        internal_format: InternalFormat,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+        /// Size of a single pixel in bytes
+        pixel_size: usize,
+    },
+}
+impl KtxFormat {
+    /// The internal format a texture needs to store this data without conversion.
+    pub fn internal_format(&self) -> InternalFormat {
+        match *self {
+            KtxFormat::Compressed(ty) => Texture::compressed_internal_format(ty),
+            KtxFormat::Uncompressed {
+                internal_format, ..
+            } => internal_format,
+        }
+    }
+
+    /// The number of bytes of a single `width` by `height` image, or `None` if it does not fit in
+    /// a `usize`.
+    pub fn image_size(&self, width: usize, height: usize) -> Option<usize> {
+        let (blocks_x, blocks_y, block_size) = match *self {
+            KtxFormat::Compressed(ty) => {
+                let (block_width, block_height, block_size) = compressed_block_size(ty);
+                (
+                    (width + block_width - 1) / block_width,
+                    (height + block_height - 1) / block_height,
+                    block_size,
+                )
+            }
+            KtxFormat::Uncompressed { pixel_size, .. } => (width, height, pixel_size),
+        };
+        blocks_x.checked_mul(blocks_y)?.checked_mul(block_size)
+    }
+
+    /// Maps a KTX1 `glInternalFormat`.
+    pub fn from_gl(internal_format: u32) -> Option<Self> {
+        use CompressedType::*;
+        let compressed = match internal_format {
+            0x9270 => EAC_R11,
+            0x9271 => EAC_R11_SIGNED,
+            0x9272 => EAC_RG11,
+            0x9273 => EAC_RG11_SIGNED,
+            // ETC1 is a subset of ETC2
+            0x8d64 | 0x9274 => ETC2_RGB8,
+            0x9275 => ETC2_SRGB8,
+            0x9276 => ETC2_RGB8_A1,
+            0x9277 => ETC2_SRGB8_A1,
+            0x9278 => ETC2_EAC_RGBA8,
+            0x9279 => ETC2_EAC_SRGBA8,
+            0x83f0 => DXT1_RGB,
+            0x83f1 => DXT1_RGBA,
+            0x83f2 => DXT3_RGBA,
+            0x83f3 => DXT5_RGBA,
+            0x8c4c => DXT1_SRGB,
+            0x8c4d => DXT1_SRGBA,
+            0x8c4e => DXT3_SRGBA,
+            0x8c4f => DXT5_SRGBA,
+            0x93b0..=0x93bd => ASTC_BLOCKS[(internal_format - 0x93b0) as usize].0,
+            0x93d0..=0x93dd => ASTC_BLOCKS[(internal_format - 0x93d0) as usize].1,
+            _ => {
+                return match internal_format {
+                    0x8229 => Some(uncompressed(InternalFormat::R8)),
+                    0x822b => Some(uncompressed(InternalFormat::RG8)),
+                    0x8051 => Some(uncompressed(InternalFormat::RGB8)),
+                    0x8c41 => Some(uncompressed(InternalFormat::SRGB8)),
+                    0x8058 => Some(uncompressed(InternalFormat::RGBA8)),
+                    0x8c43 => Some(uncompressed(InternalFormat::SRGB8_A8)),
+                    0x822d => Some(uncompressed(InternalFormat::R16F)),
+                    0x822f => Some(uncompressed(InternalFormat::RG16F)),
+                    0x881b => Some(uncompressed(InternalFormat::RGB16F)),
+                    0x881a => Some(uncompressed(InternalFormat::RGBA16F)),
+                    0x822e => Some(uncompressed(InternalFormat::R32F)),
+                    0x8230 => Some(uncompressed(InternalFormat::RG32F)),
+                    0x8815 => Some(uncompressed(InternalFormat::RGB32F)),
+                    0x8814 => Some(uncompressed(InternalFormat::RGBA32F)),
+                    0x8c3a => Some(uncompressed(InternalFormat::R11F_G11F_B10F)),
+                    _ => None,
+                }
+            }
+        };
+        Some(KtxFormat::Compressed(compressed))
+    }
+
+    /// Maps a KTX2 `vkFormat`.
+    pub fn from_vk(vk_format: u32) -> Option<Self> {
+        use CompressedType::*;
+        let compressed = match vk_format {
+            131 => DXT1_RGB,
+            132 => DXT1_SRGB,
+            133 => DXT1_RGBA,
+            134 => DXT1_SRGBA,
+            135 => DXT3_RGBA,
+            136 => DXT3_SRGBA,
+            137 => DXT5_RGBA,
+            138 => DXT5_SRGBA,
+            147 => ETC2_RGB8,
+            148 => ETC2_SRGB8,
+            149 => ETC2_RGB8_A1,
+            150 => ETC2_SRGB8_A1,
+            151 => ETC2_EAC_RGBA8,
+            152 => ETC2_EAC_SRGBA8,
+            153 => EAC_R11,
+            154 => EAC_R11_SIGNED,
+            155 => EAC_RG11,
+            156 => EAC_RG11_SIGNED,
+            // UNORM and SRGB variants alternate for each ASTC block size
+            157..=184 => {
+                let (unorm, srgb) = ASTC_BLOCKS[((vk_format - 157) / 2) as usize];
+                if vk_format % 2 == 1 {
+                    unorm
+                } else {
+                    srgb
+                }
+            }
+            _ => {
+                return match vk_format {
+                    9 => Some(uncompressed(InternalFormat::R8)),
+                    16 => Some(uncompressed(InternalFormat::RG8)),
+                    23 => Some(uncompressed(InternalFormat::RGB8)),
+                    29 => Some(uncompressed(InternalFormat::SRGB8)),
+                    37 => Some(uncompressed(InternalFormat::RGBA8)),
+                    43 => Some(uncompressed(InternalFormat::SRGB8_A8)),
+                    76 => Some(uncompressed(InternalFormat::R16F)),
+                    83 => Some(uncompressed(InternalFormat::RG16F)),
+                    90 => Some(uncompressed(InternalFormat::RGB16F)),
+                    97 => Some(uncompressed(InternalFormat::RGBA16F)),
+                    100 => Some(uncompressed(InternalFormat::R32F)),
+                    103 => Some(uncompressed(InternalFormat::RG32F)),
+                    106 => Some(uncompressed(InternalFormat::RGB32F)),
+                    109 => Some(uncompressed(InternalFormat::RGBA32F)),
+                    122 => Some(uncompressed(InternalFormat::R11F_G11F_B10F)),
+                    _ => None,
+                }
+            }
+        };
+        Some(KtxFormat::Compressed(compressed))
+    }
+}
+
+/// ASTC block sizes in the order both GL and Vulkan list them, as (linear, sRGB).
+const ASTC_BLOCKS: [(CompressedType, CompressedType); 14] = [
+    (
+        CompressedType::RGBA_ASTC_4x4,
+        CompressedType::SRGB8_ALPHA8_ASTC_4x4,
+    ),
+    (
+        CompressedType::RGBA_ASTC_5x4,
+        CompressedType::SRGB8_ALPHA8_ASTC_5x4,
+    ),
+    (
+        CompressedType::RGBA_ASTC_5x5,
+        CompressedType::SRGB8_ALPHA8_ASTC_5x5,
+    ),
+    (
+        CompressedType::RGBA_ASTC_6x5,
+        CompressedType::SRGB8_ALPHA8_ASTC_6x5,
+    ),
+    (
+        CompressedType::RGBA_ASTC_6x6,
+        CompressedType::SRGB8_ALPHA8_ASTC_6x6,
+    ),
+    (
+        CompressedType::RGBA_ASTC_8x5,
+        CompressedType::SRGB8_ALPHA8_ASTC_8x5,
+    ),
+    (
+        CompressedType::RGBA_ASTC_8x6,
+        CompressedType::SRGB8_ALPHA8_ASTC_8x6,
+    ),
+    (
+        CompressedType::RGBA_ASTC_8x8,
+        CompressedType::SRGB8_ALPHA8_ASTC_8x8,
+    ),
+    (
+        CompressedType::RGBA_ASTC_10x5,
+        CompressedType::SRGB8_ALPHA8_ASTC_10x5,
+    ),
+    (
+        CompressedType::RGBA_ASTC_10x6,
+        CompressedType::SRGB8_ALPHA8_ASTC_10x6,
+    ),
+    (
+        CompressedType::RGBA_ASTC_10x8,
+        CompressedType::SRGB8_ALPHA8_ASTC_10x8,
+    ),
+    (
+        CompressedType::RGBA_ASTC_10x10,
+        CompressedType::SRGB8_ALPHA8_ASTC_10x10,
+    ),
+    (
+        CompressedType::RGBA_ASTC_12x10,
+        CompressedType::SRGB8_ALPHA8_ASTC_12x10,
+    ),
+    (
+        CompressedType::RGBA_ASTC_12x12,
+        CompressedType::SRGB8_ALPHA8_ASTC_12x12,
+    ),
+];
+
+fn uncompressed(internal_format: InternalFormat) -> KtxFormat {
+    use InternalFormat::*;
+    let (format, ty, pixel_size) = match internal_format {
+        R8 => (PixelDataFormat::R, PixelDataType::UBYTE, 1),
+        RG8 => (PixelDataFormat::RG, PixelDataType::UBYTE, 2),
+        RGB8 | SRGB8 => (PixelDataFormat::RGB, PixelDataType::UBYTE, 3),
+        RGBA8 | SRGB8_A8 => (PixelDataFormat::RGBA, PixelDataType::UBYTE, 4),
+        R16F => (PixelDataFormat::R, PixelDataType::HALF, 2),
+        RG16F => (PixelDataFormat::RG, PixelDataType::HALF, 4),
+        RGB16F => (PixelDataFormat::RGB, PixelDataType::HALF, 6),
+        RGBA16F => (PixelDataFormat::RGBA, PixelDataType::HALF, 8),
+        R32F => (PixelDataFormat::R, PixelDataType::FLOAT, 4),
+        RG32F => (PixelDataFormat::RG, PixelDataType::FLOAT, 8),
+        RGB32F => (PixelDataFormat::RGB, PixelDataType::FLOAT, 12),
+        RGBA32F => (PixelDataFormat::RGBA, PixelDataType::FLOAT, 16),
+        R11F_G11F_B10F => (PixelDataFormat::RGB, PixelDataType::UINT_10F_11F_11F_REV, 4),
+        _ => unreachable!("no KTX mapping for {:?}", internal_format),
+    };
+    KtxFormat::Uncompressed {
+        internal_format,
+        format,
+        ty,
+        pixel_size,
+    }
+}
+
+/// The images of a KTX container, with any padding removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KtxTexture {
+    pub format: KtxFormat,
+    pub width: u32,
+    pub height: u32,
+    /// 1 for 2D textures
+    pub depth: u32,
+    /// 1 for non-array textures
+    pub layers: u32,
+    /// 6 for cubemaps, 1 otherwise
+    pub faces: u32,
+    /// The data of each mip level, starting with the largest. Each level holds its images tightly
+    /// packed by layer, then face, then depth slice.
+    pub levels: Vec<Vec<u8>>,
+    /// Whether the container asks for its mip chain to be generated at load time.
+    pub generate_mipmaps: bool,
+}
+impl KtxTexture {
+    /// Parses a KTX1 or KTX2 container.
+    pub fn parse(bytes: &[u8]) -> Result<Self, KtxError> {
+        match bytes.get(..12) {
+            Some(identifier) if identifier == KTX1_IDENTIFIER => Self::parse_ktx1(bytes),
+            Some(identifier) if identifier == KTX2_IDENTIFIER => Self::parse_ktx2(bytes),
+            _ => Err(KtxError::InvalidIdentifier),
+        }
+    }
+
+    #[inline]
+    pub fn is_cubemap(&self) -> bool {
+        self.faces == 6
+    }
+
+    /// The size of the mip level `level`, in texels.
+    pub fn level_size(&self, level: usize) -> (usize, usize) {
+        (
+            (self.width as usize >> level).max(1),
+            (self.height as usize >> level).max(1),
+        )
+    }
+
+    /// The number of bytes of a single face or layer of the mip level `level`, or `None` if it
+    /// does not fit in a `usize`.
+    pub fn image_size(&self, level: usize) -> Option<usize> {
+        let (width, height) = self.level_size(level);
+        self.format
+            .image_size(width, height)?
+            .checked_mul((self.depth as usize >> level).max(1))
+    }
+
+    /// The kind of texture these images can be uploaded to.
+    pub fn sampler(&self) -> Result<SamplerType, KtxError> {
+        match (self.depth, self.layers, self.faces) {
+            (1, 1, 1) => Ok(SamplerType::SAMPLER_2D),
+            (1, 1, 6) => Ok(SamplerType::SAMPLER_CUBEMAP),
+            (1, _, 1) => Ok(SamplerType::SAMPLER_2D_ARRAY),
+            (1, _, _) => Err(KtxError::UnsupportedLayout("cubemap arrays")),
+            _ => Err(KtxError::UnsupportedLayout("3D textures")),
+        }
+    }
+
+    fn parse_ktx1(bytes: &[u8]) -> Result<Self, KtxError> {
+        let swap = match read_u32(bytes, 12, false)? {
+            0x0403_0201 => false,
+            0x0102_0304 => true,
+            _ => return Err(KtxError::InvalidHeader("invalid endianness")),
+        };
+        let field = |index: usize| read_u32(bytes, 16 + index * 4, swap);
+        let type_size = field(1)? as usize;
+        let gl_format = field(3)?;
+        let format = KtxFormat::from_gl(gl_format).ok_or(KtxError::UnknownFormat(gl_format))?;
+        let (width, height, depth) = (field(5)?, field(6)?.max(1), field(7)?.max(1));
+        let (array_elements, faces) = (field(8)?, field(9)?);
+        let level_count = field(10)?;
+        let key_value_size = field(11)? as usize;
+        if width == 0 {
+            return Err(KtxError::InvalidHeader("zero width"));
+        }
+
+        let mut texture = Self {
+            format,
+            width,
+            height,
+            depth,
+            layers: array_elements.max(1),
+            faces,
+            levels: Vec::new(),
+            generate_mipmaps: level_count == 0,
+        };
+        texture.check_layout()?;
+
+        let mut offset = 64 + key_value_size;
+        for level in 0..level_count.max(1) as usize {
+            let image_size = read_u32(bytes, offset, swap)? as usize;
+            offset += 4;
+
+            // Faces of non-array cubemaps are stored separately, each with its own padding
+            let mut data = Vec::new();
+            if array_elements == 0 && faces == 6 {
+                for _ in 0..6 {
+                    data.extend_from_slice(read_bytes(bytes, offset, image_size)?);
+                    offset += align4(image_size);
+                }
+            } else {
+                data.extend_from_slice(read_bytes(bytes, offset, image_size)?);
+                offset += align4(image_size);
+            }
+
+            // Uncompressed rows are aligned to 4 bytes
+            if let KtxFormat::Uncompressed { pixel_size, .. } = format {
+                let row_size = texture
+                    .level_size(level)
+                    .0
+                    .checked_mul(pixel_size)
+                    .ok_or(KtxError::InvalidHeader("image size overflows"))?;
+                if row_size % 4 != 0 {
+                    data = data
+                        .chunks(align4(row_size))
+                        .flat_map(|row| row[..row_size.min(row.len())].iter().copied())
+                        .collect();
+                }
+            }
+            if swap && type_size > 1 {
+                for element in data.chunks_mut(type_size) {
+                    element.reverse();
+                }
+            }
+
+            texture.push_level(data)?;
+        }
+
+        Ok(texture)
+    }
+
+    fn parse_ktx2(bytes: &[u8]) -> Result<Self, KtxError> {
+        let field = |index: usize| read_u32(bytes, 12 + index * 4, false);
+        let vk_format = field(0)?;
+        let format = KtxFormat::from_vk(vk_format).ok_or(KtxError::UnknownFormat(vk_format))?;
+        let (width, height, depth) = (field(2)?, field(3)?.max(1), field(4)?.max(1));
+        let (layers, faces, level_count) = (field(5)?, field(6)?, field(7)?);
+        let supercompression = field(8)?;
+        if supercompression != 0 {
+            return Err(KtxError::Supercompressed(supercompression));
+        }
+        if width == 0 {
+            return Err(KtxError::InvalidHeader("zero width"));
+        }
+
+        let mut texture = Self {
+            format,
+            width,
+            height,
+            depth,
+            layers: layers.max(1),
+            faces,
+            levels: Vec::new(),
+            generate_mipmaps: level_count == 0,
+        };
+        texture.check_layout()?;
+
+        // The level index follows the 48 byte header and the 32 byte index of the other sections
+        for level in 0..level_count.max(1) as usize {
+            let entry = 80 + level * 24;
+            let offset = read_u64(bytes, entry)?;
+            let length = read_u64(bytes, entry + 8)?;
+            texture.push_level(read_bytes(bytes, offset, length)?.to_vec())?;
+        }
+
+        Ok(texture)
+    }
+
+    fn check_layout(&self) -> Result<(), KtxError> {
+        if self.faces != 1 && self.faces != 6 {
+            return Err(KtxError::InvalidHeader("face count must be 1 or 6"));
+        }
+        if self.faces == 6 && self.width != self.height {
+            return Err(KtxError::InvalidHeader("cubemap faces must be square"));
+        }
+        Ok(())
+    }
+
+    fn push_level(&mut self, data: Vec<u8>) -> Result<(), KtxError> {
+        let expected = self
+            .image_size(self.levels.len())
+            .and_then(|size| size.checked_mul(self.layers as usize))
+            .and_then(|size| size.checked_mul(self.faces as usize))
+            .ok_or(KtxError::InvalidHeader("image size overflows"))?;
+        if data.len() != expected {
+            return Err(KtxError::InvalidHeader(
+                "level size does not match its dimensions",
+            ));
+        }
+        self.levels.push(data);
+        Ok(())
+    }
+}
+
+fn align4(size: usize) -> usize {
+    (size + 3) & !3
+}
+
+fn read_bytes(bytes: &[u8], offset: usize, len: usize) -> Result<&[u8], KtxError> {
+    bytes
+        .get(offset..offset.checked_add(len).ok_or(KtxError::Truncated)?)
+        .ok_or(KtxError::Truncated)
+}
+
+fn read_u32(bytes: &[u8], offset: usize, swap: bool) -> Result<u32, KtxError> {
+    let value = u32::from_le_bytes(read_bytes(bytes, offset, 4)?.try_into().unwrap());
+    Ok(if swap { value.swap_bytes() } else { value })
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<usize, KtxError> {
+    let value = u64::from_le_bytes(read_bytes(bytes, offset, 8)?.try_into().unwrap());
+    value.try_into().map_err(|_| KtxError::Truncated)
+}
+
+/// Reads the KTX file at `path` and uploads it into a new texture.
+pub fn load_texture<P: AsRef<Path>>(engine: &mut Engine, path: P) -> Result<Texture, KtxError> {
+    let bytes = std::fs::read(path)?;
+    load_texture_from_memory(engine, &bytes)
+}
+
+/// Parses an in-memory KTX file and uploads it into a new texture.
+pub fn load_texture_from_memory(engine: &mut Engine, bytes: &[u8]) -> Result<Texture, KtxError> {
+    let ktx = KtxTexture::parse(bytes)?;
+    texture_from_ktx(engine, &ktx)
+}
+
+/// Uploads parsed KTX images into a new texture. Compressed data is uploaded as is when the
+/// backend supports its format, and decompressed on the CPU otherwise.
+pub fn texture_from_ktx(engine: &mut Engine, ktx: &KtxTexture) -> Result<Texture, KtxError> {
+    let supported = Texture::is_format_supported(engine, ktx.format.internal_format());
+    upload_ktx(engine, ktx, !supported)
+}
+
+fn upload_ktx(
+    engine: &mut Engine,
+    ktx: &KtxTexture,
+    decompress: bool,
+) -> Result<Texture, KtxError> {
+    let sampler = ktx.sampler()?;
+
+    let mut internal_format = ktx.format.internal_format();
+    if decompress {
+        match ktx.format {
+            KtxFormat::Compressed(ty) if block_compression::can_decode(ty) => {
+                internal_format = block_compression::decoded_format(ty).unwrap().0;
+            }
+            _ => return Err(KtxError::UnsupportedFormat(internal_format)),
+        }
+    }
+
+    let generate_mipmaps = ktx.generate_mipmaps
+        && ktx.levels.len() == 1
+        && Texture::is_format_mipmappable(internal_format);
+    let levels = if generate_mipmaps {
+        Texture::max_level_count(ktx.width as usize, ktx.height as usize)
+    } else {
+        ktx.levels.len()
+    };
+
+    let mut texture = Texture::builder()?
+        .width(ktx.width as usize)
+        .height(ktx.height as usize)
+        .depth(ktx.layers as usize)
+        .levels(levels)
+        .sampler(sampler)
+        .format(internal_format)
+        .build(engine)?;
+
+    for (level, data) in ktx.levels.iter().enumerate() {
+        let (width, height) = ktx.level_size(level);
+        let region = TextureRegion::new_3d(
+            0,
+            0,
+            0,
+            width.try_into().unwrap(),
+            height.try_into().unwrap(),
+            ktx.layers,
+        );
+
+        match ktx.format {
+            KtxFormat::Compressed(ty) if decompress => {
+                let mut pixels = Vec::new();
+                let (mut format, mut pixel_type) = (PixelDataFormat::RGBA, PixelDataType::UBYTE);
+                let image_size = ktx
+                    .image_size(level)
+                    .ok_or(KtxError::InvalidHeader("image size overflows"))?;
+                for image in data.chunks(image_size) {
+                    let decoded = block_compression::decode(ty, width, height, image)
+                        .ok_or(KtxError::UnsupportedFormat(internal_format))?;
+                    format = decoded.format;
+                    pixel_type = decoded.ty;
+                    pixels.extend_from_slice(&decoded.data);
+                }
+                if ktx.is_cubemap() {
+                    texture.set_cube_faces(level, pixels, format, pixel_type)?;
+                } else {
+                    texture.set_region(level, region, pixels, format, pixel_type)?;
+                }
+            }
+            KtxFormat::Compressed(ty) => {
+                if ktx.is_cubemap() {
+                    texture.set_compressed_cube_faces(level, data.clone(), ty)?;
+                } else {
+                    texture.set_compressed_region(level, region, data.clone(), ty)?;
+                }
+            }
+            KtxFormat::Uncompressed { format, ty, .. } => {
+                if ktx.is_cubemap() {
+                    texture.set_cube_faces(level, data.clone(), format, ty)?;
+                } else {
+                    texture.set_region(level, region, data.clone(), format, ty)?;
+                }
+            }
+        }
+    }
+
+    if generate_mipmaps && levels > 1 {
+        texture.generate_mipmaps()?;
+    }
+
+    Ok(texture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        texture_from_ktx, upload_ktx, KtxError, KtxFormat, KtxTexture, KTX1_IDENTIFIER,
+        KTX2_IDENTIFIER,
+    };
+    use crate::{
+        engine::Engine,
+        texture::{CompressedType, InternalFormat, SamplerType},
+        Backend,
+    };
+
+    fn ktx1(header: [u32; 12], levels: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = KTX1_IDENTIFIER.to_vec();
+        bytes.extend_from_slice(&0x0403_0201u32.to_le_bytes());
+        for field in header.iter() {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        for level in levels {
+            bytes.extend_from_slice(level);
+        }
+        bytes
+    }
+
+    #[test]
+    fn ktx1_row_padding() {
+        // A 3x2 RGB8 image whose rows are padded from 9 to 12 bytes
+        let mut level = 24u32.to_le_bytes().to_vec();
+        level.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0]);
+        level.extend_from_slice(&[10, 11, 12, 13, 14, 15, 16, 17, 18, 0, 0, 0]);
+        let bytes = ktx1(
+            [0x1401, 1, 0x1907, 0x8051, 0x1907, 3, 2, 0, 0, 1, 1, 0],
+            &[&level],
+        );
+
+        let ktx = KtxTexture::parse(&bytes).unwrap();
+        assert_eq!(ktx.format.internal_format(), InternalFormat::RGB8);
+        assert_eq!(ktx.levels, vec![(1..=18).collect::<Vec<u8>>()]);
+        assert_eq!(ktx.sampler().unwrap(), SamplerType::SAMPLER_2D);
+        assert!(!ktx.generate_mipmaps);
+
+        assert!(matches!(
+            KtxTexture::parse(&bytes[..bytes.len() - 1]),
+            Err(KtxError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn ktx1_size_overflow() {
+        // RGBA32F images of 2^32 - 1 by 2^32 - 1 texels do not fit in memory
+        let level = 0u32.to_le_bytes();
+        let bytes = ktx1(
+            [0x1406, 4, 0x1908, 0x8814, 0x1908, !0, !0, 0, 0, 1, 1, 0],
+            &[&level],
+        );
+        assert!(matches!(
+            KtxTexture::parse(&bytes),
+            Err(KtxError::InvalidHeader("image size overflows"))
+        ));
+    }
+
+    #[test]
+    fn ktx1_cubemap() {
+        // ETC2 cubemap with a 4x4 and 2x2 level, each face a single 8 byte block
+        let mut level = 8u32.to_le_bytes().to_vec();
+        for face in 0..6 {
+            level.extend_from_slice(&[face; 8]);
+        }
+        let bytes = ktx1(
+            [0, 1, 0, 0x9274, 0x1907, 4, 4, 0, 0, 6, 2, 0],
+            &[&level, &level],
+        );
+
+        let ktx = KtxTexture::parse(&bytes).unwrap();
+        assert_eq!(ktx.format, KtxFormat::Compressed(CompressedType::ETC2_RGB8));
+        assert_eq!(ktx.sampler().unwrap(), SamplerType::SAMPLER_CUBEMAP);
+        assert_eq!(ktx.levels.len(), 2);
+        assert_eq!(ktx.levels[1].len(), 48);
+        assert_eq!(ktx.levels[1][40..], [5; 8]);
+    }
+
+    #[test]
+    fn ktx2_levels() {
+        // A 2 layer BC3 array with a full mip chain of 8x8, 4x4, 2x2 and 1x1
+        let sizes = [64usize, 16, 16, 16]
+            .iter()
+            .map(|size| size * 2)
+            .collect::<Vec<_>>();
+        let mut bytes = KTX2_IDENTIFIER.to_vec();
+        for field in [137u32, 1, 8, 8, 0, 2, 1, 4, 0].iter() {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0; 32]);
+
+        // Levels are stored smallest first
+        let mut offset = 80 + 24 * sizes.len();
+        let mut offsets = vec![0; sizes.len()];
+        for level in (0..sizes.len()).rev() {
+            offsets[level] = offset;
+            offset += sizes[level];
+        }
+        for (level, size) in sizes.iter().enumerate() {
+            bytes.extend_from_slice(&(offsets[level] as u64).to_le_bytes());
+            bytes.extend_from_slice(&(*size as u64).to_le_bytes());
+            bytes.extend_from_slice(&(*size as u64).to_le_bytes());
+        }
+        bytes.resize(offsets[sizes.len() - 1], 0);
+        for level in (0..sizes.len()).rev() {
+            bytes.extend(std::iter::repeat(level as u8).take(sizes[level]));
+        }
+
+        let ktx = KtxTexture::parse(&bytes).unwrap();
+        assert_eq!(ktx.format, KtxFormat::Compressed(CompressedType::DXT5_RGBA));
+        assert_eq!(ktx.sampler().unwrap(), SamplerType::SAMPLER_2D_ARRAY);
+        assert_eq!(ktx.level_size(3), (1, 1));
+        assert_eq!(ktx.image_size(3), Some(16));
+        for (level, data) in ktx.levels.iter().enumerate() {
+            assert_eq!(data.len(), sizes[level]);
+            assert!(data.iter().all(|&byte| byte == level as u8));
+        }
+
+        // Supercompressed data cannot be read directly
+        bytes[44..48].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            KtxTexture::parse(&bytes),
+            Err(KtxError::Supercompressed(2))
+        ));
+    }
+
+    #[test]
+    fn texture_uploads() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        // A 2 layer BC3 array with a full mip chain of 8x8, 4x4, 2x2 and 1x1
+        let array = KtxTexture {
+            format: KtxFormat::Compressed(CompressedType::DXT5_RGBA),
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 2,
+            faces: 1,
+            levels: [64, 16, 16, 16]
+                .iter()
+                .map(|size| vec![0; size * 2])
+                .collect(),
+            generate_mipmaps: false,
+        };
+        // An ETC2 cubemap with a 4x4 and 2x2 level
+        let cubemap = KtxTexture {
+            format: KtxFormat::Compressed(CompressedType::ETC2_RGB8),
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            faces: 6,
+            levels: vec![vec![0; 48]; 2],
+            generate_mipmaps: false,
+        };
+
+        for ktx in [&array, &cubemap].iter() {
+            let texture = texture_from_ktx(&mut engine, ktx).unwrap();
+            assert_eq!(texture.levels(), ktx.levels.len());
+            assert_eq!(texture.target(), ktx.sampler().unwrap());
+
+            // The fallback for backends without the compressed format
+            let texture = upload_ktx(&mut engine, ktx, true).unwrap();
+            assert_eq!(texture.levels(), ktx.levels.len());
+        }
+        assert_eq!(
+            upload_ktx(&mut engine, &array, true).unwrap().format(),
+            InternalFormat::RGBA8
+        );
+    }
+
+    #[test]
+    fn format_tables() {
+        assert_eq!(
+            KtxFormat::from_gl(0x93b2),
+            Some(KtxFormat::Compressed(CompressedType::RGBA_ASTC_5x5))
+        );
+        assert_eq!(
+            KtxFormat::from_gl(0x93dd),
+            Some(KtxFormat::Compressed(
+                CompressedType::SRGB8_ALPHA8_ASTC_12x12
+            ))
+        );
+        assert_eq!(
+            KtxFormat::from_vk(160),
+            Some(KtxFormat::Compressed(CompressedType::SRGB8_ALPHA8_ASTC_5x4))
+        );
+        assert_eq!(
+            KtxFormat::from_vk(183),
+            Some(KtxFormat::Compressed(CompressedType::RGBA_ASTC_12x12))
+        );
+        assert_eq!(
+            KtxFormat::from_vk(97).map(|format| format.internal_format()),
+            Some(InternalFormat::RGBA16F)
+        );
+        assert_eq!(KtxFormat::from_vk(1), None);
+    }
+}
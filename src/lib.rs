@@ -11,19 +11,42 @@ pub mod camera;
 pub use camera::*;
 pub mod engine;
 pub use engine::*;
+pub mod frame_stats;
+pub use frame_stats::*;
+pub mod render_target;
+pub use render_target::*;
 pub mod renderer;
 pub use renderer::*;
 pub mod scene;
 pub use scene::*;
 pub mod swapchain;
 pub use swapchain::*;
+pub mod surface;
+pub use surface::*;
 pub mod view;
 pub use view::*;
 
 pub mod texture;
 pub use texture::*;
 
+mod astc;
+pub mod block_compression;
+#[cfg(feature = "ecs")]
+pub mod ecs;
+#[cfg(feature = "gltf")]
+pub mod gltf_animator;
+#[cfg(feature = "gltf")]
+pub mod gltf_loader;
+#[cfg(feature = "image")]
+pub mod image_loader;
+pub mod ktx;
+#[cfg(feature = "tobj")]
+pub mod obj_loader;
+pub mod stream;
+
 pub mod entity_manager;
+pub mod geometry;
+pub mod light;
 pub mod renderable;
 
 pub mod material;
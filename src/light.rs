@@ -0,0 +1,223 @@
+use crate::{Engine, EngineError, Entity};
+use filament_sys::{ffi, Float3};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::ToPrimitive;
+use thiserror::Error;
+
+pub type LightInstance = u32;
+
+#[derive(Error, Debug)]
+pub enum LightBuildError {
+    #[error("Creation of a type failed")]
+    CreationFailed,
+}
+
+/// The kind of a light, matching `filament::LightManager::Type`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+#[allow(non_camel_case_types)]
+pub enum LightType {
+    /// A directional light which also draws a sun disk in the sky.
+    SUN = 0,
+    DIRECTIONAL = 1,
+    POINT = 2,
+    /// A spot light whose intensity stays the same as its cone angle changes.
+    FOCUSED_SPOT = 3,
+    SPOT = 4,
+}
+
+pub struct LightBuilder<'a> {
+    ptr: *mut ffi::helpers_LightBuilderProxy,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+impl<'a> Drop for LightBuilder<'a> {
+    fn drop(&mut self) {
+        unsafe { ffi::helpers_light_builder_destroy(self.ptr) }
+    }
+}
+impl<'a> LightBuilder<'a> {
+    pub fn cast_shadows(self, value: bool) -> Self {
+        unsafe { ffi::helpers_light_builder_castShadows(self.ptr, value) }
+        self
+    }
+
+    /// Sets the linear RGB color of the light.
+    pub fn color<V: Into<Float3>>(self, color: V) -> Self {
+        let color = color.into();
+        unsafe { ffi::helpers_light_builder_color(self.ptr, &color as *const Float3 as *const f32) }
+        self
+    }
+
+    /// Sets the intensity in lux for directional lights and in lumens for point and spot lights.
+    pub fn intensity(self, intensity: f32) -> Self {
+        unsafe { ffi::helpers_light_builder_intensity(self.ptr, intensity) }
+        self
+    }
+
+    /// Sets the position of point and spot lights, relative to the transform of the entity.
+    pub fn position<V: Into<Float3>>(self, position: V) -> Self {
+        let position = position.into();
+        unsafe {
+            ffi::helpers_light_builder_position(self.ptr, &position as *const Float3 as *const f32)
+        }
+        self
+    }
+
+    /// Sets the direction of directional and spot lights, relative to the transform of the entity.
+    pub fn direction<V: Into<Float3>>(self, direction: V) -> Self {
+        let direction = direction.into();
+        unsafe {
+            ffi::helpers_light_builder_direction(
+                self.ptr,
+                &direction as *const Float3 as *const f32,
+            )
+        }
+        self
+    }
+
+    /// Sets the distance at which point and spot lights stop having an effect.
+    pub fn falloff(self, radius: f32) -> Self {
+        unsafe { ffi::helpers_light_builder_falloff(self.ptr, radius) }
+        self
+    }
+
+    /// Sets the inner and outer angles of a spot light's cone, in radians.
+    pub fn spot_light_cone(self, inner: f32, outer: f32) -> Self {
+        unsafe { ffi::helpers_light_builder_spotLightCone(self.ptr, inner, outer) }
+        self
+    }
+
+    pub fn build(self, engine: &mut Engine, entity: Entity) -> Result<(), LightBuildError> {
+        match unsafe { ffi::helpers_light_builder_build(self.ptr, engine.as_raw_ptr(), entity) } {
+            true => Ok(()),
+            false => Err(LightBuildError::CreationFailed),
+        }
+    }
+
+    pub fn new(light_type: LightType) -> Result<Self, EngineError> {
+        let ptr = unsafe { ffi::helpers_light_builder_new(light_type.to_u8().unwrap()) };
+        if ptr.is_null() {
+            Err(EngineError::CreationFailed)
+        } else {
+            Ok(Self {
+                ptr,
+                _marker: std::marker::PhantomData,
+            })
+        }
+    }
+}
+
+// No drop, we get this as a ref from the engine
+pub struct LightManager<'a> {
+    ptr: *mut ffi::filament_LightManager,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+impl<'a> LightManager<'a> {
+    pub fn get_instance(&self, entity: Entity) -> Option<LightInstance> {
+        let instance = unsafe { ffi::helpers_light_manager_getInstance(self.ptr, entity) };
+        if instance == 0 {
+            None
+        } else {
+            Some(instance)
+        }
+    }
+
+    pub fn has_component(&self, entity: Entity) -> bool {
+        unsafe { ffi::helpers_light_manager_hasComponent(self.ptr, entity) }
+    }
+
+    /// Removes the light component from `entity`, the entity itself is left alive.
+    pub fn destroy(&mut self, entity: Entity) {
+        unsafe { ffi::helpers_light_manager_destroy(self.ptr, entity) }
+    }
+
+    pub fn set_position<V: Into<Float3>>(&mut self, instance: LightInstance, position: V) {
+        let position = position.into();
+        unsafe {
+            ffi::helpers_light_manager_setPosition(
+                self.ptr,
+                instance,
+                &position as *const Float3 as *const f32,
+            )
+        }
+    }
+
+    pub fn set_direction<V: Into<Float3>>(&mut self, instance: LightInstance, direction: V) {
+        let direction = direction.into();
+        unsafe {
+            ffi::helpers_light_manager_setDirection(
+                self.ptr,
+                instance,
+                &direction as *const Float3 as *const f32,
+            )
+        }
+    }
+
+    pub fn set_color<V: Into<Float3>>(&mut self, instance: LightInstance, color: V) {
+        let color = color.into();
+        unsafe {
+            ffi::helpers_light_manager_setColor(
+                self.ptr,
+                instance,
+                &color as *const Float3 as *const f32,
+            )
+        }
+    }
+
+    pub fn set_intensity(&mut self, instance: LightInstance, intensity: f32) {
+        unsafe { ffi::helpers_light_manager_setIntensity(self.ptr, instance, intensity) }
+    }
+
+    pub fn set_falloff(&mut self, instance: LightInstance, radius: f32) {
+        unsafe { ffi::helpers_light_manager_setFalloff(self.ptr, instance, radius) }
+    }
+
+    pub fn set_spot_light_cone(&mut self, instance: LightInstance, inner: f32, outer: f32) {
+        unsafe { ffi::helpers_light_manager_setSpotLightCone(self.ptr, instance, inner, outer) }
+    }
+
+    pub fn set_shadow_caster(&mut self, instance: LightInstance, value: bool) {
+        unsafe { ffi::helpers_light_manager_setShadowCaster(self.ptr, instance, value) }
+    }
+
+    pub(crate) fn new(engine: &'a Engine) -> Result<LightManager<'a>, EngineError> {
+        let ptr = unsafe { ffi::filament_Engine_getLightManager(engine.as_raw_ptr()) };
+        if ptr.is_null() {
+            Err(EngineError::CreationFailed)
+        } else {
+            Ok(Self {
+                ptr,
+                _marker: Default::default(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LightBuilder, LightType};
+    use crate::{engine::Engine, entity_manager::EntityManager, Backend};
+
+    #[test]
+    fn light_create() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let entity = EntityManager::get().create();
+
+        LightBuilder::new(LightType::POINT)
+            .unwrap()
+            .color([1.0, 0.5, 0.25])
+            .intensity(1000.0)
+            .falloff(10.0)
+            .build(&mut engine, entity)
+            .unwrap();
+
+        let mut light_manager = engine.light_manager().unwrap();
+        assert!(light_manager.has_component(entity));
+        let instance = light_manager.get_instance(entity).unwrap();
+        light_manager.set_intensity(instance, 500.0);
+
+        light_manager.destroy(entity);
+        assert!(!light_manager.has_component(entity));
+        EntityManager::get().destroy(&[entity]);
+    }
+}
@@ -1,32 +1,140 @@
-use filament_sys::ffi;
+use filament_sys::{ffi, Float2, Float3, Float4, Mat3f, Mat4f};
 use std::{convert::TryInto, ffi::CString, sync::Arc};
 
 use crate::{
     engine::{Engine, EngineError},
-    impl_handle,
+    impl_handle, impl_ptr_functions,
     texture::{Texture, TextureSampler},
 };
 
 pub enum MaterialParameter<'a> {
     Texture(&'a Texture, &'a TextureSampler),
+    Float(f32),
+    Float2(Float2),
+    Float3(Float3),
+    Float4(Float4),
+    Int(i32),
+    Bool(bool),
+    Mat3(Mat3f),
+    Mat4(Mat4f),
 }
 
-impl_handle!(MaterialInstance, ffi::filament_MaterialInstance);
+macro_rules! impl_material_parameter_from {
+    ($($ty:ty => $variant:ident),+ $(,)?) => {
+        $(
+            impl<'a> From<$ty> for MaterialParameter<'a> {
+                #[inline]
+                fn from(value: $ty) -> Self {
+                    MaterialParameter::$variant(value.into())
+                }
+            }
+        )+
+    };
+}
+
+impl_material_parameter_from!(
+    f32 => Float,
+    Float2 => Float2,
+    Float3 => Float3,
+    Float4 => Float4,
+    i32 => Int,
+    bool => Bool,
+    Mat3f => Mat3,
+    Mat4f => Mat4,
+);
+
+#[cfg(feature = "mint")]
+impl_material_parameter_from!(
+    mint::Vector2<f32> => Float2,
+    mint::Vector3<f32> => Float3,
+    mint::Vector4<f32> => Float4,
+    mint::ColumnMatrix3<f32> => Mat3,
+    mint::ColumnMatrix4<f32> => Mat4,
+);
+
+#[cfg(feature = "glam")]
+impl_material_parameter_from!(
+    glam::Vec2 => Float2,
+    glam::Vec3 => Float3,
+    glam::Vec4 => Float4,
+    glam::Mat3 => Mat3,
+    glam::Mat4 => Mat4,
+);
+
+#[cfg(feature = "nalgebra")]
+impl_material_parameter_from!(
+    nalgebra::Vector2<f32> => Float2,
+    nalgebra::Vector3<f32> => Float3,
+    nalgebra::Vector4<f32> => Float4,
+    nalgebra::Matrix3<f32> => Mat3,
+    nalgebra::Matrix4<f32> => Mat4,
+);
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MaterialInstance {
+    engine: Engine,
+    ptr: Arc<*mut ffi::filament_MaterialInstance>,
+    // Instances are only valid while their material is, so they keep it alive
+    material: Material,
+    // The default instance belongs to its material and must not be destroyed
+    owned: bool,
+}
+impl_ptr_functions!(MaterialInstance, ffi::filament_MaterialInstance);
+impl Drop for MaterialInstance {
+    fn drop(&mut self) {
+        if !self.owned {
+            return;
+        }
+        if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
+            log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+            unsafe { self.engine.as_raw_mut().destroy7(*ptr) }
+        }
+    }
+}
 impl MaterialInstance {
-    pub fn set<S>(&mut self, name: S, parameter: MaterialParameter)
+    /// Sets the parameter `name`, from a `MaterialParameter` or anything converting into one, such
+    /// as `f32`, `Float3` or `Mat4f`.
+    pub fn set<'a, S, P>(&mut self, name: S, parameter: P)
     where
         S: AsRef<str>,
+        P: Into<MaterialParameter<'a>>,
     {
         let name = CString::new(name.as_ref()).unwrap();
-        match parameter {
+        let instance = self.as_raw_ptr();
+        let name = name.as_ptr();
+        match parameter.into() {
             MaterialParameter::Texture(texture, sampler) => unsafe {
                 ffi::helpers_material_instance_setParameter_texture(
-                    self.as_raw_ptr(),
-                    name.as_ptr(),
+                    instance,
+                    name,
                     texture.as_raw_ptr(),
                     sampler as *const _,
                 )
             },
+            MaterialParameter::Float(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_float(instance, name, value)
+            },
+            MaterialParameter::Float2(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_float2(instance, name, &value)
+            },
+            MaterialParameter::Float3(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_float3(instance, name, &value)
+            },
+            MaterialParameter::Float4(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_float4(instance, name, &value)
+            },
+            MaterialParameter::Int(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_int(instance, name, value)
+            },
+            MaterialParameter::Bool(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_bool(instance, name, value)
+            },
+            MaterialParameter::Mat3(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_mat3f(instance, name, &value)
+            },
+            MaterialParameter::Mat4(value) => unsafe {
+                ffi::helpers_material_instance_setParameter_mat4f(instance, name, &value)
+            },
         }
     }
 }
@@ -46,10 +154,34 @@ impl Material {
             MaterialInstance {
                 engine: self.engine.clone(),
                 ptr: Arc::new(ffi::helpers_material_getDefaultInstance(self.as_raw_ptr())),
+                material: self.clone(),
+                owned: false,
             }
         }
     }
 
+    /// Creates a new instance of this material, destroyed once every handle to it is dropped. The
+    /// instance keeps the material alive.
+    pub fn create_instance(&self) -> Result<MaterialInstance, EngineError> {
+        let ptr = unsafe { ffi::helpers_material_createInstance(self.as_raw_ptr()) };
+        if ptr.is_null() {
+            Err(EngineError::CreationFailed)
+        } else {
+            Ok(MaterialInstance {
+                engine: self.engine.clone(),
+                ptr: Arc::new(ptr),
+                material: self.clone(),
+                owned: true,
+            })
+        }
+    }
+
+    /// Whether this material declares a parameter called `name`.
+    pub fn has_parameter<S: AsRef<str>>(&self, name: S) -> bool {
+        let name = CString::new(name.as_ref()).unwrap();
+        unsafe { ffi::helpers_material_hasParameter(self.as_raw_ptr(), name.as_ptr()) }
+    }
+
     pub fn new(engine: &mut Engine, package: &[u8]) -> Result<Self, EngineError> {
         let ptr = unsafe {
             ffi::helpers_material_build(
@@ -0,0 +1,437 @@
+//! Loading of Wavefront OBJ files and their MTL materials through the `tobj` crate.
+//!
+//! MTL parameters are mapped onto the same material parameters as the glTF loader
+//! (`baseColorFactor`, `roughnessFactor`, `baseColorMap`, ...), and parameters the material does
+//! not declare are skipped. Texture maps are only loaded when the `image` feature is enabled.
+use std::{collections::BTreeMap, path::Path};
+use thiserror::Error;
+
+use crate::{
+    buffers::{IndexBuffer, VertexBuffer},
+    engine::{Engine, EngineError},
+    entity_manager::{self, EntityManager},
+    geometry::{Geometry, GeometryError},
+    material::{Material, MaterialInstance, MaterialParameter},
+    renderable::{PrimitiveType, RenderableBuildError, RenderableBuilder},
+    scene::Scene,
+    texture::{
+        SamplerMagFilter, SamplerMinFilter, SamplerWrapMode, Texture, TextureError, TextureSampler,
+    },
+    Entity,
+};
+
+#[derive(Error, Debug)]
+pub enum ObjError {
+    #[error("Failed to load OBJ: {0}")]
+    Load(#[from] tobj::LoadError),
+    #[error("Failed to create a Filament object: {0}")]
+    Engine(#[from] EngineError),
+    #[error("Failed to create texture: {0}")]
+    Texture(#[from] TextureError),
+    #[cfg(feature = "image")]
+    #[error("Failed to load texture map: {0}")]
+    Image(#[from] crate::image_loader::ImageLoadError),
+    #[error("Failed to build renderable: {0}")]
+    Renderable(#[from] RenderableBuildError),
+    #[error("Failed to generate normals or tangents: {0}")]
+    Geometry(#[from] GeometryError),
+}
+
+/// Creates `ObjAsset`s, using the given lit material for every MTL material.
+pub struct ObjLoader {
+    engine: Engine,
+    material: Material,
+}
+impl ObjLoader {
+    pub fn new(engine: &mut Engine, material: &Material) -> Self {
+        Self {
+            engine: engine.clone(),
+            material: material.clone(),
+        }
+    }
+
+    /// Loads the OBJ file at `path` along with the MTL libraries it references. A missing MTL
+    /// library is logged and its materials replaced by the defaults.
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<ObjAsset, ObjError> {
+        let path = path.as_ref();
+        let (models, materials) = tobj::load_obj(path, &load_options())?;
+        let materials = materials.unwrap_or_else(|e| {
+            log::warn!("Failed to load the materials of {}: {}", path.display(), e);
+            Vec::new()
+        });
+
+        let mut asset = ObjAsset {
+            engine: self.engine.clone(),
+            renderables: Vec::new(),
+            material_instances: Vec::new(),
+            textures: Vec::new(),
+            vertex_buffers: Vec::new(),
+            index_buffers: Vec::new(),
+        };
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        for (material_id, geometry) in group_by_material(&models) {
+            let material = material_id.and_then(|id| materials.get(id));
+            // Anything created before an error is released by the asset's drop
+            asset.add_renderable(&self.material, geometry, material, directory)?;
+        }
+        Ok(asset)
+    }
+}
+
+/// The renderables created from an OBJ file, one per material. Every entity, buffer, texture and
+/// material instance is destroyed when the asset is dropped.
+pub struct ObjAsset {
+    engine: Engine,
+    renderables: Vec<Entity>,
+    // Dropped in declaration order, instances before the textures they reference
+    material_instances: Vec<MaterialInstance>,
+    textures: Vec<Texture>,
+    vertex_buffers: Vec<VertexBuffer>,
+    index_buffers: Vec<IndexBuffer>,
+}
+impl Drop for ObjAsset {
+    fn drop(&mut self) {
+        log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+        entity_manager::destroy_entities(&mut self.engine, &self.renderables);
+    }
+}
+impl ObjAsset {
+    /// The entities holding a renderable component, one per material used by the file.
+    #[inline]
+    pub fn renderables(&self) -> &[Entity] {
+        &self.renderables
+    }
+
+    #[inline]
+    pub fn material_instances(&self) -> &[MaterialInstance] {
+        &self.material_instances
+    }
+
+    pub fn add_to_scene(&self, scene: &mut Scene) {
+        scene.extend_from_slice(&self.renderables);
+    }
+
+    pub fn remove_from_scene(&self, scene: &mut Scene) {
+        for entity in self.renderables.iter() {
+            scene.remove(*entity);
+        }
+    }
+
+    fn add_renderable(
+        &mut self,
+        material: &Material,
+        mut geometry: Geometry,
+        mtl: Option<&tobj::Material>,
+        directory: &Path,
+    ) -> Result<(), ObjError> {
+        if geometry.normals.len() != geometry.vertex_count() {
+            geometry.generate_normals()?;
+        }
+        geometry.generate_tangents()?;
+
+        let instance = self.create_material_instance(material, mtl, directory)?;
+        let (vertex_buffer, index_buffer) = geometry.build(&mut self.engine)?;
+
+        let entity = EntityManager::get().create();
+        self.renderables.push(entity);
+        RenderableBuilder::new(1)?
+            .bounding_box(geometry.bounding_box())
+            .geometry(0, PrimitiveType::TRIANGLES, &vertex_buffer, &index_buffer)
+            .material(0, &instance)
+            .build(&mut self.engine, entity)?;
+
+        self.material_instances.push(instance);
+        self.vertex_buffers.push(vertex_buffer);
+        self.index_buffers.push(index_buffer);
+        Ok(())
+    }
+
+    fn create_material_instance(
+        &mut self,
+        material: &Material,
+        mtl: Option<&tobj::Material>,
+        directory: &Path,
+    ) -> Result<MaterialInstance, ObjError> {
+        let mut instance = material.create_instance()?;
+        let mut set = |name: &str, parameter: MaterialParameter| {
+            if material.has_parameter(name) {
+                instance.set(name, parameter);
+            }
+        };
+
+        let parameters = mtl.map(MtlParameters::from).unwrap_or_default();
+        set(
+            "baseColorFactor",
+            MaterialParameter::Float4(parameters.base_color.into()),
+        );
+        set("metallicFactor", parameters.metallic.into());
+        set("roughnessFactor", parameters.roughness.into());
+        set(
+            "emissiveFactor",
+            MaterialParameter::Float3(parameters.emissive.into()),
+        );
+
+        let maps = [
+            (
+                "baseColorMap",
+                mtl.and_then(|m| m.diffuse_texture.as_deref()),
+                true,
+            ),
+            (
+                "normalMap",
+                mtl.and_then(|m| m.normal_texture.as_deref()),
+                false,
+            ),
+        ];
+        let sampler = TextureSampler::builder()
+            .filter_mag(SamplerMagFilter::Linear)
+            .filter_min(SamplerMinFilter::LinearMipmapLinear)
+            .wrap(SamplerWrapMode::Repeat)
+            .build();
+        for (name, file, srgb) in maps.iter() {
+            if !material.has_parameter(name) {
+                continue;
+            }
+            let texture = self.load_map(*file, directory, *srgb)?;
+            instance.set(name, MaterialParameter::Texture(texture, &sampler));
+        }
+
+        Ok(instance)
+    }
+
+    /// Loads the texture map `file`, falling back to a white texture or a flat normal map when
+    /// there is none or when the `image` feature is disabled.
+    fn load_map(
+        &mut self,
+        file: Option<&str>,
+        directory: &Path,
+        srgb: bool,
+    ) -> Result<&Texture, ObjError> {
+        #[cfg(feature = "image")]
+        {
+            if let Some(file) = file {
+                use crate::image_loader::{load_texture, ColorSpace, ImageLoadOptions};
+
+                let options = ImageLoadOptions {
+                    color_space: if srgb {
+                        ColorSpace::Srgb
+                    } else {
+                        ColorSpace::Linear
+                    },
+                    ..ImageLoadOptions::default()
+                };
+                let texture = load_texture(&mut self.engine, directory.join(file), options)?;
+                self.textures.push(texture);
+                return Ok(self.textures.last().unwrap());
+            }
+        }
+        #[cfg(not(feature = "image"))]
+        let _ = (file, directory);
+
+        // Normal maps are the only linear maps
+        let color = if srgb {
+            [255, 255, 255, 255]
+        } else {
+            [128, 128, 255, 255]
+        };
+        let texture = Texture::solid_color(&mut self.engine, color)?;
+        self.textures.push(texture);
+        Ok(self.textures.last().unwrap())
+    }
+}
+
+/// Lit material parameters derived from an MTL material.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct MtlParameters {
+    base_color: [f32; 4],
+    metallic: f32,
+    roughness: f32,
+    emissive: [f32; 3],
+}
+impl Default for MtlParameters {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0; 4],
+            metallic: 0.0,
+            roughness: 1.0,
+            emissive: [0.0; 3],
+        }
+    }
+}
+impl From<&tobj::Material> for MtlParameters {
+    fn from(mtl: &tobj::Material) -> Self {
+        let unknown = |name: &str| mtl.unknown_param.get(name).map(String::as_str);
+        let float = |name: &str| unknown(name).and_then(|value| value.trim().parse::<f32>().ok());
+
+        let diffuse = mtl.diffuse.unwrap_or([1.0; 3]);
+        Self {
+            base_color: [
+                diffuse[0],
+                diffuse[1],
+                diffuse[2],
+                mtl.dissolve.unwrap_or(1.0),
+            ],
+            // The PBR extension of MTL, when present, is used as is
+            metallic: float("Pm").unwrap_or(0.0),
+            roughness: float("Pr")
+                .or_else(|| mtl.shininess.map(roughness_from_shininess))
+                .unwrap_or(1.0),
+            emissive: unknown("Ke")
+                .and_then(|value| {
+                    let mut components = value.split_whitespace().map(|c| c.parse::<f32>().ok());
+                    Some([
+                        components.next()??,
+                        components.next()??,
+                        components.next()??,
+                    ])
+                })
+                .unwrap_or([0.0; 3]),
+        }
+    }
+}
+
+/// Converts a Blinn-Phong specular exponent into a perceptual roughness.
+fn roughness_from_shininess(shininess: f32) -> f32 {
+    (2.0 / (shininess.max(0.0) + 2.0)).sqrt().min(1.0)
+}
+
+fn load_options() -> tobj::LoadOptions {
+    tobj::LoadOptions {
+        single_index: true,
+        triangulate: true,
+        ignore_points: true,
+        ignore_lines: true,
+        ..tobj::LoadOptions::default()
+    }
+}
+
+/// Merges the meshes of `models` sharing a material into a single geometry. Normals are left
+/// empty unless every merged mesh has them.
+fn group_by_material(models: &[tobj::Model]) -> BTreeMap<Option<usize>, Geometry> {
+    let mut groups = BTreeMap::<Option<usize>, (Geometry, bool)>::new();
+    for model in models.iter() {
+        let mesh = &model.mesh;
+        let (geometry, has_normals) = groups
+            .entry(mesh.material_id)
+            .or_insert_with(|| (Geometry::default(), true));
+
+        let base = geometry.vertex_count() as u32;
+        let count = mesh.positions.len() / 3;
+        geometry
+            .positions
+            .extend(mesh.positions.chunks_exact(3).map(|p| [p[0], p[1], p[2]]));
+        if mesh.texcoords.len() == count * 2 {
+            geometry
+                .uvs
+                .extend(mesh.texcoords.chunks_exact(2).map(|t| [t[0], t[1]]));
+        } else {
+            geometry.uvs.extend(std::iter::repeat([0.0; 2]).take(count));
+        }
+        if mesh.normals.len() == count * 3 {
+            geometry
+                .normals
+                .extend(mesh.normals.chunks_exact(3).map(|n| [n[0], n[1], n[2]]));
+        } else {
+            *has_normals = false;
+        }
+        geometry
+            .indices
+            .extend(mesh.indices.iter().map(|i| base + i));
+    }
+
+    groups
+        .into_iter()
+        .map(|(material, (mut geometry, has_normals))| {
+            if !has_normals {
+                geometry.normals.clear();
+            }
+            (material, geometry)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_by_material, load_options, roughness_from_shininess, MtlParameters};
+    use std::io::Cursor;
+
+    const OBJ: &str = "
+mtllib cube.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+o red
+usemtl red
+f 1/1 2/2 3/3 4/4
+o blue
+usemtl blue
+f 1/1 3/3 4/4
+o red_again
+usemtl red
+f 1/1 2/2 3/3
+";
+
+    const MTL: &str = "
+newmtl red
+Kd 1 0 0
+d 0.5
+Ns 2
+Ke 0.25 0.5 1
+newmtl blue
+Kd 0 0 1
+Pr 0.3
+Pm 1
+";
+
+    fn load() -> (Vec<tobj::Model>, Vec<tobj::Material>) {
+        let (models, materials) =
+            tobj::load_obj_buf(&mut Cursor::new(OBJ), &load_options(), |_| {
+                tobj::load_mtl_buf(&mut Cursor::new(MTL))
+            })
+            .unwrap();
+        (models, materials.unwrap())
+    }
+
+    #[test]
+    fn obj_grouping() {
+        let (models, materials) = load();
+        let groups = group_by_material(&models);
+        assert_eq!(groups.len(), 2);
+
+        let red = materials.iter().position(|m| m.name == "red").unwrap();
+        let red = &groups[&Some(red)];
+        // The quad is triangulated, and the second red mesh is appended after it
+        assert_eq!(red.index_count(), 9);
+        assert_eq!(red.uvs.len(), red.vertex_count());
+        assert!(red.normals.is_empty());
+        assert!(red
+            .indices
+            .iter()
+            .all(|i| (*i as usize) < red.vertex_count()));
+        assert_eq!(red.indices[6..].iter().min(), Some(&4));
+    }
+
+    #[test]
+    fn mtl_parameters() {
+        let (_, materials) = load();
+
+        let red = MtlParameters::from(&materials[0]);
+        assert_eq!(red.base_color, [1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(red.roughness, roughness_from_shininess(2.0));
+        assert_eq!(red.metallic, 0.0);
+        assert_eq!(red.emissive, [0.25, 0.5, 1.0]);
+
+        let blue = MtlParameters::from(&materials[1]);
+        assert_eq!(blue.roughness, 0.3);
+        assert_eq!(blue.metallic, 1.0);
+        assert_eq!(blue.emissive, [0.0; 3]);
+
+        assert_eq!(roughness_from_shininess(0.0), 1.0);
+        assert!(roughness_from_shininess(1000.0) < 0.05);
+    }
+}
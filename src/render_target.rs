@@ -0,0 +1,123 @@
+use filament_sys::ffi;
+use std::{convert::TryInto, sync::Arc};
+
+use crate::{
+    engine::{Engine, EngineError},
+    impl_ptr_functions,
+    texture::{CubeMapFace, Texture},
+};
+
+/// A set of textures a `View` renders into instead of its swap chain.
+///
+/// The attached textures are kept alive for as long as the render target.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RenderTarget {
+    engine: Engine,
+    ptr: Arc<*mut ffi::filament_RenderTarget>,
+    color: Option<Texture>,
+    depth: Option<Texture>,
+}
+impl_ptr_functions!(RenderTarget, ffi::filament_RenderTarget);
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
+            log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+            unsafe { self.engine.as_raw_mut().destroy14(*ptr) }
+        }
+    }
+}
+impl RenderTarget {
+    #[inline]
+    pub fn builder<'a>() -> RenderTargetBuilder<'a> {
+        RenderTargetBuilder::default()
+    }
+
+    #[inline]
+    pub fn color(&self) -> Option<&Texture> {
+        self.color.as_ref()
+    }
+
+    #[inline]
+    pub fn depth(&self) -> Option<&Texture> {
+        self.depth.as_ref()
+    }
+}
+
+/// Selects the textures of a `RenderTarget`, and the mip level, cubemap face or array layer of
+/// them to render into.
+pub struct RenderTargetBuilder<'a> {
+    color: Option<&'a Texture>,
+    depth: Option<&'a Texture>,
+    level: usize,
+    face: CubeMapFace,
+    layer: u32,
+}
+impl<'a> Default for RenderTargetBuilder<'a> {
+    fn default() -> Self {
+        Self {
+            color: None,
+            depth: None,
+            level: 0,
+            face: CubeMapFace::POSITIVE_X,
+            layer: 0,
+        }
+    }
+}
+impl<'a> RenderTargetBuilder<'a> {
+    /// The color attachment, which needs the `COLOR_ATTACHMENT` usage.
+    #[inline]
+    pub fn color(mut self, texture: &'a Texture) -> Self {
+        self.color = Some(texture);
+        self
+    }
+
+    /// The depth attachment, which needs the `DEPTH_ATTACHMENT` usage.
+    #[inline]
+    pub fn depth(mut self, texture: &'a Texture) -> Self {
+        self.depth = Some(texture);
+        self
+    }
+
+    #[inline]
+    pub fn mip_level(mut self, level: usize) -> Self {
+        self.level = level;
+        self
+    }
+
+    #[inline]
+    pub fn face(mut self, face: CubeMapFace) -> Self {
+        self.face = face;
+        self
+    }
+
+    #[inline]
+    pub fn layer(mut self, layer: u32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn build(self, engine: &mut Engine) -> Result<RenderTarget, EngineError> {
+        let ptr = unsafe {
+            ffi::helpers_render_target_build(
+                engine.as_raw_ptr(),
+                self.color
+                    .map_or(std::ptr::null_mut(), |texture| texture.as_raw_ptr()),
+                self.depth
+                    .map_or(std::ptr::null_mut(), |texture| texture.as_raw_ptr()),
+                self.level.try_into().unwrap(),
+                self.face,
+                self.layer,
+            )
+        };
+        if ptr.is_null() {
+            Err(EngineError::CreationFailed)
+        } else {
+            Ok(RenderTarget {
+                engine: engine.clone(),
+                ptr: Arc::new(ptr),
+                color: self.color.cloned(),
+                depth: self.depth.cloned(),
+            })
+        }
+    }
+}
@@ -1,20 +1,233 @@
 use crate::{
-    buffers::{IndexBuffer, VertexBuffer},
+    buffers::{IndexBuffer, VertexAttribute, VertexBuffer},
     material::MaterialInstance,
-    Engine, EngineError,
+    Engine, EngineError, Entity,
+};
+use filament_sys::{ffi, Float3, Mat4f, Quatf};
+use std::{
+    convert::TryInto,
+    ops::{Range, RangeInclusive},
 };
-use filament_sys::ffi;
-use std::convert::TryInto;
 use thiserror::Error;
 
 pub use ffi::filament_backend_PrimitiveType as PrimitiveType;
 
+pub type RenderableInstance = u32;
+
 #[derive(Error, Debug)]
 pub enum RenderableBuildError {
     #[error("Creation of a type failed")]
     CreationFailed,
 }
 
+#[derive(Error, Debug)]
+pub enum MorphError {
+    #[error(
+        "{0} morph targets requested, at most {} are supported",
+        MAX_MORPH_TARGETS
+    )]
+    TooManyTargets(usize),
+    #[error("Morph target {target} is out of range for {count} targets")]
+    InvalidTarget { target: usize, count: usize },
+    #[error("Expected {expected} morph weights, got {actual}")]
+    WeightCountMismatch { expected: usize, actual: usize },
+    #[error("Expected {expected} vertices for the morph target, got {actual}")]
+    VertexCountMismatch { expected: usize, actual: usize },
+}
+
+/// The maximum number of morph targets a renderable can blend between.
+pub const MAX_MORPH_TARGETS: usize = 4;
+
+const MORPH_POSITION_ATTRIBUTES: [VertexAttribute; MAX_MORPH_TARGETS] = [
+    VertexAttribute::MORPH_POSITION_0,
+    VertexAttribute::MORPH_POSITION_1,
+    VertexAttribute::MORPH_POSITION_2,
+    VertexAttribute::MORPH_POSITION_3,
+];
+
+const MORPH_TANGENTS_ATTRIBUTES: [VertexAttribute; MAX_MORPH_TARGETS] = [
+    VertexAttribute::MORPH_TANGENTS_0,
+    VertexAttribute::MORPH_TANGENTS_1,
+    VertexAttribute::MORPH_TANGENTS_2,
+    VertexAttribute::MORPH_TANGENTS_3,
+];
+
+/// Describes how the morph targets of a renderable are laid out in its vertex buffer.
+///
+/// Each target uses two buffers starting at `first_buffer`, one holding the float3 positions and
+/// one holding the float4 tangent quaternions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MorphTargets {
+    count: usize,
+    first_buffer: usize,
+}
+impl MorphTargets {
+    pub fn new(count: usize, first_buffer: usize) -> Result<Self, MorphError> {
+        if count > MAX_MORPH_TARGETS {
+            Err(MorphError::TooManyTargets(count))
+        } else {
+            Ok(Self {
+                count,
+                first_buffer,
+            })
+        }
+    }
+
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The number of vertex buffers used by the morph targets.
+    #[inline]
+    pub fn buffer_count(&self) -> usize {
+        self.count * 2
+    }
+
+    #[inline]
+    pub fn positions_buffer(&self, target: usize) -> usize {
+        self.first_buffer + target * 2
+    }
+
+    #[inline]
+    pub fn tangents_buffer(&self, target: usize) -> usize {
+        self.first_buffer + target * 2 + 1
+    }
+
+    pub(crate) fn check_target(&self, target: usize) -> Result<(), MorphError> {
+        if target < self.count {
+            Ok(())
+        } else {
+            Err(MorphError::InvalidTarget {
+                target,
+                count: self.count,
+            })
+        }
+    }
+
+    /// The attribute of the positions of `target`, which must be below `MAX_MORPH_TARGETS`.
+    #[inline]
+    pub(crate) fn position_attribute(target: usize) -> VertexAttribute {
+        MORPH_POSITION_ATTRIBUTES[target]
+    }
+
+    /// The attribute of the tangents of `target`, which must be below `MAX_MORPH_TARGETS`.
+    #[inline]
+    pub(crate) fn tangents_attribute(target: usize) -> VertexAttribute {
+        MORPH_TANGENTS_ATTRIBUTES[target]
+    }
+}
+
+/// A bone transform expressed as a rotation followed by a translation, matching Filament's
+/// `RenderableManager::Bone` layout.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bone {
+    pub unit_quaternion: Quatf,
+    pub translation: Float3,
+    pub reserved: f32,
+}
+impl Bone {
+    #[inline]
+    pub fn new(unit_quaternion: Quatf, translation: Float3) -> Self {
+        Self {
+            unit_quaternion,
+            translation,
+            reserved: 0.0,
+        }
+    }
+}
+impl Default for Bone {
+    fn default() -> Self {
+        Self::new(Quatf::IDENTITY, Float3::default())
+    }
+}
+
+/// An axis aligned bounding box, described by its center and half extent in object space.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct BoundingBox {
+    pub center: Float3,
+    pub half_extent: Float3,
+}
+impl BoundingBox {
+    #[inline]
+    pub fn new(center: Float3, half_extent: Float3) -> Self {
+        Self {
+            center,
+            half_extent,
+        }
+    }
+
+    pub fn from_min_max(min: Float3, max: Float3) -> Self {
+        let mut center = Float3::default();
+        let mut half_extent = Float3::default();
+        for i in 0..3 {
+            center[i] = (min[i] + max[i]) * 0.5;
+            half_extent[i] = (max[i] - min[i]) * 0.5;
+        }
+        Self::new(center, half_extent)
+    }
+
+    /// Computes the box enclosing all the given positions. An empty slice yields an empty box at the origin.
+    pub fn from_positions(positions: &[[f32; 3]]) -> Self {
+        Self::from_iter(positions.iter().copied())
+    }
+
+    /// Computes the box enclosing a float3 position attribute stored in a (possibly interleaved)
+    /// vertex buffer, using the same byte `offset` and `stride` given to `VertexBufferBuilder::attribute`.
+    pub fn from_attribute<T: Sized>(data: &[T], offset: usize, stride: usize) -> Self {
+        const POSITION_SIZE: usize = std::mem::size_of::<[f32; 3]>();
+
+        let bytes = std::mem::size_of_val(data);
+        let count = if stride == 0 || bytes < offset + POSITION_SIZE {
+            0
+        } else {
+            (bytes - offset - POSITION_SIZE) / stride + 1
+        };
+        let base = data.as_ptr() as *const u8;
+
+        Self::from_iter((0..count).map(|i| unsafe {
+            std::ptr::read_unaligned(base.add(i * stride + offset) as *const [f32; 3])
+        }))
+    }
+
+    #[inline]
+    pub fn min(&self) -> Float3 {
+        Float3::new(
+            self.center.x - self.half_extent.x,
+            self.center.y - self.half_extent.y,
+            self.center.z - self.half_extent.z,
+        )
+    }
+
+    #[inline]
+    pub fn max(&self) -> Float3 {
+        Float3::new(
+            self.center.x + self.half_extent.x,
+            self.center.y + self.half_extent.y,
+            self.center.z + self.half_extent.z,
+        )
+    }
+
+    fn from_iter<I>(positions: I) -> Self
+    where
+        I: Iterator<Item = [f32; 3]>,
+    {
+        let mut bounds: Option<([f32; 3], [f32; 3])> = None;
+        for position in positions {
+            let (min, max) = bounds.get_or_insert((position, position));
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+        }
+
+        bounds
+            .map(|(min, max)| Self::from_min_max(min.into(), max.into()))
+            .unwrap_or_default()
+    }
+}
+
 pub struct RenderableBuilder<'a> {
     ptr: *mut ffi::helpers_BuilderProxy,
     _marker: std::marker::PhantomData<&'a ()>,
@@ -57,6 +270,99 @@ impl<'a> RenderableBuilder<'a> {
         self
     }
 
+    /// Like `geometry`, but only draws the indices in `range`, which allows several primitives to
+    /// share the same buffers.
+    pub fn geometry_range(
+        self,
+        index: usize,
+        primitive_type: PrimitiveType,
+        vertices: &'a VertexBuffer,
+        indices: &'a IndexBuffer,
+        range: Range<usize>,
+    ) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_geometry_range(
+                self.ptr,
+                index.try_into().unwrap(),
+                primitive_type,
+                vertices.as_raw_ptr(),
+                indices.as_raw_ptr(),
+                range.start.try_into().unwrap(),
+                range.len().try_into().unwrap(),
+            );
+        }
+
+        self
+    }
+
+    /// Like `geometry_range`, additionally giving the smallest and largest vertex index referenced
+    /// by the range in `vertex_range`.
+    pub fn geometry_range_bounded(
+        self,
+        index: usize,
+        primitive_type: PrimitiveType,
+        vertices: &'a VertexBuffer,
+        indices: &'a IndexBuffer,
+        range: Range<usize>,
+        vertex_range: RangeInclusive<usize>,
+    ) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_geometry_range_bounded(
+                self.ptr,
+                index.try_into().unwrap(),
+                primitive_type,
+                vertices.as_raw_ptr(),
+                indices.as_raw_ptr(),
+                range.start.try_into().unwrap(),
+                (*vertex_range.start()).try_into().unwrap(),
+                (*vertex_range.end()).try_into().unwrap(),
+                range.len().try_into().unwrap(),
+            );
+        }
+
+        self
+    }
+
+    pub fn bounding_box(self, bounding_box: BoundingBox) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_boundingBox(
+                self.ptr,
+                &bounding_box.center as *const Float3 as *const f32,
+                &bounding_box.half_extent as *const Float3 as *const f32,
+            );
+        }
+        self
+    }
+
+    /// Sets the bounding box from the positions of the vertices, see `BoundingBox::from_positions`.
+    pub fn bounding_box_from_positions(self, positions: &[[f32; 3]]) -> Self {
+        self.bounding_box(BoundingBox::from_positions(positions))
+    }
+
+    /// Sets the visibility layers of this renderable; only the bits set in `select` are replaced by `values`.
+    pub fn layer_mask(self, select: u8, values: u8) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_layerMask(self.ptr, select, values);
+        }
+        self
+    }
+
+    /// Sets the drawing priority, between 0 (drawn first) and 7 (drawn last).
+    pub fn priority(self, priority: u8) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_priority(self.ptr, priority);
+        }
+        self
+    }
+
+    /// Sets the order in which blended primitives of this renderable are drawn.
+    pub fn blend_order(self, index: usize, order: u16) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_blendOrder(self.ptr, index.try_into().unwrap(), order);
+        }
+        self
+    }
+
     pub fn culling(self, value: bool) -> Self {
         unsafe {
             ffi::helpers_renderable_builder_culling(self.ptr, value);
@@ -87,6 +393,40 @@ impl<'a> RenderableBuilder<'a> {
         }
         self
     }
+    /// Enables morphing when `targets` holds at least one morph target.
+    pub fn morph_targets(self, targets: &MorphTargets) -> Self {
+        self.morphing(targets.count() > 0)
+    }
+
+    /// Enables skinning with `bone_count` bones, all initialized to the identity.
+    pub fn skinning(self, bone_count: usize) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_skinning(self.ptr, bone_count.try_into().unwrap());
+        }
+        self
+    }
+    /// Enables skinning with one bone per entry of `bones`, using them as the initial transforms.
+    pub fn skinning_with_bones(self, bones: &'a [Bone]) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_skinning_bones(
+                self.ptr,
+                bones.len().try_into().unwrap(),
+                bones.as_ptr() as *const _,
+            );
+        }
+        self
+    }
+    /// Enables skinning with one bone per entry of `transforms`, using them as the initial transforms.
+    pub fn skinning_with_matrices(self, transforms: &'a [Mat4f]) -> Self {
+        unsafe {
+            ffi::helpers_renderable_builder_skinning_matrices(
+                self.ptr,
+                transforms.len().try_into().unwrap(),
+                transforms.as_ptr() as *const _,
+            );
+        }
+        self
+    }
 
     pub fn build(
         self,
@@ -108,8 +448,353 @@ impl<'a> RenderableBuilder<'a> {
         } else {
             Ok(Self {
                 ptr,
-                _marker: std::marker::PhantomData::default(),
+                _marker: std::marker::PhantomData,
+            })
+        }
+    }
+}
+
+// No drop, we get this as a ref from the engine
+pub struct RenderableManager<'a> {
+    ptr: *mut ffi::filament_RenderableManager,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+impl<'a> RenderableManager<'a> {
+    pub fn get_instance(&self, entity: Entity) -> Option<RenderableInstance> {
+        let instance = unsafe { ffi::helpers_renderable_manager_getInstance(self.ptr, entity) };
+        if instance == 0 {
+            None
+        } else {
+            Some(instance)
+        }
+    }
+
+    pub fn has_component(&self, entity: Entity) -> bool {
+        unsafe { ffi::helpers_renderable_manager_hasComponent(self.ptr, entity) }
+    }
+
+    /// Removes the renderable component from `entity`, the entity itself is left alive.
+    pub fn destroy(&mut self, entity: Entity) {
+        unsafe { ffi::helpers_renderable_manager_destroy(self.ptr, entity) }
+    }
+
+    pub fn set_material_instance_at(
+        &mut self,
+        instance: RenderableInstance,
+        primitive_index: usize,
+        material: &MaterialInstance,
+    ) {
+        unsafe {
+            ffi::helpers_renderable_manager_setMaterialInstanceAt(
+                self.ptr,
+                instance,
+                primitive_index.try_into().unwrap(),
+                material.as_raw_ptr(),
+            )
+        }
+    }
+
+    /// Replaces the buffers of a primitive, drawing the indices in `range`.
+    pub fn set_geometry_at(
+        &mut self,
+        instance: RenderableInstance,
+        primitive_index: usize,
+        primitive_type: PrimitiveType,
+        vertices: &VertexBuffer,
+        indices: &IndexBuffer,
+        range: Range<usize>,
+    ) {
+        unsafe {
+            ffi::helpers_renderable_manager_setGeometryAt(
+                self.ptr,
+                instance,
+                primitive_index.try_into().unwrap(),
+                primitive_type,
+                vertices.as_raw_ptr(),
+                indices.as_raw_ptr(),
+                range.start.try_into().unwrap(),
+                range.len().try_into().unwrap(),
+            )
+        }
+    }
+
+    pub fn set_axis_aligned_bounding_box(
+        &mut self,
+        instance: RenderableInstance,
+        bounding_box: BoundingBox,
+    ) {
+        unsafe {
+            ffi::helpers_renderable_manager_setAxisAlignedBoundingBox(
+                self.ptr,
+                instance,
+                &bounding_box.center as *const Float3 as *const f32,
+                &bounding_box.half_extent as *const Float3 as *const f32,
+            )
+        }
+    }
+
+    pub fn set_layer_mask(&mut self, instance: RenderableInstance, select: u8, values: u8) {
+        unsafe { ffi::helpers_renderable_manager_setLayerMask(self.ptr, instance, select, values) }
+    }
+
+    pub fn set_cast_shadows(&mut self, instance: RenderableInstance, value: bool) {
+        unsafe { ffi::helpers_renderable_manager_setCastShadows(self.ptr, instance, value) }
+    }
+    pub fn is_shadow_caster(&self, instance: RenderableInstance) -> bool {
+        unsafe { ffi::helpers_renderable_manager_isShadowCaster(self.ptr, instance) }
+    }
+
+    pub fn set_receive_shadows(&mut self, instance: RenderableInstance, value: bool) {
+        unsafe { ffi::helpers_renderable_manager_setReceiveShadows(self.ptr, instance, value) }
+    }
+    pub fn is_shadow_receiver(&self, instance: RenderableInstance) -> bool {
+        unsafe { ffi::helpers_renderable_manager_isShadowReceiver(self.ptr, instance) }
+    }
+
+    pub fn get_primitive_count(&self, instance: RenderableInstance) -> usize {
+        unsafe {
+            ffi::helpers_renderable_manager_getPrimitiveCount(self.ptr, instance)
+                .try_into()
+                .unwrap()
+        }
+    }
+
+    /// Updates the bones of a skinned renderable, starting at bone `offset`.
+    ///
+    /// Filament does not expose the bone count of a renderable, so `offset + bones.len()` must not
+    /// exceed the count it was built with; Filament aborts otherwise.
+    pub fn set_bones(&mut self, instance: RenderableInstance, bones: &[Bone], offset: usize) {
+        unsafe {
+            ffi::helpers_renderable_manager_setBones(
+                self.ptr,
+                instance,
+                bones.as_ptr() as *const _,
+                bones.len().try_into().unwrap(),
+                offset.try_into().unwrap(),
+            )
+        }
+    }
+
+    /// Updates the bones of a skinned renderable from matrices, starting at bone `offset`, with
+    /// the same bounds as `set_bones`.
+    pub fn set_bone_matrices(
+        &mut self,
+        instance: RenderableInstance,
+        transforms: &[Mat4f],
+        offset: usize,
+    ) {
+        unsafe {
+            ffi::helpers_renderable_manager_setBones_matrices(
+                self.ptr,
+                instance,
+                transforms.as_ptr() as *const _,
+                transforms.len().try_into().unwrap(),
+                offset.try_into().unwrap(),
+            )
+        }
+    }
+
+    /// Sets the per-frame blend weights of a morphing renderable, one weight per target.
+    pub fn set_morph_weights(
+        &mut self,
+        instance: RenderableInstance,
+        targets: &MorphTargets,
+        weights: &[f32],
+    ) -> Result<(), MorphError> {
+        if weights.len() != targets.count() {
+            return Err(MorphError::WeightCountMismatch {
+                expected: targets.count(),
+                actual: weights.len(),
+            });
+        }
+
+        let mut padded = [0.0f32; MAX_MORPH_TARGETS];
+        padded[..weights.len()].copy_from_slice(weights);
+        unsafe {
+            ffi::helpers_renderable_manager_setMorphWeights(self.ptr, instance, padded.as_ptr())
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn new(engine: &'a Engine) -> Result<RenderableManager<'a>, EngineError> {
+        let ptr = unsafe { ffi::filament_Engine_getRenderableManager(engine.as_raw_ptr()) };
+        if ptr.is_null() {
+            Err(EngineError::CreationFailed)
+        } else {
+            Ok(Self {
+                ptr,
+                _marker: Default::default(),
             })
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Bone, BoundingBox, MorphError, MorphTargets, PrimitiveType, RenderableBuilder};
+    use crate::{
+        buffers::{AttributeType, IndexBuffer, IndexType, VertexAttribute, VertexBuffer},
+        engine::Engine,
+        entity_manager::EntityManager,
+        geometry::Geometry,
+        Backend,
+    };
+    use filament_sys::{Float3, Quatf};
+
+    #[test]
+    fn renderable_manager_edit() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let (vertices, indices) = Geometry::cube(1.0, 1).build(&mut engine).unwrap();
+
+        let entity = EntityManager::get().create();
+        RenderableBuilder::new(1)
+            .unwrap()
+            .geometry(0, PrimitiveType::TRIANGLES, &vertices, &indices)
+            .cast_shadows(false)
+            .build(&mut engine, entity)
+            .unwrap();
+
+        let mut manager = engine.renderable_manager().unwrap();
+        let instance = manager.get_instance(entity).unwrap();
+        assert_eq!(manager.get_primitive_count(instance), 1);
+        assert!(!manager.is_shadow_caster(instance));
+
+        manager.set_cast_shadows(instance, true);
+        assert!(manager.is_shadow_caster(instance));
+
+        manager.destroy(entity);
+        assert!(!manager.has_component(entity));
+        assert!(manager.get_instance(entity).is_none());
+    }
+
+    #[test]
+    fn morph_targets_layout() {
+        assert!(matches!(
+            MorphTargets::new(5, 0),
+            Err(MorphError::TooManyTargets(5))
+        ));
+
+        let targets = MorphTargets::new(2, 3).unwrap();
+        assert_eq!(targets.buffer_count(), 4);
+        assert_eq!(targets.positions_buffer(1), 5);
+        assert_eq!(targets.tangents_buffer(1), 6);
+        assert!(targets.check_target(1).is_ok());
+        assert!(targets.check_target(2).is_err());
+    }
+
+    #[test]
+    fn morph_data_mismatch() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let targets = MorphTargets::new(2, 1).unwrap();
+        let mut vertices = VertexBuffer::builder()
+            .vertex_count(3)
+            .buffer_count(1 + targets.buffer_count())
+            .attribute(VertexAttribute::POSITION, 0, AttributeType::FLOAT3, 0, 12)
+            .morph_targets(&targets)
+            .build(&mut engine)
+            .unwrap();
+        let indices = IndexBuffer::builder()
+            .index_count(3)
+            .ty(IndexType::USHORT)
+            .build(&mut engine)
+            .unwrap();
+
+        assert!(vertices
+            .write_morph_target(&targets, 1, vec![[0.0; 3]; 3], vec![[0.0; 4]; 3])
+            .is_ok());
+        assert!(matches!(
+            vertices.write_morph_target(&targets, 0, vec![[0.0; 3]; 2], vec![[0.0; 4]; 3]),
+            Err(MorphError::VertexCountMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+        assert!(matches!(
+            vertices.write_morph_target(&targets, 0, vec![[0.0; 3]; 3], vec![[0.0; 4]; 4]),
+            Err(MorphError::VertexCountMismatch {
+                expected: 3,
+                actual: 4
+            })
+        ));
+        assert!(matches!(
+            vertices.write_morph_target(&targets, 2, vec![[0.0; 3]; 3], vec![[0.0; 4]; 3]),
+            Err(MorphError::InvalidTarget {
+                target: 2,
+                count: 2
+            })
+        ));
+
+        let entity = EntityManager::get().create();
+        RenderableBuilder::new(1)
+            .unwrap()
+            .geometry(0, PrimitiveType::TRIANGLES, &vertices, &indices)
+            .morph_targets(&targets)
+            .build(&mut engine, entity)
+            .unwrap();
+
+        let mut manager = engine.renderable_manager().unwrap();
+        let instance = manager.get_instance(entity).unwrap();
+        assert!(manager
+            .set_morph_weights(instance, &targets, &[0.5, 0.5])
+            .is_ok());
+        assert!(matches!(
+            manager.set_morph_weights(instance, &targets, &[1.0]),
+            Err(MorphError::WeightCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(matches!(
+            manager.set_morph_weights(instance, &targets, &[0.0; 3]),
+            Err(MorphError::WeightCountMismatch {
+                expected: 2,
+                actual: 3
+            })
+        ));
+        manager.destroy(entity);
+    }
+
+    #[test]
+    fn bone_layout() {
+        assert_eq!(std::mem::size_of::<Bone>(), 32);
+        assert_eq!(Bone::default().unit_quaternion, Quatf::IDENTITY);
+    }
+
+    #[test]
+    fn bounding_box_from_positions() {
+        let bounds =
+            BoundingBox::from_positions(&[[1.0, 0.0, -2.0], [-1.0, 4.0, 2.0], [0.0, 1.0, 0.0]]);
+        assert_eq!(bounds.center, Float3::new(0.0, 2.0, 0.0));
+        assert_eq!(bounds.half_extent, Float3::new(1.0, 2.0, 2.0));
+        assert_eq!(bounds.min(), Float3::new(-1.0, 0.0, -2.0));
+        assert_eq!(bounds.max(), Float3::new(1.0, 4.0, 2.0));
+
+        assert_eq!(BoundingBox::from_positions(&[]), BoundingBox::default());
+    }
+
+    #[test]
+    fn bounding_box_from_interleaved_attribute() {
+        #[repr(C)]
+        struct Vertex {
+            uv: [f32; 2],
+            position: [f32; 3],
+        }
+        let vertices = vec![
+            Vertex {
+                uv: [9.0, 9.0],
+                position: [0.0, 0.0, 0.0],
+            },
+            Vertex {
+                uv: [-9.0, -9.0],
+                position: [2.0, 2.0, 2.0],
+            },
+        ];
+
+        let bounds = BoundingBox::from_attribute(&vertices, 8, 20);
+        assert_eq!(
+            bounds,
+            BoundingBox::new(Float3::new(1.0, 1.0, 1.0), Float3::new(1.0, 1.0, 1.0))
+        );
+    }
+}
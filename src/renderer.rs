@@ -1,12 +1,26 @@
 use filament_sys::ffi;
-use std::sync::Arc;
+use std::{
+    convert::TryInto,
+    hash::{Hash, Hasher},
+    os::raw::c_void,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Instant,
+};
+use thiserror::Error;
 
 use crate::{
     engine::{Engine, EngineError},
-    impl_handle,
+    frame_stats::{FrameHistory, FrameStats},
+    impl_ptr_functions,
+    render_target::RenderTarget,
+    surface::Surface,
     swapchain::SwapChain,
+    texture::{PixelDataFormat, PixelDataType, TextureError, TextureRegion},
     view::View,
-    Viewport,
+    Backend, Viewport,
 };
 
 pub use ffi::filament_Renderer_ClearOptions as ClearOptions;
@@ -21,7 +35,102 @@ bitflags::bitflags! {
     }
 }
 
-impl_handle!(Renderer, ffi::filament_Renderer);
+#[derive(Error, Debug)]
+pub enum RenderError {
+    #[error("Present hooks are only supported by the Metal backend, not {0:?}")]
+    PresentHookUnsupported(Backend),
+}
+
+/// Whether `Renderer::render_frame` drew a frame, or skipped it because the GPU was falling
+/// behind.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FrameStatus {
+    Rendered,
+    Skipped,
+}
+impl FrameStatus {
+    #[inline]
+    pub fn is_rendered(self) -> bool {
+        self == FrameStatus::Rendered
+    }
+}
+
+/// Schedules a finished frame for presentation. Filament hands one out per frame when a
+/// `PresentHook` is given to `Renderer::render_frame_with`.
+///
+/// Dropping it without calling `present` or `cancel` presents the frame, so that no frame is ever
+/// left waiting.
+pub struct PresentCallable(Option<ffi::filament_backend_PresentCallable>);
+unsafe impl Send for PresentCallable {}
+impl PresentCallable {
+    #[inline]
+    pub fn present(mut self) {
+        self.call(true)
+    }
+
+    /// Drops the frame instead of presenting it, releasing its resources.
+    #[inline]
+    pub fn cancel(mut self) {
+        self.call(false)
+    }
+
+    fn call(&mut self, present_frame: bool) {
+        if let Some(callable) = self.0.take() {
+            if let Some(present_fn) = callable.mPresentFn {
+                unsafe { present_fn(present_frame, callable.mUser) }
+            }
+        }
+    }
+}
+impl Drop for PresentCallable {
+    fn drop(&mut self) {
+        self.call(true)
+    }
+}
+
+/// Called with the `PresentCallable` of every frame rendered with it, on an arbitrary thread, to
+/// present frames manually or record when they are presented.
+///
+/// Only Filament's Metal backend supports presentation callbacks. Other backends present frames
+/// themselves in `end_frame`, so `Renderer::render_frame_with` refuses hooks there.
+#[derive(Clone)]
+pub struct PresentHook(Arc<PresentHookFn>);
+impl PresentHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: FnMut(PresentCallable) + Send + 'static,
+    {
+        Self(Arc::new(Mutex::new(Box::new(hook))))
+    }
+}
+
+/// Records the stats of the frame being submitted into the history.
+struct FrameRecorder {
+    history: FrameHistory,
+    current: Option<FrameStats>,
+    frame_interval: u8,
+}
+
+/// Renders views into swap chains, recording the CPU time and contents of every frame into a
+/// `FrameHistory`, which is shared between clones.
+#[derive(Clone)]
+pub struct Renderer {
+    engine: Engine,
+    ptr: Arc<*mut ffi::filament_Renderer>,
+    recorder: Arc<Mutex<FrameRecorder>>,
+}
+impl_ptr_functions!(Renderer, ffi::filament_Renderer);
+impl PartialEq for Renderer {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+impl Eq for Renderer {}
+impl Hash for Renderer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ptr.hash(state)
+    }
+}
 impl Drop for Renderer {
     fn drop(&mut self) {
         if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
@@ -40,6 +149,7 @@ impl Renderer {
             self.as_raw_mut()
                 .setFrameRateOptions(&frame_rate_options as *const _)
         }
+        self.recorder().frame_interval = frame_rate_options.interval;
     }
 
     pub fn set_clear_options(&mut self, clear_options: ClearOptions) {
@@ -57,7 +167,43 @@ impl Renderer {
     }
 
     pub fn render(&mut self, view: &View) {
+        let start = Instant::now();
         unsafe { self.as_raw_mut().render(view.as_raw_ptr() as _) }
+        let elapsed = start.elapsed();
+
+        let scene = unsafe { ffi::filament_View_getScene(view.as_raw_ptr()) };
+        let renderables = if scene.is_null() {
+            0
+        } else {
+            unsafe { (*scene).getRenderableCount().try_into().unwrap() }
+        };
+        if let Some(current) = &mut self.recorder().current {
+            current.render += elapsed;
+            current.views += 1;
+            current.scene_renderables += renderables;
+        }
+    }
+
+    /// The stats of the most recent frames. Frames are recorded by `begin_frame`, `render` and
+    /// `end_frame`, and so by `render_frame`.
+    pub fn frame_history(&self) -> FrameHistory {
+        self.recorder().history.clone()
+    }
+
+    /// Sets how many frames the history keeps, which is `FrameHistory::DEFAULT_CAPACITY` by
+    /// default.
+    pub fn set_frame_history_capacity(&mut self, capacity: usize) {
+        self.recorder().history.set_capacity(capacity)
+    }
+
+    pub fn reset_frame_history(&mut self) {
+        self.recorder().history.clear()
+    }
+
+    fn recorder(&self) -> MutexGuard<'_, FrameRecorder> {
+        self.recorder
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 
     pub fn copy_frame(
@@ -77,23 +223,183 @@ impl Renderer {
         }
     }
 
+    #[must_use = "nothing may be rendered for the frame unless it returns true"]
     pub fn begin_frame(
         &mut self,
         swapchain: &mut SwapChain,
         vsync_steady_clock_time_nano: u64,
     ) -> bool {
-        unsafe {
+        self.begin_frame_with(
+            swapchain,
+            vsync_steady_clock_time_nano,
+            None,
+            std::ptr::null_mut(),
+        )
+    }
+
+    fn begin_frame_with(
+        &mut self,
+        swapchain: &mut SwapChain,
+        vsync_steady_clock_time_nano: u64,
+        callback: ffi::filament_backend_FrameFinishedCallback,
+        user: *mut c_void,
+    ) -> bool {
+        let start = Instant::now();
+        let accepted = unsafe {
             self.as_raw_mut().beginFrame(
                 swapchain.as_raw_ptr(),
                 vsync_steady_clock_time_nano,
-                None,
-                std::ptr::null_mut(),
+                callback,
+                user,
             )
+        };
+        let elapsed = start.elapsed();
+
+        let mut recorder = self.recorder();
+        let stats = FrameStats {
+            begin: elapsed,
+            skipped: !accepted,
+            frame_interval: recorder.frame_interval,
+            ..FrameStats::default()
+        };
+        if accepted {
+            recorder.current = Some(stats);
+        } else {
+            recorder.current = None;
+            recorder.history.push(stats);
         }
+        accepted
     }
 
     pub fn end_frame(&mut self) {
+        let start = Instant::now();
         unsafe { self.as_raw_mut().endFrame() }
+        let elapsed = start.elapsed();
+
+        let mut recorder = self.recorder();
+        if let Some(mut stats) = recorder.current.take() {
+            stats.end = elapsed;
+            recorder.history.push(stats);
+        }
+    }
+
+    /// Renders `views` into `swap_chain` as one frame, unless Filament skips it.
+    #[inline]
+    pub fn render_frame(&mut self, swap_chain: &mut SwapChain, views: &[&View]) -> FrameStatus {
+        self.render_frame_hooked(swap_chain, views, 0, None)
+    }
+
+    /// Renders `views` into `swap_chain` as one frame, unless Filament skips it.
+    ///
+    /// `vsync_steady_clock_time_nano` is the time of the vsync the frame is for, or 0 for now. If
+    /// `present` is given, the finished frame is handed to it instead of being presented, which
+    /// fails with `RenderError::PresentHookUnsupported` on backends other than Metal.
+    pub fn render_frame_with(
+        &mut self,
+        swap_chain: &mut SwapChain,
+        views: &[&View],
+        vsync_steady_clock_time_nano: u64,
+        present: Option<&PresentHook>,
+    ) -> Result<FrameStatus, RenderError> {
+        // Other backends never call the frame finished callback, which would leak the hook
+        let backend = self.engine.backend();
+        if present.is_some() && backend != Backend::METAL {
+            return Err(RenderError::PresentHookUnsupported(backend));
+        }
+        Ok(self.render_frame_hooked(swap_chain, views, vsync_steady_clock_time_nano, present))
+    }
+
+    fn render_frame_hooked(
+        &mut self,
+        swap_chain: &mut SwapChain,
+        views: &[&View],
+        vsync_steady_clock_time_nano: u64,
+        present: Option<&PresentHook>,
+    ) -> FrameStatus {
+        let user = present.map_or(std::ptr::null_mut(), |hook| {
+            Arc::into_raw(hook.0.clone()) as *mut c_void
+        });
+
+        let accepted = self.begin_frame_with(
+            swap_chain,
+            vsync_steady_clock_time_nano,
+            present.map(|_| frame_finished as _),
+            user,
+        );
+        if !accepted {
+            if present.is_some() {
+                // Skipped frames are never finished, so the hook's reference is released here
+                unsafe { drop(Arc::from_raw(user as *const PresentHookFn)) };
+            }
+            return FrameStatus::Skipped;
+        }
+
+        for view in views {
+            self.render(view);
+        }
+        self.end_frame();
+        FrameStatus::Rendered
+    }
+
+    /// Renders the views of `surface` as one frame, as `render_frame_with` does. The frame is
+    /// skipped if the surface has no swap chain.
+    pub fn render_surface(
+        &mut self,
+        surface: &mut Surface,
+        present: Option<&PresentHook>,
+    ) -> Result<FrameStatus, RenderError> {
+        let views = surface.views().cloned().collect::<Vec<_>>();
+        let views = views.iter().collect::<Vec<_>>();
+        match surface.swap_chain_mut() {
+            Some(swap_chain) => self.render_frame_with(swap_chain, &views, 0, present),
+            None => Ok(FrameStatus::Skipped),
+        }
+    }
+
+    /// Reads back a region of the color attachment of `render_target`, blocking until the copy
+    /// completes. Rows are returned bottom to top, and `region.z` and `region.depth` are ignored.
+    pub fn read_pixels(
+        &mut self,
+        render_target: &RenderTarget,
+        region: TextureRegion,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+    ) -> Result<Vec<u8>, TextureError> {
+        let size = TextureRegion { depth: 1, ..region }.data_size(format, ty);
+        let mut pixels = vec![0u8; size];
+        let completed = Arc::new(AtomicBool::new(false));
+
+        let mut desc = unsafe {
+            ffi::helpers_make_PixelBufferDescriptor(
+                pixels.as_mut_ptr() as *mut _,
+                size.try_into().unwrap(),
+                Some(read_pixels_completed),
+                Arc::into_raw(completed.clone()) as *mut c_void,
+                format,
+                ty,
+            )
+        };
+        unsafe {
+            self.as_raw_mut().readPixels1(
+                render_target.as_raw_ptr(),
+                region.x,
+                region.y,
+                region.width,
+                region.height,
+                &mut desc as *mut _,
+            )
+        };
+        std::mem::forget(desc);
+
+        // Flushing runs the copy and then the callbacks of completed buffers
+        self.engine.flush_and_wait();
+        if completed.load(Ordering::Acquire) {
+            Ok(pixels)
+        } else {
+            // The backend may still write into the buffer, so it cannot be freed
+            std::mem::forget(pixels);
+            Err(TextureError::ReadbackFailed)
+        }
     }
 
     pub fn user_time(&self) -> f64 {
@@ -112,7 +418,132 @@ impl Renderer {
             Ok(Self {
                 ptr: Arc::new(ptr),
                 engine: engine.clone(),
+                recorder: Arc::new(Mutex::new(FrameRecorder {
+                    history: FrameHistory::default(),
+                    current: None,
+                    // Filament's default FrameRateOptions
+                    frame_interval: 1,
+                })),
             })
         }
     }
 }
+
+type PresentHookFn = Mutex<Box<dyn FnMut(PresentCallable) + Send>>;
+
+unsafe extern "C" fn frame_finished(
+    callable: ffi::filament_backend_PresentCallable,
+    user: *mut c_void,
+) {
+    let hook = Arc::from_raw(user as *const PresentHookFn);
+    let callable = PresentCallable(Some(callable));
+    let mut hook = hook.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    hook(callable);
+}
+
+unsafe extern "C" fn read_pixels_completed(_buffer: *mut c_void, _size: u64, user: *mut c_void) {
+    let completed = Arc::from_raw(user as *const AtomicBool);
+    completed.store(true, Ordering::Release);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameStatus, PresentCallable, PresentHook, RenderError, Renderer};
+    use crate::{
+        engine::Engine,
+        swapchain::{SwapChain, SwapChainFlags},
+        sys::ffi,
+        view::View,
+        Backend,
+    };
+    use std::{
+        os::raw::c_void,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[test]
+    fn present_callable() {
+        static PRESENTED: AtomicUsize = AtomicUsize::new(0);
+        static CANCELLED: AtomicUsize = AtomicUsize::new(0);
+        unsafe extern "C" fn present_fn(present_frame: bool, _user: *mut c_void) {
+            if present_frame {
+                PRESENTED.fetch_add(1, Ordering::SeqCst);
+            } else {
+                CANCELLED.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let callable = || {
+            PresentCallable(Some(ffi::filament_backend_PresentCallable {
+                mPresentFn: Some(present_fn),
+                mUser: std::ptr::null_mut(),
+            }))
+        };
+
+        callable().present();
+        callable().cancel();
+        drop(callable());
+        assert_eq!(PRESENTED.load(Ordering::SeqCst), 2);
+        assert_eq!(CANCELLED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn render_frame() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut swap_chain =
+            SwapChain::new_headless(&mut engine, 64, 64, SwapChainFlags::empty()).unwrap();
+        let mut renderer = Renderer::new(&mut engine).unwrap();
+        let view = View::new(&mut engine).unwrap();
+
+        renderer.set_frame_history_capacity(2);
+        let mut statuses = Vec::new();
+        for _ in 0..3 {
+            statuses.push(renderer.render_frame(&mut swap_chain, &[&view, &view]));
+        }
+        engine.flush_and_wait();
+
+        let history = renderer.frame_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.frame_count(), 3);
+        let skipped = statuses
+            .iter()
+            .filter(|status| **status == FrameStatus::Skipped)
+            .count();
+        assert_eq!(history.skipped_count(), skipped as u64);
+        for stats in history.iter().filter(|stats| !stats.skipped) {
+            assert_eq!(stats.views, 2);
+            assert_eq!(stats.scene_renderables, 0);
+            assert_eq!(stats.frame_interval, 1);
+        }
+
+        renderer.reset_frame_history();
+        assert!(renderer.frame_history().is_empty());
+    }
+
+    #[test]
+    fn unsupported_present_hook() {
+        static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut swap_chain =
+            SwapChain::new_headless(&mut engine, 64, 64, SwapChainFlags::empty()).unwrap();
+        let mut renderer = Renderer::new(&mut engine).unwrap();
+        let view = View::new(&mut engine).unwrap();
+
+        let hook = PresentHook::new(|callable| {
+            HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+            callable.present();
+        });
+        assert!(matches!(
+            renderer.render_frame_with(&mut swap_chain, &[&view], 0, Some(&hook)),
+            Err(RenderError::PresentHookUnsupported(Backend::NOOP))
+        ));
+        engine.flush_and_wait();
+
+        // The frame is refused before it begins
+        assert_eq!(renderer.frame_history().frame_count(), 0);
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 0);
+        assert!(matches!(
+            renderer.render_frame_with(&mut swap_chain, &[&view], 0, None),
+            Ok(FrameStatus::Rendered)
+        ));
+    }
+}
@@ -0,0 +1,292 @@
+//! Streams of external images, such as video or camera frames, sampled through
+//! `SAMPLER_EXTERNAL` textures.
+use filament_sys::ffi;
+use std::{convert::TryInto, os::raw::c_void, sync::Arc};
+use thiserror::Error;
+
+use crate::{
+    engine::{Engine, EngineError},
+    impl_handle,
+    texture::{
+        ExternalImage, InternalFormat, PixelDataFormat, PixelDataType, SamplerType, Texture,
+        TextureError, TextureRegion,
+    },
+};
+
+pub use ffi::filament_backend_StreamType as StreamType;
+
+#[derive(Error, Debug)]
+pub enum StreamError {
+    #[error("Expected a {expected:?} stream, got {actual:?}")]
+    InvalidStreamType {
+        expected: StreamType,
+        actual: StreamType,
+    },
+}
+
+impl_handle!(Stream, ffi::filament_Stream);
+impl Drop for Stream {
+    fn drop(&mut self) {
+        if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
+            log::trace!(target: "drop", "drop {}", std::any::type_name::<Self>());
+            unsafe { self.engine.as_raw_mut().destroy12(*ptr) }
+        }
+    }
+}
+impl Stream {
+    /// Creates an unsynchronized, copy-free stream from a platform stream object, such as an
+    /// Android `SurfaceTexture`. Good for video.
+    ///
+    /// # Safety
+    /// `stream` must be a valid platform stream for the backend, which outlives this `Stream`.
+    pub unsafe fn from_native(
+        engine: &mut Engine,
+        stream: *mut c_void,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, EngineError> {
+        let ptr = ffi::helpers_stream_build_native(engine.as_raw_ptr(), stream, width, height);
+        Self::from_ptr(engine, ptr)
+    }
+
+    /// Creates a synchronized stream which copies from an OpenGL texture name created on a context
+    /// shared with the engine. Only supported by the OpenGL backend.
+    ///
+    /// # Safety
+    /// `texture_id` must name a texture of the engine's shared context for the lifetime of this
+    /// `Stream`.
+    pub unsafe fn from_texture_id(
+        engine: &mut Engine,
+        texture_id: isize,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, EngineError> {
+        let ptr =
+            ffi::helpers_stream_build_texture_id(engine.as_raw_ptr(), texture_id, width, height);
+        Self::from_ptr(engine, ptr)
+    }
+
+    /// Creates a synchronized, copy-free stream whose images are pushed with `set_acquired_image`.
+    pub fn acquired(engine: &mut Engine, width: u32, height: u32) -> Result<Self, EngineError> {
+        let ptr = unsafe { ffi::helpers_stream_build_acquired(engine.as_raw_ptr(), width, height) };
+        Self::from_ptr(engine, ptr)
+    }
+
+    fn from_ptr(engine: &Engine, ptr: *mut ffi::filament_Stream) -> Result<Self, EngineError> {
+        if ptr.is_null() {
+            Err(EngineError::CreationFailed)
+        } else {
+            Ok(Self {
+                ptr: Arc::new(ptr),
+                engine: engine.clone(),
+            })
+        }
+    }
+
+    #[inline]
+    pub fn stream_type(&self) -> StreamType {
+        unsafe { ffi::helpers_stream_getStreamType(self.as_raw_ptr()) }
+    }
+
+    #[inline]
+    pub fn set_dimensions(&mut self, width: u32, height: u32) {
+        unsafe { ffi::helpers_stream_setDimensions(self.as_raw_ptr(), width, height) }
+    }
+
+    /// The presentation timestamp of the image currently displayed, in nanoseconds.
+    #[inline]
+    pub fn timestamp(&self) -> i64 {
+        unsafe { ffi::helpers_stream_getTimestamp(self.as_raw_ptr()) }
+    }
+
+    /// Pushes a new image to an `ACQUIRED` stream. `release` is called on the application thread
+    /// once Filament no longer uses the image. Other streams return an error, leaving `image` to
+    /// the caller and dropping `release` without calling it.
+    ///
+    /// Filament drops an image still held when the stream is destroyed without releasing it, so
+    /// `release` and everything it captures are leaked in that case.
+    ///
+    /// # Safety
+    /// `image` must stay valid until `release` is called.
+    pub unsafe fn set_acquired_image<F>(
+        &mut self,
+        image: ExternalImage,
+        release: F,
+    ) -> Result<(), StreamError>
+    where
+        F: FnOnce(ExternalImage) + Send + 'static,
+    {
+        let stream_type = self.stream_type();
+        if stream_type != StreamType::ACQUIRED {
+            return Err(StreamError::InvalidStreamType {
+                expected: StreamType::ACQUIRED,
+                actual: stream_type,
+            });
+        }
+
+        let user: Box<Box<dyn FnOnce(ExternalImage) + Send>> = Box::new(Box::new(release));
+        ffi::helpers_stream_setAcquiredImage(
+            self.as_raw_ptr(),
+            image.as_raw(),
+            Some(release_acquired_image),
+            Box::into_raw(user) as *mut c_void,
+        );
+        Ok(())
+    }
+}
+
+unsafe extern "C" fn release_acquired_image(image: *mut c_void, user: *mut c_void) {
+    let release = Box::from_raw(user as *mut Box<dyn FnOnce(ExternalImage) + Send>);
+    release(ExternalImage::from_raw(image))
+}
+
+/// A stream of frames produced on the CPU, such as by a software video decoder.
+///
+/// Frames are uploaded into a regular 2D texture, so unlike `Stream` this needs no platform
+/// support and works with every backend, including `NOOP`.
+pub struct CpuStream {
+    texture: Texture,
+    width: u32,
+    height: u32,
+    format: PixelDataFormat,
+    ty: PixelDataType,
+    frame_count: u64,
+    timestamp: i64,
+}
+impl CpuStream {
+    pub fn new(
+        engine: &mut Engine,
+        width: u32,
+        height: u32,
+        internal_format: InternalFormat,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+    ) -> Result<Self, EngineError> {
+        let texture = Texture::builder()?
+            .width(width.try_into().unwrap())
+            .height(height.try_into().unwrap())
+            .levels(1)
+            .sampler(SamplerType::SAMPLER_2D)
+            .format(internal_format)
+            .build(engine)?;
+
+        Ok(Self {
+            texture,
+            width,
+            height,
+            format,
+            ty,
+            frame_count: 0,
+            timestamp: 0,
+        })
+    }
+
+    /// The texture frames are uploaded to, which can be bound to materials like any other.
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The number of frames pushed so far.
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// The timestamp of the last frame pushed, in nanoseconds.
+    #[inline]
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Uploads a whole frame of tightly packed pixels, replacing the previous one.
+    pub fn push_frame<T: Sized>(
+        &mut self,
+        pixels: Vec<T>,
+        timestamp: i64,
+    ) -> Result<(), TextureError> {
+        let region = TextureRegion::new(0, 0, self.width, self.height);
+        self.texture
+            .set_region(0, region, pixels, self.format, self.ty)?;
+
+        self.frame_count += 1;
+        self.timestamp = timestamp;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CpuStream, Stream, StreamError, StreamType};
+    use crate::{
+        engine::Engine,
+        texture::{
+            ExternalImage, InternalFormat, PixelDataFormat, PixelDataType, SamplerType,
+            TextureError,
+        },
+        Backend,
+    };
+
+    #[test]
+    fn cpu_stream_frames() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut stream = CpuStream::new(
+            &mut engine,
+            2,
+            2,
+            InternalFormat::RGBA8,
+            PixelDataFormat::RGBA,
+            PixelDataType::UBYTE,
+        )
+        .unwrap();
+        assert_eq!(stream.texture().target(), SamplerType::SAMPLER_2D);
+
+        assert!(stream.push_frame(vec![0u8; 16], 1_000).is_ok());
+        assert!(stream.push_frame(vec![255u8; 16], 2_000).is_ok());
+        assert!(matches!(
+            stream.push_frame(vec![0u8; 12], 3_000),
+            Err(TextureError::DataSizeMismatch {
+                expected: 16,
+                actual: 12
+            })
+        ));
+        assert_eq!(stream.frame_count(), 2);
+        assert_eq!(stream.timestamp(), 2_000);
+
+        // External images can only be attached to external textures
+        let image = unsafe { ExternalImage::from_raw(std::ptr::null_mut()) };
+        let mut texture = stream.texture().clone();
+        assert!(matches!(
+            texture.set_external_image(image),
+            Err(TextureError::InvalidTarget { .. })
+        ));
+    }
+
+    #[test]
+    fn acquired_image_stream_type() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut stream =
+            unsafe { Stream::from_native(&mut engine, std::ptr::null_mut(), 2, 2) }.unwrap();
+        assert_eq!(stream.stream_type(), StreamType::NATIVE);
+
+        let image = unsafe { ExternalImage::from_raw(std::ptr::null_mut()) };
+        let result = unsafe { stream.set_acquired_image(image, |_| panic!("never acquired")) };
+        assert!(matches!(
+            result,
+            Err(StreamError::InvalidStreamType {
+                expected: StreamType::ACQUIRED,
+                actual: StreamType::NATIVE
+            })
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+//! A window surface owning its swap chain and the views drawn to it, keeping both in sync with the
+//! size of the window.
+use filament_sys::Viewport;
+use raw_window_handle::HasRawWindowHandle;
+
+use crate::{
+    camera::{Camera, FovDirection},
+    engine::Engine,
+    swapchain::{SwapChain, SwapChainError, SwapChainFlags},
+    view::View,
+};
+
+/// The perspective projection of a camera attached to a `Surface`. Its aspect ratio is taken from
+/// the size of the surface.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Perspective {
+    pub fov: f64,
+    pub near: f64,
+    pub far: f64,
+    pub direction: FovDirection,
+}
+impl Default for Perspective {
+    fn default() -> Self {
+        Self {
+            fov: 60.0,
+            near: 0.1,
+            far: 1000.0,
+            direction: FovDirection::default(),
+        }
+    }
+}
+
+struct SurfaceView {
+    view: View,
+    camera: Option<(Camera, Perspective)>,
+}
+
+/// Owns a `SwapChain` and a set of views covering it.
+///
+/// `resize` recreates the swap chain for the new window size, then updates the viewport of every
+/// view and the aspect ratio of every camera attached with `add_view_with_camera`.
+pub struct Surface {
+    engine: Engine,
+    swap_chain: Option<SwapChain>,
+    width: u32,
+    height: u32,
+    views: Vec<SurfaceView>,
+}
+impl Surface {
+    /// Takes ownership of `swap_chain`, which presents to a window of `width` by `height` pixels.
+    pub fn new(engine: &mut Engine, swap_chain: SwapChain, width: u32, height: u32) -> Self {
+        Self {
+            engine: engine.clone(),
+            swap_chain: Some(swap_chain),
+            width,
+            height,
+            views: Vec::new(),
+        }
+    }
+
+    pub fn from_window<W: HasRawWindowHandle>(
+        engine: &mut Engine,
+        window: &W,
+        width: u32,
+        height: u32,
+        flags: SwapChainFlags,
+    ) -> Result<Self, SwapChainError> {
+        let swap_chain = SwapChain::from_window_with_size(engine, window, width, height, flags)?;
+        Ok(Self::new(engine, swap_chain, width, height))
+    }
+
+    /// The swap chain `Renderer::render_surface` renders to. This is only `None` after recreating
+    /// it failed in `resize`, until a later `resize` succeeds.
+    #[inline]
+    pub fn swap_chain(&self) -> Option<&SwapChain> {
+        self.swap_chain.as_ref()
+    }
+
+    #[inline]
+    pub fn swap_chain_mut(&mut self) -> Option<&mut SwapChain> {
+        self.swap_chain.as_mut()
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn aspect(&self) -> f64 {
+        f64::from(self.width) / f64::from(self.height.max(1))
+    }
+
+    #[inline]
+    pub fn viewport(&self) -> Viewport {
+        Viewport::new(0, 0, self.width, self.height)
+    }
+
+    /// The views drawn to this surface, in the order they were added.
+    pub fn views(&self) -> impl Iterator<Item = &View> {
+        self.views.iter().map(|entry| &entry.view)
+    }
+
+    /// Adds a view covering the whole surface.
+    pub fn add_view(&mut self, view: &View) {
+        let mut entry = SurfaceView {
+            view: view.clone(),
+            camera: None,
+        };
+        self.update_view(&mut entry);
+        self.views.push(entry);
+    }
+
+    /// Adds a view covering the whole surface, seen through `camera` with `perspective`. The
+    /// camera is set on the view.
+    pub fn add_view_with_camera(&mut self, view: &View, camera: &Camera, perspective: Perspective) {
+        let mut entry = SurfaceView {
+            view: view.clone(),
+            camera: Some((camera.clone(), perspective)),
+        };
+        entry.view.set_camera(camera);
+        self.update_view(&mut entry);
+        self.views.push(entry);
+    }
+
+    /// Removes `view` from the surface, returning whether it had been added.
+    pub fn remove_view(&mut self, view: &View) -> bool {
+        let len = self.views.len();
+        self.views.retain(|entry| &entry.view != view);
+        self.views.len() != len
+    }
+
+    /// Resizes the surface to `width` by `height` pixels.
+    ///
+    /// The GPU is drained with `flush_and_wait` before the old swap chain is destroyed and a new
+    /// one created for the same window. Nothing is done if the size is unchanged or either
+    /// dimension is zero, as happens when a window is minimized.
+    ///
+    /// Fails with `SwapChainError::Shared` if clones of the swap chain are still held, as it could
+    /// not be destroyed.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), SwapChainError> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        if self.swap_chain.is_some() && width == self.width && height == self.height {
+            return Ok(());
+        }
+        if self.swap_chain.as_ref().map_or(false, SwapChain::is_shared) {
+            return Err(SwapChainError::Shared);
+        }
+
+        self.width = width;
+        self.height = height;
+
+        if let Some(swap_chain) = self.swap_chain.take() {
+            let source = swap_chain.source().clone();
+            let flags = swap_chain.flags();
+
+            self.engine.flush_and_wait();
+            // Some platforms only allow one swap chain per window, so the old one is destroyed
+            // before the new one is created.
+            drop(swap_chain);
+            self.swap_chain = Some(SwapChain::from_source(
+                &mut self.engine,
+                &source,
+                width,
+                height,
+                flags,
+            )?);
+        }
+
+        let mut views = std::mem::take(&mut self.views);
+        for entry in &mut views {
+            self.update_view(entry);
+        }
+        self.views = views;
+
+        Ok(())
+    }
+
+    fn update_view(&self, entry: &mut SurfaceView) {
+        entry.view.set_viewport(self.viewport());
+        if let Some((camera, perspective)) = &mut entry.camera {
+            camera.set_projection_fov(
+                perspective.fov,
+                self.aspect(),
+                perspective.near,
+                perspective.far,
+                perspective.direction,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Perspective, Surface};
+    use crate::{
+        camera::Camera,
+        engine::Engine,
+        entity_manager::EntityManager,
+        swapchain::{SwapChain, SwapChainError, SwapChainFlags},
+        view::View,
+        Backend,
+    };
+
+    #[test]
+    fn surface_resize() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let swap_chain =
+            SwapChain::new_headless(&mut engine, 800, 600, SwapChainFlags::READABLE).unwrap();
+        let mut surface = Surface::new(&mut engine, swap_chain, 800, 600);
+
+        let view = View::new(&mut engine).unwrap();
+        let overlay = View::new(&mut engine).unwrap();
+        let camera = Camera::new(&mut engine, EntityManager::get().create()).unwrap();
+        surface.add_view_with_camera(&view, &camera, Perspective::default());
+        surface.add_view(&overlay);
+        assert_eq!(view.viewport().width, 800);
+        assert_eq!(overlay.viewport().height, 600);
+
+        // A clone would keep the old swap chain alive for the same window
+        let clone = surface.swap_chain().unwrap().clone();
+        assert!(matches!(
+            surface.resize(1024, 512),
+            Err(SwapChainError::Shared)
+        ));
+        assert_eq!(surface.width(), 800);
+        drop(clone);
+
+        surface.resize(1024, 512).unwrap();
+        assert_eq!(surface.aspect(), 2.0);
+        assert_eq!(
+            surface.swap_chain().unwrap().flags(),
+            SwapChainFlags::READABLE
+        );
+        for view in surface.views() {
+            let viewport = view.viewport();
+            assert_eq!((viewport.width, viewport.height), (1024, 512));
+        }
+
+        // Minimizing leaves everything as it was
+        let current = surface.swap_chain().unwrap().as_raw_ptr();
+        surface.resize(0, 0).unwrap();
+        assert_eq!(surface.swap_chain().unwrap().as_raw_ptr(), current);
+        assert_eq!(overlay.viewport().width, 1024);
+
+        assert!(surface.remove_view(&overlay));
+        assert!(!surface.remove_view(&overlay));
+        assert_eq!(surface.views().count(), 1);
+    }
+}
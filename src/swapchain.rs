@@ -1,11 +1,23 @@
 use filament_sys::ffi;
-use std::sync::Arc;
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use std::{os::raw::c_void, sync::Arc};
+use thiserror::Error;
 
 use crate::{
     engine::{Engine, EngineError},
-    impl_handle,
+    impl_ptr_functions,
 };
 
+#[derive(Error, Debug)]
+pub enum SwapChainError {
+    #[error("Window handle {0} cannot be used to create a swap chain")]
+    UnsupportedWindowHandle(&'static str),
+    #[error("Swap chain cannot be recreated while other handles to it exist")]
+    Shared,
+    #[error("Failed to create swap chain: {0}")]
+    Engine(#[from] EngineError),
+}
+
 bitflags::bitflags! {
     pub struct SwapChainFlags: u64 {
         const TRANSPARENT = ffi::filament_SwapChain_CONFIG_TRANSPARENT;
@@ -13,7 +25,14 @@ bitflags::bitflags! {
     }
 }
 
-impl_handle!(SwapChain, ffi::filament_SwapChain);
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SwapChain {
+    engine: Engine,
+    ptr: Arc<*mut ffi::filament_SwapChain>,
+    source: SwapChainSource,
+    flags: SwapChainFlags,
+}
+impl_ptr_functions!(SwapChain, ffi::filament_SwapChain);
 impl Drop for SwapChain {
     fn drop(&mut self) {
         if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
@@ -33,16 +52,98 @@ impl SwapChain {
                 .as_raw_mut()
                 .createSwapChain(raw_window_handle, flags.bits)
         };
-        if ptr.is_null() {
-            Err(EngineError::CreationFailed)
-        } else {
-            Ok(Self {
-                ptr: Arc::new(ptr),
-                engine: engine.clone(),
-            })
+        Self::from_ptr(
+            engine,
+            ptr,
+            SwapChainSource::Window(raw_window_handle),
+            flags,
+        )
+    }
+
+    /// Creates a swap chain presenting to `window`, converting its handle into the native window
+    /// Filament expects on each platform: an X11 window id for Xlib and Xcb, an `HWND` on Windows, an
+    /// `NSView` on macOS and an `ANativeWindow` on Android.
+    ///
+    /// Wayland handles carry no surface size, so Wayland surfaces need `from_window_with_size`.
+    pub fn from_window<W: HasRawWindowHandle>(
+        engine: &mut Engine,
+        window: &W,
+        flags: SwapChainFlags,
+    ) -> Result<Self, SwapChainError> {
+        let native_window = native_window(window.raw_window_handle())?;
+        Ok(Self::new(engine, native_window, flags)?)
+    }
+
+    /// Creates a swap chain presenting to `window`, as `from_window` does, with Wayland surfaces
+    /// supported as well. `width` and `height` are the size of the window in pixels, which only
+    /// Wayland surfaces need.
+    pub fn from_window_with_size<W: HasRawWindowHandle>(
+        engine: &mut Engine,
+        window: &W,
+        width: u32,
+        height: u32,
+        flags: SwapChainFlags,
+    ) -> Result<Self, SwapChainError> {
+        match window.raw_window_handle() {
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            RawWindowHandle::Wayland(handle) => {
+                if handle.display.is_null() || handle.surface.is_null() {
+                    return Err(SwapChainError::UnsupportedWindowHandle(
+                        "Wayland without a surface",
+                    ));
+                }
+                // The window owns the display and surface, and outlives anything drawn to it
+                unsafe {
+                    Self::from_wayland_surface(
+                        engine,
+                        handle.display,
+                        handle.surface,
+                        width,
+                        height,
+                        flags,
+                    )
+                }
+            }
+            _ => Self::from_window(engine, window, flags),
         }
     }
 
+    /// Creates a swap chain presenting to a Wayland surface of `width` by `height` pixels.
+    ///
+    /// # Safety
+    /// `display` and `surface` must be a valid `wl_display` and `wl_surface`, which outlive the swap
+    /// chain.
+    pub unsafe fn from_wayland_surface(
+        engine: &mut Engine,
+        display: *mut c_void,
+        surface: *mut c_void,
+        width: u32,
+        height: u32,
+        flags: SwapChainFlags,
+    ) -> Result<Self, SwapChainError> {
+        let wayland_window = Arc::new(WaylandWindow {
+            display,
+            surface,
+            width,
+            height,
+        });
+        let ptr = engine
+            .as_raw_mut()
+            .createSwapChain(Arc::as_ptr(&wayland_window) as *mut c_void, flags.bits);
+        Ok(Self::from_ptr(
+            engine,
+            ptr,
+            SwapChainSource::Wayland(wayland_window),
+            flags,
+        )?)
+    }
+
     pub fn new_headless(
         engine: &mut Engine,
         width: u32,
@@ -54,17 +155,226 @@ impl SwapChain {
                 .as_raw_mut()
                 .createSwapChain1(width, height, flags.bits)
         };
+        Self::from_ptr(engine, ptr, SwapChainSource::Headless, flags)
+    }
+
+    /// Creates a swap chain of `width` by `height` pixels presenting to the same window as one
+    /// created from `source`. Native windows keep their own size, so the size is only used by
+    /// Wayland surfaces and headless swap chains.
+    pub(crate) fn from_source(
+        engine: &mut Engine,
+        source: &SwapChainSource,
+        width: u32,
+        height: u32,
+        flags: SwapChainFlags,
+    ) -> Result<Self, SwapChainError> {
+        match source {
+            SwapChainSource::Window(native_window) => Ok(Self::new(engine, *native_window, flags)?),
+            SwapChainSource::Wayland(window) => unsafe {
+                Self::from_wayland_surface(
+                    engine,
+                    window.display,
+                    window.surface,
+                    width,
+                    height,
+                    flags,
+                )
+            },
+            SwapChainSource::Headless => Ok(Self::new_headless(engine, width, height, flags)?),
+        }
+    }
+
+    fn from_ptr(
+        engine: &Engine,
+        ptr: *mut ffi::filament_SwapChain,
+        source: SwapChainSource,
+        flags: SwapChainFlags,
+    ) -> Result<Self, EngineError> {
         if ptr.is_null() {
             Err(EngineError::CreationFailed)
         } else {
             Ok(Self {
                 ptr: Arc::new(ptr),
                 engine: engine.clone(),
+                source,
+                flags,
             })
         }
     }
 
+    #[inline]
+    pub fn flags(&self) -> SwapChainFlags {
+        self.flags
+    }
+
+    #[inline]
+    pub(crate) fn source(&self) -> &SwapChainSource {
+        &self.source
+    }
+
+    /// Whether other clones of this handle exist, keeping the swap chain alive when this one drops.
+    #[inline]
+    pub(crate) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.ptr) > 1
+    }
+
     pub fn raw_handle(&self) -> *mut ::std::os::raw::c_void {
         unsafe { self.as_raw_ref().getNativeWindow() }
     }
 }
+
+/// What a swap chain presents to, kept so that it can be recreated when the window resizes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum SwapChainSource {
+    Window(*mut c_void),
+    Wayland(Arc<WaylandWindow>),
+    Headless,
+}
+
+/// The native window Filament's Vulkan backend reads Wayland surfaces from. It is only read when
+/// the driver creates the swap chain, which may happen after `createSwapChain` returns, so it is
+/// kept alive by the `SwapChain`.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct WaylandWindow {
+    display: *mut c_void,
+    surface: *mut c_void,
+    width: u32,
+    height: u32,
+}
+
+fn native_window(handle: RawWindowHandle) -> Result<*mut c_void, SwapChainError> {
+    match handle {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        RawWindowHandle::Xlib(handle) => Ok(handle.window as *mut c_void),
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        RawWindowHandle::Xcb(handle) => Ok(handle.window as usize as *mut c_void),
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        RawWindowHandle::Wayland(_) => Err(SwapChainError::UnsupportedWindowHandle(
+            "Wayland without a size, use `from_window_with_size`",
+        )),
+        #[cfg(target_os = "windows")]
+        RawWindowHandle::Windows(handle) => Ok(handle.hwnd),
+        #[cfg(target_os = "macos")]
+        RawWindowHandle::MacOS(handle) if !handle.ns_view.is_null() => Ok(handle.ns_view),
+        #[cfg(target_os = "android")]
+        RawWindowHandle::Android(handle) => Ok(handle.a_native_window),
+        _ => Err(SwapChainError::UnsupportedWindowHandle("for this platform")),
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::{native_window, SwapChain, SwapChainError, SwapChainFlags};
+    use crate::{engine::Engine, Backend};
+    use raw_window_handle::{
+        unix::{WaylandHandle, XlibHandle},
+        HasRawWindowHandle, RawWindowHandle,
+    };
+    use std::os::raw::c_void;
+
+    struct XlibWindow(XlibHandle);
+    unsafe impl HasRawWindowHandle for XlibWindow {
+        fn raw_window_handle(&self) -> RawWindowHandle {
+            RawWindowHandle::Xlib(self.0)
+        }
+    }
+
+    struct WaylandWindow(WaylandHandle);
+    unsafe impl HasRawWindowHandle for WaylandWindow {
+        fn raw_window_handle(&self) -> RawWindowHandle {
+            RawWindowHandle::Wayland(self.0)
+        }
+    }
+
+    #[test]
+    fn window_handle_conversion() {
+        let handle = XlibHandle {
+            window: 0x1234,
+            ..XlibHandle::empty()
+        };
+        assert_eq!(
+            native_window(RawWindowHandle::Xlib(handle)).unwrap(),
+            0x1234 as *mut c_void
+        );
+        assert!(matches!(
+            native_window(RawWindowHandle::Wayland(WaylandHandle::empty())),
+            Err(SwapChainError::UnsupportedWindowHandle(_))
+        ));
+    }
+
+    #[test]
+    fn wayland_window_size() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let window = WaylandWindow(WaylandHandle::empty());
+        assert!(matches!(
+            SwapChain::from_window(&mut engine, &window, SwapChainFlags::empty()),
+            Err(SwapChainError::UnsupportedWindowHandle(_))
+        ));
+        // Handles without a surface are refused before reaching Filament
+        assert!(matches!(
+            SwapChain::from_window_with_size(&mut engine, &window, 64, 64, SwapChainFlags::empty()),
+            Err(SwapChainError::UnsupportedWindowHandle(
+                "Wayland without a surface"
+            ))
+        ));
+    }
+
+    /// Needs an X server, such as `Xvfb :99 & DISPLAY=:99 cargo test`, and is skipped without one.
+    #[test]
+    fn xlib_swap_chain() {
+        let xlib = match x11_dl::xlib::Xlib::open() {
+            Ok(xlib) => xlib,
+            Err(_) => return,
+        };
+        let display = unsafe { (xlib.XOpenDisplay)(std::ptr::null()) };
+        if display.is_null() {
+            return;
+        }
+
+        let window = unsafe {
+            let root = (xlib.XDefaultRootWindow)(display);
+            let window = (xlib.XCreateSimpleWindow)(display, root, 0, 0, 64, 64, 0, 0, 0);
+            (xlib.XMapWindow)(display, window);
+            (xlib.XSync)(display, 0);
+            window
+        };
+
+        {
+            let mut engine = Engine::new(Backend::OPENGL).unwrap();
+            let window = XlibWindow(XlibHandle {
+                window,
+                display: display as *mut c_void,
+                ..XlibHandle::empty()
+            });
+            let swap_chain =
+                SwapChain::from_window(&mut engine, &window, SwapChainFlags::empty()).unwrap();
+            assert_eq!(swap_chain.raw_handle(), window.0.window as *mut c_void);
+            engine.flush_and_wait();
+        }
+
+        unsafe {
+            (xlib.XDestroyWindow)(display, window);
+            (xlib.XCloseDisplay)(display);
+        }
+    }
+}
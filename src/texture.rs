@@ -1,28 +1,141 @@
 use filament_sys::ffi;
-use std::{convert::TryInto, sync::Arc};
+use std::{convert::TryInto, os::raw::c_void, sync::Arc};
+use thiserror::Error;
 
 use crate::{
     engine::{Engine, EngineError},
-    impl_handle,
+    impl_ptr_functions,
+    render_target::RenderTarget,
+    renderer::Renderer,
+    stream::Stream,
+};
+
+pub use filament_sys::{
+    SamplerCompareFunc, SamplerCompareMode, SamplerMagFilter, SamplerMinFilter, SamplerWrapMode,
+    TextureSamplerBuilder,
 };
 
 pub use ffi::{
-    filament_TextureSampler as TextureSampler,
-    filament_TextureSampler_CompareFunc as SamplerCompareFunc,
-    filament_TextureSampler_CompareMode as SamplerCompareMode,
-    filament_TextureSampler_MagFilter as SamplerMagFilter,
-    filament_TextureSampler_MinFilter as SamplerMinFilter,
-    filament_TextureSampler_WrapMode as SamplerWrapMode,
-    filament_Texture_CompressedType as CompressedType, filament_Texture_CubemapFace as CubeMapFace,
+    filament_TextureSampler as TextureSampler, filament_Texture_CompressedType as CompressedType,
+    filament_Texture_CubemapFace as CubeMapFace, filament_Texture_FaceOffsets as FaceOffsets,
     filament_Texture_Format as TextureFormat, filament_Texture_InternalFormat as InternalFormat,
-    filament_Texture_Sampler as SamplerType, filament_Texture_Swizzle as TextureSwizzle,
-    filament_Texture_Type as TextureType, filament_Texture_Usage as TextureUsage,
+    filament_Texture_PrefilterOptions as PrefilterOptions, filament_Texture_Sampler as SamplerType,
+    filament_Texture_Swizzle as TextureSwizzle, filament_Texture_Type as TextureType,
     filament_backend_PixelBufferDescriptor as PixelBufferDescriptor,
     filament_backend_PixelDataFormat as PixelDataFormat,
     filament_backend_PixelDataType as PixelDataType,
 };
 
-impl_handle!(Texture, ffi::filament_Texture);
+bitflags::bitflags! {
+    pub struct TextureUsage: u8 {
+        const COLOR_ATTACHMENT = ffi::filament_Texture_Usage::COLOR_ATTACHMENT.0;
+        const DEPTH_ATTACHMENT = ffi::filament_Texture_Usage::DEPTH_ATTACHMENT.0;
+        const STENCIL_ATTACHMENT = ffi::filament_Texture_Usage::STENCIL_ATTACHMENT.0;
+        const UPLOADABLE = ffi::filament_Texture_Usage::UPLOADABLE.0;
+        const SAMPLEABLE = ffi::filament_Texture_Usage::SAMPLEABLE.0;
+        const DEFAULT = ffi::filament_Texture_Usage::DEFAULT.0;
+    }
+}
+impl Default for TextureUsage {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TextureError {
+    #[error("Expected {expected} bytes of pixel data, got {actual}")]
+    DataSizeMismatch { expected: usize, actual: usize },
+    #[error("Region {region:?} does not fit in level {level}")]
+    RegionOutOfBounds { region: TextureRegion, level: usize },
+    #[error("Region {region:?} is not aligned to blocks of {block_width}x{block_height} texels")]
+    UnalignedRegion {
+        region: TextureRegion,
+        block_width: usize,
+        block_height: usize,
+    },
+    #[error("Level {level} does not exist, the texture has {levels} levels")]
+    LevelOutOfBounds { level: usize, levels: usize },
+    #[error("Texture format {0:?} cannot be mipmapped")]
+    NotMipmappable(InternalFormat),
+    #[error("Texture usage {0:?} does not allow this operation")]
+    InvalidUsage(TextureUsage),
+    #[error("Texture only has a single mip level")]
+    SingleLevel,
+    #[error("Texture dimensions {width}x{height} must be square powers of two")]
+    NotPowerOfTwo { width: usize, height: usize },
+    #[error("Operation requires a {expected:?} texture, but this texture is {actual:?}")]
+    InvalidTarget {
+        expected: SamplerType,
+        actual: SamplerType,
+    },
+    #[error("Texture read back did not complete")]
+    ReadbackFailed,
+    #[error("Failed to create read back resources: {0}")]
+    Engine(#[from] EngineError),
+}
+
+/// A box of texels within a single mip level. `z` and `depth` select layers of array textures
+/// and slices of 3D textures, and are respectively 0 and 1 for plain 2D textures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TextureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+impl TextureRegion {
+    #[inline]
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self::new_3d(x, y, 0, width, height, 1)
+    }
+
+    #[inline]
+    pub fn new_3d(x: u32, y: u32, z: u32, width: u32, height: u32, depth: u32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// The number of bytes needed to upload this region with the given pixel format.
+    pub fn data_size(&self, format: PixelDataFormat, ty: PixelDataType) -> usize {
+        Texture::compute_data_size(format, ty, self.width as usize, self.height as usize, 1)
+            * self.depth as usize
+    }
+}
+
+/// An opaque platform image, such as an `EGLImageKHR` on Android or a `CVPixelBufferRef` on
+/// Apple platforms.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ExternalImage(*mut c_void);
+impl ExternalImage {
+    /// # Safety
+    /// `image` must be a valid platform image for the backend of the engine it is used with.
+    #[inline]
+    pub unsafe fn from_raw(image: *mut c_void) -> Self {
+        Self(image)
+    }
+
+    #[inline]
+    pub fn as_raw(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Texture {
+    engine: Engine,
+    ptr: Arc<*mut ffi::filament_Texture>,
+    usage: TextureUsage,
+}
+impl_ptr_functions!(Texture, ffi::filament_Texture);
 impl Drop for Texture {
     fn drop(&mut self) {
         if let Some(ptr) = Arc::get_mut(&mut self.ptr) {
@@ -56,6 +169,95 @@ impl Texture {
         }
     }
 
+    /// The number of bytes of a `width` by `height` image compressed with `ty`. Partial blocks at
+    /// the edges are stored as whole blocks.
+    pub fn compute_compressed_data_size(ty: CompressedType, width: usize, height: usize) -> usize {
+        let (block_width, block_height, block_size) = compressed_block_size(ty);
+        let blocks_x = (width + block_width - 1) / block_width;
+        let blocks_y = (height + block_height - 1) / block_height;
+        blocks_x * blocks_y * block_size
+    }
+
+    /// The internal format storing data compressed with `ty`.
+    pub fn compressed_internal_format(ty: CompressedType) -> InternalFormat {
+        use CompressedType::*;
+        match ty {
+            EAC_R11 => InternalFormat::EAC_R11,
+            EAC_R11_SIGNED => InternalFormat::EAC_R11_SIGNED,
+            EAC_RG11 => InternalFormat::EAC_RG11,
+            EAC_RG11_SIGNED => InternalFormat::EAC_RG11_SIGNED,
+            ETC2_RGB8 => InternalFormat::ETC2_RGB8,
+            ETC2_SRGB8 => InternalFormat::ETC2_SRGB8,
+            ETC2_RGB8_A1 => InternalFormat::ETC2_RGB8_A1,
+            ETC2_SRGB8_A1 => InternalFormat::ETC2_SRGB8_A1,
+            ETC2_EAC_RGBA8 => InternalFormat::ETC2_EAC_RGBA8,
+            ETC2_EAC_SRGBA8 => InternalFormat::ETC2_EAC_SRGBA8,
+            DXT1_RGB => InternalFormat::DXT1_RGB,
+            DXT1_RGBA => InternalFormat::DXT1_RGBA,
+            DXT3_RGBA => InternalFormat::DXT3_RGBA,
+            DXT5_RGBA => InternalFormat::DXT5_RGBA,
+            DXT1_SRGB => InternalFormat::DXT1_SRGB,
+            DXT1_SRGBA => InternalFormat::DXT1_SRGBA,
+            DXT3_SRGBA => InternalFormat::DXT3_SRGBA,
+            DXT5_SRGBA => InternalFormat::DXT5_SRGBA,
+            RGBA_ASTC_4x4 => InternalFormat::RGBA_ASTC_4x4,
+            RGBA_ASTC_5x4 => InternalFormat::RGBA_ASTC_5x4,
+            RGBA_ASTC_5x5 => InternalFormat::RGBA_ASTC_5x5,
+            RGBA_ASTC_6x5 => InternalFormat::RGBA_ASTC_6x5,
+            RGBA_ASTC_6x6 => InternalFormat::RGBA_ASTC_6x6,
+            RGBA_ASTC_8x5 => InternalFormat::RGBA_ASTC_8x5,
+            RGBA_ASTC_8x6 => InternalFormat::RGBA_ASTC_8x6,
+            RGBA_ASTC_8x8 => InternalFormat::RGBA_ASTC_8x8,
+            RGBA_ASTC_10x5 => InternalFormat::RGBA_ASTC_10x5,
+            RGBA_ASTC_10x6 => InternalFormat::RGBA_ASTC_10x6,
+            RGBA_ASTC_10x8 => InternalFormat::RGBA_ASTC_10x8,
+            RGBA_ASTC_10x10 => InternalFormat::RGBA_ASTC_10x10,
+            RGBA_ASTC_12x10 => InternalFormat::RGBA_ASTC_12x10,
+            RGBA_ASTC_12x12 => InternalFormat::RGBA_ASTC_12x12,
+            SRGB8_ALPHA8_ASTC_4x4 => InternalFormat::SRGB8_ALPHA8_ASTC_4x4,
+            SRGB8_ALPHA8_ASTC_5x4 => InternalFormat::SRGB8_ALPHA8_ASTC_5x4,
+            SRGB8_ALPHA8_ASTC_5x5 => InternalFormat::SRGB8_ALPHA8_ASTC_5x5,
+            SRGB8_ALPHA8_ASTC_6x5 => InternalFormat::SRGB8_ALPHA8_ASTC_6x5,
+            SRGB8_ALPHA8_ASTC_6x6 => InternalFormat::SRGB8_ALPHA8_ASTC_6x6,
+            SRGB8_ALPHA8_ASTC_8x5 => InternalFormat::SRGB8_ALPHA8_ASTC_8x5,
+            SRGB8_ALPHA8_ASTC_8x6 => InternalFormat::SRGB8_ALPHA8_ASTC_8x6,
+            SRGB8_ALPHA8_ASTC_8x8 => InternalFormat::SRGB8_ALPHA8_ASTC_8x8,
+            SRGB8_ALPHA8_ASTC_10x5 => InternalFormat::SRGB8_ALPHA8_ASTC_10x5,
+            SRGB8_ALPHA8_ASTC_10x6 => InternalFormat::SRGB8_ALPHA8_ASTC_10x6,
+            SRGB8_ALPHA8_ASTC_10x8 => InternalFormat::SRGB8_ALPHA8_ASTC_10x8,
+            SRGB8_ALPHA8_ASTC_10x10 => InternalFormat::SRGB8_ALPHA8_ASTC_10x10,
+            SRGB8_ALPHA8_ASTC_12x10 => InternalFormat::SRGB8_ALPHA8_ASTC_12x10,
+            SRGB8_ALPHA8_ASTC_12x12 => InternalFormat::SRGB8_ALPHA8_ASTC_12x12,
+        }
+    }
+
+    /// Whether mipmaps can be generated on the GPU for `format`, which must be both color
+    /// renderable and filterable.
+    pub fn is_format_mipmappable(format: InternalFormat) -> bool {
+        match format {
+            InternalFormat::R8
+            | InternalFormat::R16F
+            | InternalFormat::RG8
+            | InternalFormat::RG16F
+            | InternalFormat::RGB565
+            | InternalFormat::RGB5_A1
+            | InternalFormat::RGBA4
+            | InternalFormat::RGB8
+            | InternalFormat::R11F_G11F_B10F
+            | InternalFormat::RGBA8
+            | InternalFormat::SRGB8_A8
+            | InternalFormat::RGB10_A2
+            | InternalFormat::RGBA16F => true,
+            _ => false,
+        }
+    }
+
+    /// The number of levels of a full mip chain for a texture of the given size.
+    pub fn max_level_count(width: usize, height: usize) -> usize {
+        let size = width.max(height).max(1);
+        (std::mem::size_of::<usize>() * 8) - size.leading_zeros() as usize
+    }
+
     #[inline]
     pub fn width(&self, level: usize) -> usize {
         unsafe {
@@ -101,6 +303,69 @@ impl Texture {
         unsafe { self.as_raw_ref().getFormat() }
     }
 
+    #[inline]
+    pub fn usage(&self) -> TextureUsage {
+        self.usage
+    }
+
+    /// Generates all the mip levels of this texture from level 0 on the GPU.
+    pub fn generate_mipmaps(&mut self) -> Result<(), TextureError> {
+        let format = self.format();
+        if !Self::is_format_mipmappable(format) {
+            return Err(TextureError::NotMipmappable(format));
+        }
+        if !self.usage.contains(TextureUsage::SAMPLEABLE) {
+            return Err(TextureError::InvalidUsage(self.usage));
+        }
+        if self.levels() < 2 {
+            return Err(TextureError::SingleLevel);
+        }
+
+        unsafe {
+            let engine = self.engine.as_raw_ptr();
+            self.as_raw_ref().generateMipmaps(engine)
+        };
+
+        Ok(())
+    }
+
+    /// Fills every level of a cubemap with a prefiltered reflection map computed from an
+    /// environment map, replacing calls to `set_cube_faces`.
+    ///
+    /// `buffer` holds the six faces of the environment as tightly packed RGB floats, in `CubeMapFace`
+    /// order, and the cubemap must be a square power of two.
+    pub fn generate_prefilter_mipmap<T: Sized>(
+        &mut self,
+        buffer: Vec<T>,
+        options: PrefilterOptions,
+    ) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_CUBEMAP)?;
+
+        let (width, height) = (self.width(0), self.height(0));
+        if width != height || !width.is_power_of_two() {
+            return Err(TextureError::NotPowerOfTwo { width, height });
+        }
+
+        let (format, ty) = (PixelDataFormat::RGB, PixelDataType::FLOAT);
+        let face_size = Self::compute_data_size(format, ty, width, height, 1);
+        check_data_size(&buffer, face_size * 6)?;
+
+        let face_offsets = make_face_offsets(face_size);
+        let mut desc = make_pixel_buffer_descriptor(buffer, format, ty);
+        unsafe {
+            let engine = self.engine.as_raw_ptr();
+            self.as_raw_mut().generatePrefilterMipmap(
+                engine,
+                &mut desc as *mut _,
+                &face_offsets as *const _,
+                &options as *const _,
+            )
+        };
+        std::mem::forget(desc);
+
+        Ok(())
+    }
+
     pub fn set<T: Sized>(
         &mut self,
         level: usize,
@@ -117,14 +382,345 @@ impl Texture {
         std::mem::forget(desc);
     }
 
-    #[inline]
-    pub fn set_external_image<T: Sized>(&mut self, engine: &mut Engine, data: &[T]) {
+    /// Uploads a region of a mip level of a 2D or 2D array texture. Regions with a depth or z offset
+    /// address the layers of a 2D array texture. Cubemaps are uploaded with `set_cube_faces`.
+    pub fn set_region<T: Sized>(
+        &mut self,
+        level: usize,
+        region: TextureRegion,
+        buffer: Vec<T>,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+    ) -> Result<(), TextureError> {
+        self.check_region(level, region)?;
+        check_data_size(&buffer, region.data_size(format, ty))?;
+
+        let desc = make_pixel_buffer_descriptor(buffer, format, ty);
+        self.upload_region(level, region, desc);
+
+        Ok(())
+    }
+
+    /// Uploads a region of a mip level from block compressed data, to the same textures as
+    /// `set_region`. The region must be aligned to the block size of `ty`, except where it reaches
+    /// the edge of the level.
+    pub fn set_compressed_region<T: Sized>(
+        &mut self,
+        level: usize,
+        region: TextureRegion,
+        buffer: Vec<T>,
+        ty: CompressedType,
+    ) -> Result<(), TextureError> {
+        self.check_region(level, region)?;
+        self.check_block_alignment(level, region, ty)?;
+        let size =
+            Self::compute_compressed_data_size(ty, region.width as usize, region.height as usize)
+                * region.depth as usize;
+        check_data_size(&buffer, size)?;
+
+        let desc = make_compressed_pixel_buffer_descriptor(buffer, ty, size);
+        self.upload_region(level, region, desc);
+
+        Ok(())
+    }
+
+    /// Uploads a single layer of a 2D array texture.
+    pub fn set_layer<T: Sized>(
+        &mut self,
+        level: usize,
+        layer: u32,
+        buffer: Vec<T>,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+    ) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_2D_ARRAY)?;
+
+        let region = TextureRegion::new_3d(
+            0,
+            0,
+            layer,
+            self.width(level).try_into().unwrap(),
+            self.height(level).try_into().unwrap(),
+            1,
+        );
+        self.set_region(level, region, buffer, format, ty)
+    }
+
+    /// Uploads all six faces of a cubemap level. `buffer` holds the faces tightly packed one after
+    /// the other, in `CubeMapFace` order (+X, -X, +Y, -Y, +Z, -Z).
+    pub fn set_cube_faces<T: Sized>(
+        &mut self,
+        level: usize,
+        buffer: Vec<T>,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+    ) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_CUBEMAP)?;
+        self.check_level(level)?;
+
+        let face_size =
+            Self::compute_data_size(format, ty, self.width(level), self.height(level), 1);
+        check_data_size(&buffer, face_size * 6)?;
+
+        let desc = make_pixel_buffer_descriptor(buffer, format, ty);
+        self.upload_cube_faces(level, desc, face_size);
+
+        Ok(())
+    }
+
+    /// Uploads all six faces of a cubemap level from block compressed data, tightly packed in
+    /// `CubeMapFace` order.
+    pub fn set_compressed_cube_faces<T: Sized>(
+        &mut self,
+        level: usize,
+        buffer: Vec<T>,
+        ty: CompressedType,
+    ) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_CUBEMAP)?;
+        self.check_level(level)?;
+
+        let face_size =
+            Self::compute_compressed_data_size(ty, self.width(level), self.height(level));
+        check_data_size(&buffer, face_size * 6)?;
+
+        // Each face is uploaded on its own, with the size of a single face
+        let desc = make_compressed_pixel_buffer_descriptor(buffer, ty, face_size);
+        self.upload_cube_faces(level, desc, face_size);
+
+        Ok(())
+    }
+
+    fn upload_region(
+        &mut self,
+        level: usize,
+        region: TextureRegion,
+        mut desc: PixelBufferDescriptor,
+    ) {
         unsafe {
-            self.as_raw_mut()
-                .setExternalImage(engine.as_raw_ptr(), data.as_ptr() as *mut _)
+            let engine = self.engine.as_raw_ptr();
+            if self.target() == SamplerType::SAMPLER_2D {
+                self.as_raw_mut().setImage1(
+                    engine,
+                    level.try_into().unwrap(),
+                    region.x,
+                    region.y,
+                    region.width,
+                    region.height,
+                    &mut desc as *mut _,
+                )
+            } else {
+                ffi::helpers_texture_setImage3D(
+                    self.as_raw_ptr(),
+                    engine,
+                    level.try_into().unwrap(),
+                    region.x,
+                    region.y,
+                    region.z,
+                    region.width,
+                    region.height,
+                    region.depth,
+                    &mut desc as *mut _,
+                )
+            }
+        };
+        std::mem::forget(desc);
+    }
+
+    fn upload_cube_faces(
+        &mut self,
+        level: usize,
+        mut desc: PixelBufferDescriptor,
+        face_size: usize,
+    ) {
+        let face_offsets = make_face_offsets(face_size);
+        unsafe {
+            let engine = self.engine.as_raw_ptr();
+            self.as_raw_mut().setImage2(
+                engine,
+                level.try_into().unwrap(),
+                &mut desc as *mut _,
+                &face_offsets as *const _,
+            )
+        };
+        std::mem::forget(desc);
+    }
+
+    /// Reads back a region of a mip level from the GPU, blocking until the copy completes. `region.z`
+    /// selects the cubemap face or array layer, and `region.depth` must be 1.
+    ///
+    /// The texture needs the `COLOR_ATTACHMENT` usage, and rows are returned bottom to top. A render
+    /// target is created for every call, so use `Renderer::read_pixels` with a kept `RenderTarget`
+    /// to read the same level repeatedly.
+    pub fn read(
+        &self,
+        renderer: &mut Renderer,
+        level: usize,
+        region: TextureRegion,
+        format: PixelDataFormat,
+        ty: PixelDataType,
+    ) -> Result<Vec<u8>, TextureError> {
+        if !self.usage.contains(TextureUsage::COLOR_ATTACHMENT) {
+            return Err(TextureError::InvalidUsage(self.usage));
+        }
+
+        let target = self.target();
+        let layers = if target == SamplerType::SAMPLER_CUBEMAP {
+            6
+        } else {
+            self.layer_count(level)
+        };
+        let fits = level < self.levels()
+            && region.depth == 1
+            && (region.z as usize) < layers
+            && region.x as usize + region.width as usize <= self.width(level)
+            && region.y as usize + region.height as usize <= self.height(level);
+        if !fits {
+            return Err(TextureError::RegionOutOfBounds { region, level });
+        }
+
+        let mut builder = RenderTarget::builder().color(self).mip_level(level);
+        builder = if target == SamplerType::SAMPLER_CUBEMAP {
+            builder.face(cube_map_face(region.z))
+        } else {
+            builder.layer(region.z)
+        };
+
+        let render_target = builder.build(&mut self.engine.clone())?;
+        renderer.read_pixels(&render_target, region, format, ty)
+    }
+
+    fn check_target(&self, expected: SamplerType) -> Result<(), TextureError> {
+        let actual = self.target();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(TextureError::InvalidTarget { expected, actual })
+        }
+    }
+
+    fn check_level(&self, level: usize) -> Result<(), TextureError> {
+        let levels = self.levels();
+        if level < levels {
+            Ok(())
+        } else {
+            Err(TextureError::LevelOutOfBounds { level, levels })
+        }
+    }
+
+    /// The number of layers of `level`. Filament halves the depth at each level, but the layers of
+    /// a 2D array texture are the same at every level.
+    fn layer_count(&self, level: usize) -> usize {
+        if self.target() == SamplerType::SAMPLER_2D_ARRAY {
+            self.depth(0)
+        } else {
+            self.depth(level)
         }
     }
 
+    fn check_block_alignment(
+        &self,
+        level: usize,
+        region: TextureRegion,
+        ty: CompressedType,
+    ) -> Result<(), TextureError> {
+        let (block_width, block_height, _) = compressed_block_size(ty);
+        let aligned = |offset: u32, size: u32, level_size: usize, block: usize| {
+            let (offset, end) = (offset as usize, offset as usize + size as usize);
+            offset % block == 0 && (end % block == 0 || end == level_size)
+        };
+        if aligned(region.x, region.width, self.width(level), block_width)
+            && aligned(region.y, region.height, self.height(level), block_height)
+        {
+            Ok(())
+        } else {
+            Err(TextureError::UnalignedRegion {
+                region,
+                block_width,
+                block_height,
+            })
+        }
+    }
+
+    fn check_region(&self, level: usize, region: TextureRegion) -> Result<(), TextureError> {
+        let actual = self.target();
+        match actual {
+            SamplerType::SAMPLER_2D | SamplerType::SAMPLER_2D_ARRAY => {}
+            _ => {
+                return Err(TextureError::InvalidTarget {
+                    expected: SamplerType::SAMPLER_2D,
+                    actual,
+                })
+            }
+        }
+
+        let fits = level < self.levels()
+            && region.x as usize + region.width as usize <= self.width(level)
+            && region.y as usize + region.height as usize <= self.height(level)
+            && region.z as usize + region.depth as usize <= self.layer_count(level);
+        if fits {
+            Ok(())
+        } else {
+            Err(TextureError::RegionOutOfBounds { region, level })
+        }
+    }
+
+    /// Attaches a platform image, such as a video or camera frame, to a `SAMPLER_EXTERNAL` texture.
+    /// Only level 0 exists, and the size and format of the texture are those of the image.
+    pub fn set_external_image(&mut self, image: ExternalImage) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_EXTERNAL)?;
+        unsafe {
+            let engine = self.engine.as_raw_ptr();
+            self.as_raw_mut().setExternalImage(engine, image.as_raw())
+        };
+        Ok(())
+    }
+
+    /// Attaches one plane of a multi-planar platform image to a `SAMPLER_EXTERNAL` texture. Only
+    /// meaningful for bi-planar YCbCr images on iOS, and a no-op on other platforms.
+    pub fn set_external_image_plane(
+        &mut self,
+        image: ExternalImage,
+        plane: usize,
+    ) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_EXTERNAL)?;
+        unsafe {
+            let engine = self.engine.as_raw_ptr();
+            self.as_raw_mut()
+                .setExternalImage1(engine, image.as_raw(), plane.try_into().unwrap())
+        };
+        Ok(())
+    }
+
+    /// Samples the images of `stream` through this `SAMPLER_EXTERNAL` texture. The stream must
+    /// outlive its use by this texture.
+    pub fn set_external_stream(&mut self, stream: &Stream) -> Result<(), TextureError> {
+        self.check_target(SamplerType::SAMPLER_EXTERNAL)?;
+        unsafe {
+            let engine = self.engine.as_raw_ptr();
+            self.as_raw_mut()
+                .setExternalStream(engine, stream.as_raw_ptr())
+        };
+        Ok(())
+    }
+
+    /// A 1x1 RGBA8 texture filled with `color`, to bind to samplers that have no image.
+    pub fn solid_color(engine: &mut Engine, color: [u8; 4]) -> Result<Texture, TextureError> {
+        let mut texture = Texture::builder()?
+            .width(1)
+            .height(1)
+            .sampler(SamplerType::SAMPLER_2D)
+            .format(InternalFormat::RGBA8)
+            .build(engine)?;
+        texture.set_region(
+            0,
+            TextureRegion::new(0, 0, 1, 1),
+            color.to_vec(),
+            PixelDataFormat::RGBA,
+            PixelDataType::UBYTE,
+        )?;
+        Ok(texture)
+    }
+
     #[inline]
     pub fn builder() -> Result<TextureBuilder, EngineError> {
         TextureBuilder::new()
@@ -133,6 +729,7 @@ impl Texture {
 
 pub struct TextureBuilder {
     inner: ffi::filament_Texture_Builder,
+    usage: TextureUsage,
 }
 impl Drop for TextureBuilder {
     fn drop(&mut self) {
@@ -149,6 +746,7 @@ impl TextureBuilder {
             Ok(Texture {
                 ptr: Arc::new(ptr),
                 engine: engine.clone(),
+                usage: self.usage,
             })
         }
     }
@@ -191,7 +789,8 @@ impl TextureBuilder {
 
     #[inline]
     pub fn usage(mut self, usage: TextureUsage) -> Self {
-        unsafe { self.inner.usage(usage) };
+        unsafe { self.inner.usage(ffi::filament_Texture_Usage(usage.bits())) };
+        self.usage = usage;
         self
     }
 
@@ -217,10 +816,66 @@ impl TextureBuilder {
     pub fn new() -> Result<Self, EngineError> {
         Ok(Self {
             inner: unsafe { ffi::filament_Texture_Builder::new() },
+            usage: TextureUsage::DEFAULT,
         })
     }
 }
 
+fn check_data_size<T: Sized>(buffer: &[T], expected: usize) -> Result<(), TextureError> {
+    let actual = buffer.len() * std::mem::size_of::<T>();
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(TextureError::DataSizeMismatch { expected, actual })
+    }
+}
+
+/// The width and height in texels, and the size in bytes, of a single block of `ty`.
+pub fn compressed_block_size(ty: CompressedType) -> (usize, usize, usize) {
+    use CompressedType::*;
+    match ty {
+        EAC_R11 | EAC_R11_SIGNED | ETC2_RGB8 | ETC2_SRGB8 | ETC2_RGB8_A1 | ETC2_SRGB8_A1
+        | DXT1_RGB | DXT1_RGBA | DXT1_SRGB | DXT1_SRGBA => (4, 4, 8),
+        EAC_RG11 | EAC_RG11_SIGNED | ETC2_EAC_RGBA8 | ETC2_EAC_SRGBA8 | DXT3_RGBA | DXT5_RGBA
+        | DXT3_SRGBA | DXT5_SRGBA => (4, 4, 16),
+        RGBA_ASTC_4x4 | SRGB8_ALPHA8_ASTC_4x4 => (4, 4, 16),
+        RGBA_ASTC_5x4 | SRGB8_ALPHA8_ASTC_5x4 => (5, 4, 16),
+        RGBA_ASTC_5x5 | SRGB8_ALPHA8_ASTC_5x5 => (5, 5, 16),
+        RGBA_ASTC_6x5 | SRGB8_ALPHA8_ASTC_6x5 => (6, 5, 16),
+        RGBA_ASTC_6x6 | SRGB8_ALPHA8_ASTC_6x6 => (6, 6, 16),
+        RGBA_ASTC_8x5 | SRGB8_ALPHA8_ASTC_8x5 => (8, 5, 16),
+        RGBA_ASTC_8x6 | SRGB8_ALPHA8_ASTC_8x6 => (8, 6, 16),
+        RGBA_ASTC_8x8 | SRGB8_ALPHA8_ASTC_8x8 => (8, 8, 16),
+        RGBA_ASTC_10x5 | SRGB8_ALPHA8_ASTC_10x5 => (10, 5, 16),
+        RGBA_ASTC_10x6 | SRGB8_ALPHA8_ASTC_10x6 => (10, 6, 16),
+        RGBA_ASTC_10x8 | SRGB8_ALPHA8_ASTC_10x8 => (10, 8, 16),
+        RGBA_ASTC_10x10 | SRGB8_ALPHA8_ASTC_10x10 => (10, 10, 16),
+        RGBA_ASTC_12x10 | SRGB8_ALPHA8_ASTC_12x10 => (12, 10, 16),
+        RGBA_ASTC_12x12 | SRGB8_ALPHA8_ASTC_12x12 => (12, 12, 16),
+    }
+}
+
+fn cube_map_face(index: u32) -> CubeMapFace {
+    match index {
+        0 => CubeMapFace::POSITIVE_X,
+        1 => CubeMapFace::NEGATIVE_X,
+        2 => CubeMapFace::POSITIVE_Y,
+        3 => CubeMapFace::NEGATIVE_Y,
+        4 => CubeMapFace::POSITIVE_Z,
+        _ => CubeMapFace::NEGATIVE_Z,
+    }
+}
+
+/// Builds the offsets of six tightly packed cubemap faces of `face_size` bytes each.
+pub fn make_face_offsets(face_size: usize) -> FaceOffsets {
+    let mut face_offsets: FaceOffsets = unsafe { std::mem::zeroed() };
+    let offsets = unsafe { face_offsets.__bindgen_anon_1.offsets.as_mut() };
+    for (i, offset) in offsets.iter_mut().enumerate() {
+        *offset = (face_size * i).try_into().unwrap();
+    }
+    face_offsets
+}
+
 pub fn make_pixel_buffer_descriptor<T: Sized>(
     mut data: Vec<T>,
     format: PixelDataFormat,
@@ -240,10 +895,36 @@ pub fn make_pixel_buffer_descriptor<T: Sized>(
     desc
 }
 
+/// Wraps block compressed `data`, where `image_size` is the size of the image uploaded at once: the
+/// whole region, or one face for cubemaps.
+pub fn make_compressed_pixel_buffer_descriptor<T: Sized>(
+    mut data: Vec<T>,
+    ty: CompressedType,
+    image_size: usize,
+) -> PixelBufferDescriptor {
+    let size = data.len() * std::mem::size_of::<T>();
+    let desc = unsafe {
+        ffi::helpers_make_CompressedPixelBufferDescriptor(
+            data.as_mut_ptr() as *mut _,
+            size.try_into().unwrap(),
+            Some(filament_sys::deallocate_rust_buffer),
+            std::ptr::null_mut(),
+            ty,
+            image_size.try_into().unwrap(),
+        )
+    };
+    std::mem::forget(data);
+    desc
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{InternalFormat, Texture, TextureSampler};
-    use crate::{engine::Engine, sys::ffi, Backend};
+    use super::{
+        make_face_offsets, CompressedType, InternalFormat, PixelDataFormat, PixelDataType,
+        SamplerMinFilter, SamplerType, SamplerWrapMode, Texture, TextureError, TextureRegion,
+        TextureSampler, TextureSamplerBuilder, TextureUsage,
+    };
+    use crate::{engine::Engine, renderer::Renderer, sys::ffi, Backend};
 
     #[test]
     fn texture_creation() {
@@ -263,4 +944,271 @@ mod tests {
 
         unsafe { assert!(ffi::test_texture_eq(texture.as_raw_ptr())) };
     }
+
+    #[test]
+    fn texture_upload_checks() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut texture = Texture::builder()
+            .unwrap()
+            .width(4)
+            .height(4)
+            .format(InternalFormat::RGB8)
+            .build(&mut engine)
+            .unwrap();
+
+        let region = TextureRegion::new(2, 2, 2, 2);
+        assert_eq!(
+            region.data_size(PixelDataFormat::RGB, PixelDataType::UBYTE),
+            12
+        );
+        assert!(texture
+            .set_region(
+                0,
+                region,
+                vec![0u8; 12],
+                PixelDataFormat::RGB,
+                PixelDataType::UBYTE
+            )
+            .is_ok());
+        assert!(matches!(
+            texture.set_region(
+                0,
+                region,
+                vec![0u8; 11],
+                PixelDataFormat::RGB,
+                PixelDataType::UBYTE
+            ),
+            Err(TextureError::DataSizeMismatch {
+                expected: 12,
+                actual: 11
+            })
+        ));
+        assert!(matches!(
+            texture.set_region(
+                0,
+                TextureRegion::new(3, 3, 2, 2),
+                vec![0u8; 12],
+                PixelDataFormat::RGB,
+                PixelDataType::UBYTE
+            ),
+            Err(TextureError::RegionOutOfBounds { .. })
+        ));
+        // The end of this region wraps around in u32
+        assert!(matches!(
+            texture.set_region(
+                0,
+                TextureRegion::new(u32::MAX, 0, 2, 2),
+                vec![0u8; 12],
+                PixelDataFormat::RGB,
+                PixelDataType::UBYTE
+            ),
+            Err(TextureError::RegionOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            texture.set_cube_faces(
+                0,
+                vec![0u8; 48 * 6],
+                PixelDataFormat::RGB,
+                PixelDataType::UBYTE
+            ),
+            Err(TextureError::InvalidTarget { .. })
+        ));
+    }
+
+    #[test]
+    fn compressed_region_alignment() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut texture = Texture::builder()
+            .unwrap()
+            .width(10)
+            .height(10)
+            .format(InternalFormat::ETC2_RGB8)
+            .build(&mut engine)
+            .unwrap();
+
+        // 4x4 blocks of 8 bytes, partial blocks only at the right and bottom edges
+        let ty = CompressedType::ETC2_RGB8;
+        assert!(texture
+            .set_compressed_region(0, TextureRegion::new(4, 8, 6, 2), vec![0u8; 16], ty)
+            .is_ok());
+        assert!(matches!(
+            texture.set_compressed_region(0, TextureRegion::new(2, 0, 4, 4), vec![0u8; 16], ty),
+            Err(TextureError::UnalignedRegion {
+                block_width: 4,
+                block_height: 4,
+                ..
+            })
+        ));
+        assert!(matches!(
+            texture.set_compressed_region(0, TextureRegion::new(0, 0, 4, 6), vec![0u8; 16], ty),
+            Err(TextureError::UnalignedRegion { .. })
+        ));
+    }
+
+    #[test]
+    fn texture_upload_targets() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let mut cubemap = Texture::builder()
+            .unwrap()
+            .width(4)
+            .height(4)
+            .sampler(SamplerType::SAMPLER_CUBEMAP)
+            .format(InternalFormat::RGBA8)
+            .build(&mut engine)
+            .unwrap();
+        assert!(matches!(
+            cubemap.set_region(
+                0,
+                TextureRegion::new(0, 0, 4, 4),
+                vec![0u8; 64],
+                PixelDataFormat::RGBA,
+                PixelDataType::UBYTE
+            ),
+            Err(TextureError::InvalidTarget { .. })
+        ));
+        assert!(cubemap
+            .set_cube_faces(
+                0,
+                vec![0u8; 64 * 6],
+                PixelDataFormat::RGBA,
+                PixelDataType::UBYTE
+            )
+            .is_ok());
+
+        // Array layers do not shrink with the mip level
+        let mut array = Texture::builder()
+            .unwrap()
+            .width(4)
+            .height(4)
+            .depth(3)
+            .levels(3)
+            .sampler(SamplerType::SAMPLER_2D_ARRAY)
+            .format(InternalFormat::RGBA8)
+            .build(&mut engine)
+            .unwrap();
+        for level in 0..3 {
+            let size = (4 >> level) as u32;
+            let region = TextureRegion::new_3d(0, 0, 0, size, size, 3);
+            let data = vec![0u8; (size * size * 4 * 3) as usize];
+            assert!(array
+                .set_region(
+                    level,
+                    region,
+                    data,
+                    PixelDataFormat::RGBA,
+                    PixelDataType::UBYTE
+                )
+                .is_ok());
+        }
+        assert!(array
+            .set_layer(
+                2,
+                2,
+                vec![0u8; 4],
+                PixelDataFormat::RGBA,
+                PixelDataType::UBYTE
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn mip_level_count() {
+        assert_eq!(Texture::max_level_count(1, 1), 1);
+        assert_eq!(Texture::max_level_count(256, 256), 9);
+        assert_eq!(Texture::max_level_count(300, 20), 9);
+        assert!(Texture::is_format_mipmappable(InternalFormat::RGBA8));
+        assert!(!Texture::is_format_mipmappable(InternalFormat::DEPTH24));
+        assert!(!Texture::is_format_mipmappable(InternalFormat::ETC2_RGB8));
+    }
+
+    #[test]
+    fn texture_readback() {
+        let mut engine = Engine::new(Backend::NOOP).unwrap();
+        let texture = Texture::builder()
+            .unwrap()
+            .width(4)
+            .height(4)
+            .format(InternalFormat::RGBA8)
+            .usage(TextureUsage::COLOR_ATTACHMENT | TextureUsage::SAMPLEABLE)
+            .build(&mut engine)
+            .unwrap();
+
+        let mut renderer = Renderer::new(&mut engine).unwrap();
+        let (format, ty) = (PixelDataFormat::RGBA, PixelDataType::UBYTE);
+        let pixels = texture
+            .read(&mut renderer, 0, TextureRegion::new(1, 1, 2, 3), format, ty)
+            .unwrap();
+        assert_eq!(pixels.len(), 2 * 3 * 4);
+        assert!(matches!(
+            texture.read(&mut renderer, 0, TextureRegion::new(3, 0, 2, 1), format, ty),
+            Err(TextureError::RegionOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            texture.read(
+                &mut renderer,
+                0,
+                TextureRegion::new(0, u32::MAX, 1, 2),
+                format,
+                ty
+            ),
+            Err(TextureError::RegionOutOfBounds { .. })
+        ));
+
+        let sampled_only = Texture::builder()
+            .unwrap()
+            .width(4)
+            .height(4)
+            .format(InternalFormat::RGBA8)
+            .build(&mut engine)
+            .unwrap();
+        assert!(matches!(
+            sampled_only.read(&mut renderer, 0, TextureRegion::new(0, 0, 4, 4), format, ty),
+            Err(TextureError::InvalidUsage(_))
+        ));
+    }
+
+    #[test]
+    fn usage_and_sampler_options() {
+        assert_eq!(
+            TextureUsage::default(),
+            TextureUsage::UPLOADABLE | TextureUsage::SAMPLEABLE
+        );
+        assert_eq!(
+            ffi::filament_Texture_Usage(TextureUsage::COLOR_ATTACHMENT.bits()),
+            ffi::filament_Texture_Usage::COLOR_ATTACHMENT
+        );
+
+        let builder = TextureSamplerBuilder::default()
+            .filter_min(SamplerMinFilter::LinearMipmapLinear)
+            .wrap(SamplerWrapMode::Repeat);
+        assert_eq!(builder.wrap_r, SamplerWrapMode::Repeat);
+        assert_eq!(
+            ffi::filament_backend_SamplerMinFilter::from(builder.filter_min),
+            ffi::filament_backend_SamplerMinFilter::LINEAR_MIPMAP_LINEAR
+        );
+    }
+
+    #[test]
+    fn compressed_sizes() {
+        let size = Texture::compute_compressed_data_size;
+        assert_eq!(size(CompressedType::ETC2_RGB8, 4, 4), 8);
+        assert_eq!(size(CompressedType::DXT5_RGBA, 5, 1), 32);
+        assert_eq!(size(CompressedType::RGBA_ASTC_10x8, 20, 9), 64);
+
+        assert_eq!(
+            Texture::compressed_internal_format(CompressedType::EAC_R11),
+            InternalFormat::EAC_R11
+        );
+        assert_eq!(
+            Texture::compressed_internal_format(CompressedType::SRGB8_ALPHA8_ASTC_12x12),
+            InternalFormat::SRGB8_ALPHA8_ASTC_12x12
+        );
+    }
+
+    #[test]
+    fn face_offsets() {
+        let mut face_offsets = make_face_offsets(16);
+        let offsets = unsafe { face_offsets.__bindgen_anon_1.offsets.as_mut() };
+        assert_eq!(offsets, &[0, 16, 32, 48, 64, 80]);
+    }
 }
@@ -1,11 +1,9 @@
-use filament_sys::ffi;
+use filament_sys::{ffi, Mat4f};
 use std::{convert::TryInto, sync::Arc};
 
 use crate::{
     engine::{Engine, EngineError},
-    impl_handle,
-    sys::{FromFilament, IntoFilament},
-    Entity,
+    impl_handle, Entity,
 };
 
 pub use ffi::filament_TransformManager_Instance as TransformInstance;
@@ -16,12 +14,19 @@ pub struct TransformManager<'a> {
     _marker: std::marker::PhantomData<&'a ()>,
 }
 impl<'a> TransformManager<'a> {
-    pub fn create(
+    /// Adds a transform component to `entity`, optionally parented to another transform.
+    pub fn create<M: Into<Mat4f>>(
         &mut self,
         entity: Entity,
         parent: Option<TransformInstance>,
-        transform: [f64; 16],
+        transform: M,
     ) {
+        let transform = transform.into();
+        unsafe { (*self.ptr).create(entity, parent.unwrap_or(0), &transform as *const _) }
+    }
+
+    pub fn has_component(&self, entity: Entity) -> bool {
+        unsafe { (*self.ptr).hasComponent(entity) }
     }
 
     pub fn get(&self, entity: Entity) -> Option<TransformInstance> {
@@ -33,22 +38,43 @@ impl<'a> TransformManager<'a> {
         }
     }
 
-    pub fn set_transform(&mut self, instance: TransformInstance, transform: [f32; 16]) {
-        unsafe { (*self.ptr).setTransform(instance, &transform.into_filament() as *const _) }
+    pub fn set_transform<M: Into<Mat4f>>(&mut self, instance: TransformInstance, transform: M) {
+        let transform = transform.into();
+        unsafe { (*self.ptr).setTransform(instance, &transform as *const _) }
     }
-    pub fn get_transform(&self, instance: TransformInstance) -> Option<[f32; 16]> {
+    pub fn get_transform(&self, instance: TransformInstance) -> Option<Mat4f> {
         let ptr = unsafe { (*self.ptr).getTransform(instance) };
         if ptr.is_null() {
             None
         } else {
-            Some(unsafe { (*ptr).into_array() })
+            Some(unsafe { *ptr })
+        }
+    }
+    /// The transform of `instance` relative to the world, taking every parent into account.
+    pub fn get_world_transform(&self, instance: TransformInstance) -> Option<Mat4f> {
+        let ptr = unsafe { (*self.ptr).getWorldTransform(instance) };
+        if ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { *ptr })
         }
     }
-    pub fn remove(&mut self, instance: TransformInstance) {}
 
-    pub fn set_parent(&mut self, instance: TransformInstance, parent: Option<TransformInstance>) {}
+    /// Removes the transform component from `entity`, the entity itself is left alive.
+    pub fn remove(&mut self, entity: Entity) {
+        unsafe { (*self.ptr).destroy(entity) }
+    }
+
+    pub fn set_parent(&mut self, instance: TransformInstance, parent: Option<TransformInstance>) {
+        unsafe { (*self.ptr).setParent(instance, parent.unwrap_or(0)) }
+    }
     pub fn get_parent(&self, instance: TransformInstance) -> Option<TransformInstance> {
-        unimplemented!()
+        let parent = unsafe { (*self.ptr).getParent(instance) };
+        if parent.is_null() {
+            None
+        } else {
+            self.get(parent)
+        }
     }
 
     pub(crate) fn new(engine: &'a Engine) -> Result<TransformManager<'a>, EngineError> {
@@ -68,13 +94,13 @@ impl<'a> TransformManager<'a> {
 mod tests {
     use super::TransformManager;
     use crate::{engine::Engine, entity_manager::EntityManager, sys::ffi, Backend, Camera};
+    use filament_sys::Mat4f;
 
     #[test]
     fn transform_setget() {
+        let identity = Mat4f::IDENTITY;
         #[rustfmt::skip]
-        let identity = [1.0,0.0,0.0,0.0,0.0,1.0,0.0,0.0,0.0,0.0,1.0,0.0,0.0,0.0,0.0,1.0,];
-        #[rustfmt::skip]
-        let test_transform = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, ];
+        let test_transform = Mat4f::from([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, ]);
 
         let mut engine = Engine::new(Backend::NOOP).unwrap();
         let camera_entity = EntityManager::get().create();
@@ -100,4 +126,23 @@ mod tests {
             );
         }
     }
+    #[test]
+    fn transform_hierarchy() {
+        let engine = Engine::new(Backend::NOOP).unwrap();
+        let entities = EntityManager::get().create_vec(2);
+        let mut transform_manager = engine.transform_manager().unwrap();
+
+        transform_manager.create(entities[0], None, Mat4f::IDENTITY);
+        let parent = transform_manager.get(entities[0]).unwrap();
+        transform_manager.create(entities[1], Some(parent), Mat4f::IDENTITY);
+        let child = transform_manager.get(entities[1]).unwrap();
+
+        assert_eq!(transform_manager.get_parent(child), Some(parent));
+        transform_manager.set_parent(child, None);
+        assert_eq!(transform_manager.get_parent(child), None);
+
+        transform_manager.remove(entities[1]);
+        assert!(!transform_manager.has_component(entities[1]));
+        EntityManager::get().destroy(&entities);
+    }
 }
@@ -7,6 +7,7 @@ use crate::{
     camera::Camera,
     engine::{Engine, EngineError},
     impl_handle,
+    render_target::RenderTarget,
     scene::Scene,
 };
 
@@ -112,9 +113,21 @@ impl View {
     pub fn set_camera(&mut self, camera: &Camera) {
         unsafe { self.as_raw_mut().setCamera(camera.as_raw_ptr()) }
     }
+    /// Renders into `render_target` instead of the swap chain, or back into the swap chain for
+    /// `None`.
+    pub fn set_render_target(&mut self, render_target: Option<&RenderTarget>) {
+        unsafe {
+            self.as_raw_mut().setRenderTarget(
+                render_target.map_or(std::ptr::null_mut(), |target| target.as_raw_ptr()),
+            )
+        }
+    }
     pub fn set_viewport(&mut self, viewport: Viewport) {
         unsafe { self.as_raw_mut().setViewport(&viewport as *const _ as _) }
     }
+    pub fn viewport(&self) -> Viewport {
+        unsafe { *(self.as_raw_ref().getViewport() as *const Viewport) }
+    }
     // TODO: getters, how do we return managed handle objects that dont destruct?
     // TODO: This may require the engine to store an index of all created objects
 
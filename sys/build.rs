@@ -1,40 +1,51 @@
 //CC=clang CXX=clang++ CXXFLAGS=-stdlib=libc++ cmake -DFILAMENT_ENABLE_JAVA=off -DCMAKE_BUILD_TYPE=Release -DCMAKE_INSTALL_PREFIX=../release/filament -DFILAMENT_SUPPORTS_VULKAN=ON -DUSE_STATIC_LIBCXX=off ../..
+//
+// Set FILAMENT_DIR to the root of a prebuilt Filament release (holding `include` and `lib`) to link
+// against it instead of building the `../filament` submodule. Enable the `libstdcxx` feature when
+// Filament was, or should be, built against libstdc++ rather than libc++.
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
 fn main() {
     println!("cargo:rerun-if-changed=proxy/wrapper.h");
+    println!("cargo:rerun-if-env-changed=FILAMENT_DIR");
 
-    // Build filament
-    let filament_dst = cmake::Config::new("../filament")
-        // Set compiler to clang
-        .define("CMAKE_C_COMPILER", "clang")
-        .define("CMAKE_CXX_COMPILER", "clang++")
-        // Set build specific settings
-        .define("FILAMENT_SKIP_SAMPLES", "on")
-        .define("FILAMENT_SUPPORTS_VULKAN", "on")
-        .define("USE_STATIC_LIBCXX", "off")
-        .cxxflag("-stdlib=libc++")
-        // do it fast
-        .build_arg("-j32")
-        .build();
+    let stdlib = if env::var_os("CARGO_FEATURE_LIBSTDCXX").is_some() {
+        "stdc++"
+    } else {
+        "c++"
+    };
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+
+    let (lib_dir, include_dirs) = match env::var_os("FILAMENT_DIR") {
+        Some(dir) => prebuilt_filament(Path::new(&dir), &arch),
+        None => build_filament(stdlib, &arch),
+    };
 
     // Linking and rebuild settings
-    println!(
-        "cargo:rustc-link-search=native={}/lib/{}",
-        filament_dst.display(),
-        std::env::var("CARGO_CFG_TARGET_ARCH").unwrap()
-    );
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
 
-    // Build the helper
+    // Build the helper, which also links the C++ standard library for Filament. Only clang
+    // understands `-stdlib`, other compilers just link the library.
     let mut builder = cc::Build::new();
+    if cxx_is_clang() {
+        builder.cpp_set_stdlib(stdlib);
+    } else {
+        builder.cpp_link_stdlib(stdlib);
+    }
+    for include in include_dirs.iter() {
+        builder.include(include);
+    }
     builder
-        .cpp_set_stdlib("c++")
-        .include("../filament/libs/math/include")
-        .include("../filament/libs/utils/include")
-        .include("../filament/libs/filabridge/include")
-        .include("../filament/filament/backend/include")
-        .include("../filament/filament/include")
         .file("src/cpp/helpers.cpp")
         .file("src/cpp/materials.cpp")
         .file("src/cpp/renderable_manager.cpp")
+        .file("src/cpp/textures.cpp")
+        .file("src/cpp/streams.cpp")
+        .file("src/cpp/entities.cpp")
+        .file("src/cpp/lights.cpp")
         .include("src");
 
     #[cfg(debug_assertions)]
@@ -55,9 +66,80 @@ fn main() {
     println!("cargo:rustc-link-lib=static=smol-v");
     println!("cargo:rustc-link-lib=static=ibl");
 
-    println!("cargo:rustc-link-lib=c++");
-
-    if cfg!(debug_assertions) {
+    // Release packages do not always ship the material debugger
+    if cfg!(debug_assertions) && lib_dir.join("libmatdbg.a").exists() {
         println!("cargo:rustc-link-lib=static=matdbg");
     }
 }
+
+/// Uses the headers and static libraries of a Filament release, as found on its GitHub releases.
+fn prebuilt_filament(dir: &Path, arch: &str) -> (PathBuf, Vec<PathBuf>) {
+    let include_dir = dir.join("include");
+    let lib_dir = dir.join("lib").join(arch);
+    for required in [&include_dir, &lib_dir].iter() {
+        if !required.is_dir() {
+            panic!(
+                "FILAMENT_DIR is set to {}, but {} does not exist. It should point to the root of a \
+                 Filament release, containing `include` and `lib/{}`.",
+                dir.display(),
+                required.display(),
+                arch
+            );
+        }
+    }
+
+    (lib_dir, vec![include_dir])
+}
+
+/// Builds the `../filament` submodule with CMake.
+fn build_filament(stdlib: &str, arch: &str) -> (PathBuf, Vec<PathBuf>) {
+    let source = Path::new("../filament");
+    if !source.join("CMakeLists.txt").exists() {
+        panic!(
+            "The Filament sources are missing from {}. Run `git submodule update --init`, or set \
+             FILAMENT_DIR to a prebuilt Filament release to link against it instead.",
+            source.display()
+        );
+    }
+
+    let mut config = cmake::Config::new(source);
+    // Default to clang, which Filament is developed with, unless a toolchain is given
+    if env::var_os("CC").is_none() {
+        config.define("CMAKE_C_COMPILER", "clang");
+    }
+    let clang = if env::var_os("CXX").is_none() {
+        config.define("CMAKE_CXX_COMPILER", "clang++");
+        true
+    } else {
+        cxx_is_clang()
+    };
+    config
+        // Set build specific settings
+        .define("FILAMENT_SKIP_SAMPLES", "on")
+        .define("FILAMENT_SUPPORTS_VULKAN", "on")
+        .define("USE_STATIC_LIBCXX", "off");
+    if clang {
+        config.cxxflag(format!("-stdlib=lib{}", stdlib));
+    }
+    // Cargo gives the number of jobs it allows
+    if let Ok(jobs) = env::var("NUM_JOBS") {
+        config.build_arg(format!("-j{}", jobs));
+    }
+    let filament_dst = config.build();
+
+    (
+        filament_dst.join("lib").join(arch),
+        vec![
+            source.join("libs/math/include"),
+            source.join("libs/utils/include"),
+            source.join("libs/filabridge/include"),
+            source.join("filament/backend/include"),
+            source.join("filament/include"),
+        ],
+    )
+}
+
+/// Whether the C++ compiler given by `CXX`, or the platform default, is clang.
+fn cxx_is_clang() -> bool {
+    cc::Build::new().cpp(true).get_compiler().is_like_clang()
+}
@@ -1,5 +1,15 @@
 /* automatically generated by rust-bindgen */
 
+pub type filament_math_float2 = crate::math::Float2;
+pub type filament_math_float3 = crate::math::Float3;
+pub type filament_math_float4 = crate::math::Float4;
+pub type filament_math_double3 = crate::math::Double3;
+pub type filament_math_double4 = crate::math::Double4;
+pub type filament_math_mat3f = crate::math::Mat3f;
+pub type filament_math_mat4f = crate::math::Mat4f;
+pub type filament_math_mat4 = crate::math::Mat4;
+pub type filament_math_quatf = crate::math::Quatf;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct __BindgenBitfieldUnit<Storage, Align> {
@@ -272,10 +282,6 @@ impl utils_EntityManager {
         utils_EntityManager_unregisterListener(self, l)
     }
 }
-pub type filament_math_float2 = [u32; 2usize];
-pub type filament_math_float3 = [u32; 3usize];
-pub type filament_math_float4 = [u32; 4usize];
-pub type filament_math_mat4 = u8;
 #[repr(u8)]
 #[doc = " Selects which driver a particular Engine should use."]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -835,23 +841,60 @@ pub enum filament_backend_TextureFormat {
     SRGB8_ALPHA8_ASTC_12x10 = 99,
     SRGB8_ALPHA8_ASTC_12x12 = 100,
 }
-#[repr(u8)]
-#[doc = "! Bitmask describing the intended Texture Usage"]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub enum filament_backend_TextureUsage {
+impl filament_backend_TextureUsage {
     #[doc = "!< Texture can be used as a color attachment"]
-    COLOR_ATTACHMENT = 1,
+    pub const COLOR_ATTACHMENT: filament_backend_TextureUsage = filament_backend_TextureUsage(1);
+}
+impl filament_backend_TextureUsage {
     #[doc = "!< Texture can be used as a depth attachment"]
-    DEPTH_ATTACHMENT = 2,
+    pub const DEPTH_ATTACHMENT: filament_backend_TextureUsage = filament_backend_TextureUsage(2);
+}
+impl filament_backend_TextureUsage {
     #[doc = "!< Texture can be used as a stencil attachment"]
-    STENCIL_ATTACHMENT = 4,
+    pub const STENCIL_ATTACHMENT: filament_backend_TextureUsage = filament_backend_TextureUsage(4);
+}
+impl filament_backend_TextureUsage {
     #[doc = "!< Data can be uploaded into this texture (default)"]
-    UPLOADABLE = 8,
+    pub const UPLOADABLE: filament_backend_TextureUsage = filament_backend_TextureUsage(8);
+}
+impl filament_backend_TextureUsage {
     #[doc = "!< Texture can be sampled (default)"]
-    SAMPLEABLE = 16,
+    pub const SAMPLEABLE: filament_backend_TextureUsage = filament_backend_TextureUsage(16);
+}
+impl filament_backend_TextureUsage {
     #[doc = "!< Default texture usage"]
-    DEFAULT = 24,
+    pub const DEFAULT: filament_backend_TextureUsage = filament_backend_TextureUsage(24);
+}
+impl ::std::ops::BitOr<filament_backend_TextureUsage> for filament_backend_TextureUsage {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        filament_backend_TextureUsage(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for filament_backend_TextureUsage {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: filament_backend_TextureUsage) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<filament_backend_TextureUsage> for filament_backend_TextureUsage {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        filament_backend_TextureUsage(self.0 & other.0)
+    }
 }
+impl ::std::ops::BitAndAssign for filament_backend_TextureUsage {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: filament_backend_TextureUsage) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
+#[doc = "! Bitmask describing the intended Texture Usage"]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct filament_backend_TextureUsage(pub u8);
 #[repr(i32)]
 #[doc = "! Texture swizzle"]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -8286,6 +8329,60 @@ extern "C" {
         indices: *mut filament_IndexBuffer,
     );
 }
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers33renderable_builder_geometry_rangeEPNS_12BuilderProxyEmN8filament7backend13PrimitiveTypeEPNS2_12VertexBufferEPNS2_11IndexBufferEmm"]
+    pub fn helpers_renderable_builder_geometry_range(
+        build: *mut helpers_BuilderProxy,
+        index: size_t,
+        type_: filament_backend_PrimitiveType,
+        vertices: *mut filament_VertexBuffer,
+        indices: *mut filament_IndexBuffer,
+        offset: size_t,
+        count: size_t,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers41renderable_builder_geometry_range_boundedEPNS_12BuilderProxyEmN8filament7backend13PrimitiveTypeEPNS2_12VertexBufferEPNS2_11IndexBufferEmmmm"]
+    pub fn helpers_renderable_builder_geometry_range_bounded(
+        build: *mut helpers_BuilderProxy,
+        index: size_t,
+        type_: filament_backend_PrimitiveType,
+        vertices: *mut filament_VertexBuffer,
+        indices: *mut filament_IndexBuffer,
+        offset: size_t,
+        minIndex: size_t,
+        maxIndex: size_t,
+        count: size_t,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers30renderable_builder_boundingBoxEPNS_12BuilderProxyEPKfS3_"]
+    pub fn helpers_renderable_builder_boundingBox(
+        build: *mut helpers_BuilderProxy,
+        center: *const f32,
+        halfExtent: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers28renderable_builder_layerMaskEPNS_12BuilderProxyEhh"]
+    pub fn helpers_renderable_builder_layerMask(
+        build: *mut helpers_BuilderProxy,
+        select: u8,
+        values: u8,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers27renderable_builder_priorityEPNS_12BuilderProxyEh"]
+    pub fn helpers_renderable_builder_priority(build: *mut helpers_BuilderProxy, priority: u8);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers29renderable_builder_blendOrderEPNS_12BuilderProxyEmt"]
+    pub fn helpers_renderable_builder_blendOrder(
+        build: *mut helpers_BuilderProxy,
+        index: size_t,
+        order: u16,
+    );
+}
 extern "C" {
     #[link_name = "\u{1}_ZN7helpers26renderable_builder_cullingEPNS_12BuilderProxyEb"]
     pub fn helpers_renderable_builder_culling(build: *mut helpers_BuilderProxy, culling: bool);
@@ -8309,6 +8406,26 @@ extern "C" {
     #[link_name = "\u{1}_ZN7helpers27renderable_builder_morphingEPNS_12BuilderProxyEb"]
     pub fn helpers_renderable_builder_morphing(build: *mut helpers_BuilderProxy, arg1: bool);
 }
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers27renderable_builder_skinningEPNS_12BuilderProxyEm"]
+    pub fn helpers_renderable_builder_skinning(build: *mut helpers_BuilderProxy, boneCount: size_t);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers33renderable_builder_skinning_bonesEPNS_12BuilderProxyEmPKf"]
+    pub fn helpers_renderable_builder_skinning_bones(
+        build: *mut helpers_BuilderProxy,
+        boneCount: size_t,
+        bones: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36renderable_builder_skinning_matricesEPNS_12BuilderProxyEmPKf"]
+    pub fn helpers_renderable_builder_skinning_matrices(
+        build: *mut helpers_BuilderProxy,
+        boneCount: size_t,
+        transforms: *const f32,
+    );
+}
 extern "C" {
     #[link_name = "\u{1}_ZN7helpers27renderable_builder_materialEPNS_12BuilderProxyEmPN8filament16MaterialInstanceE"]
     pub fn helpers_renderable_builder_material(
@@ -8325,6 +8442,132 @@ extern "C" {
         entity: utils_Entity,
     ) -> bool;
 }
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers30renderable_manager_getInstanceEPN8filament17RenderableManagerEN5utils6EntityE"]
+    pub fn helpers_renderable_manager_getInstance(
+        manager: *mut filament_RenderableManager,
+        entity: utils_Entity,
+    ) -> u32;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers31renderable_manager_hasComponentEPN8filament17RenderableManagerEN5utils6EntityE"]
+    pub fn helpers_renderable_manager_hasComponent(
+        manager: *mut filament_RenderableManager,
+        entity: utils_Entity,
+    ) -> bool;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers26renderable_manager_destroyEPN8filament17RenderableManagerEN5utils6EntityE"]
+    pub fn helpers_renderable_manager_destroy(
+        manager: *mut filament_RenderableManager,
+        entity: utils_Entity,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers40renderable_manager_setMaterialInstanceAtEPN8filament17RenderableManagerEjmPKNS0_16MaterialInstanceE"]
+    pub fn helpers_renderable_manager_setMaterialInstanceAt(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        primitiveIndex: size_t,
+        material: *const filament_MaterialInstance,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers32renderable_manager_setGeometryAtEPN8filament17RenderableManagerEjmNS0_7backend13PrimitiveTypeEPNS0_12VertexBufferEPNS0_11IndexBufferEmm"]
+    pub fn helpers_renderable_manager_setGeometryAt(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        primitiveIndex: size_t,
+        type_: filament_backend_PrimitiveType,
+        vertices: *mut filament_VertexBuffer,
+        indices: *mut filament_IndexBuffer,
+        offset: size_t,
+        count: size_t,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers44renderable_manager_setAxisAlignedBoundingBoxEPN8filament17RenderableManagerEjPKfS4_"]
+    pub fn helpers_renderable_manager_setAxisAlignedBoundingBox(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        center: *const f32,
+        halfExtent: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers31renderable_manager_setLayerMaskEPN8filament17RenderableManagerEjhh"]
+    pub fn helpers_renderable_manager_setLayerMask(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        select: u8,
+        values: u8,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers33renderable_manager_setCastShadowsEPN8filament17RenderableManagerEjb"]
+    pub fn helpers_renderable_manager_setCastShadows(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        enable: bool,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36renderable_manager_setReceiveShadowsEPN8filament17RenderableManagerEjb"]
+    pub fn helpers_renderable_manager_setReceiveShadows(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        enable: bool,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers33renderable_manager_isShadowCasterEPN8filament17RenderableManagerEj"]
+    pub fn helpers_renderable_manager_isShadowCaster(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+    ) -> bool;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers35renderable_manager_isShadowReceiverEPN8filament17RenderableManagerEj"]
+    pub fn helpers_renderable_manager_isShadowReceiver(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+    ) -> bool;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36renderable_manager_getPrimitiveCountEPN8filament17RenderableManagerEj"]
+    pub fn helpers_renderable_manager_getPrimitiveCount(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+    ) -> size_t;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers27renderable_manager_setBonesEPN8filament17RenderableManagerEjPKfmm"]
+    pub fn helpers_renderable_manager_setBones(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        bones: *const f32,
+        boneCount: size_t,
+        offset: size_t,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36renderable_manager_setBones_matricesEPN8filament17RenderableManagerEjPKfmm"]
+    pub fn helpers_renderable_manager_setBones_matrices(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        transforms: *const f32,
+        boneCount: size_t,
+        offset: size_t,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers34renderable_manager_setMorphWeightsEPN8filament17RenderableManagerEjPKf"]
+    pub fn helpers_renderable_manager_setMorphWeights(
+        manager: *mut filament_RenderableManager,
+        instance: u32,
+        weights: *const f32,
+    );
+}
 extern "C" {
     #[link_name = "\u{1}_ZN7helpers18make_SamplerParamsEN8filament7backend16SamplerMagFilterENS1_16SamplerMinFilterENS1_15SamplerWrapModeES4_S4_hNS1_18SamplerCompareModeENS1_18SamplerCompareFuncE"]
     pub fn helpers_make_SamplerParams(
@@ -8349,6 +8592,17 @@ extern "C" {
         ty: filament_backend_PixelDataType,
     ) -> filament_backend_PixelBufferDescriptor;
 }
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36make_CompressedPixelBufferDescriptorEPKvmPFvPvmS2_ES2_N8filament7backend23CompressedPixelDataTypeEj"]
+    pub fn helpers_make_CompressedPixelBufferDescriptor(
+        buffer: *const ::std::os::raw::c_void,
+        size: size_t,
+        callback: filament_backend_BufferDescriptor_Callback,
+        user: *mut ::std::os::raw::c_void,
+        format: filament_backend_CompressedPixelDataType,
+        imageSize: u32,
+    ) -> filament_backend_PixelBufferDescriptor;
+}
 extern "C" {
     #[link_name = "\u{1}_ZN7helpers14material_buildERN8filament6EngineEPvm"]
     pub fn helpers_material_build(
@@ -8363,6 +8617,19 @@ extern "C" {
         material: *mut filament_Material,
     ) -> *mut filament_MaterialInstance;
 }
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers23material_createInstanceEPN8filament8MaterialE"]
+    pub fn helpers_material_createInstance(
+        material: *mut filament_Material,
+    ) -> *mut filament_MaterialInstance;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers21material_hasParameterEPKN8filament8MaterialEPKc"]
+    pub fn helpers_material_hasParameter(
+        material: *const filament_Material,
+        name: *const ::std::os::raw::c_char,
+    ) -> bool;
+}
 extern "C" {
     #[link_name = "\u{1}_ZN7helpers38material_instance_setParameter_textureEPN8filament16MaterialInstanceEPKcPKNS0_7TextureERKNS0_14TextureSamplerE"]
     pub fn helpers_material_instance_setParameter_texture(
@@ -8372,6 +8639,298 @@ extern "C" {
         sampler: *const filament_TextureSampler,
     );
 }
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36material_instance_setParameter_floatEPN8filament16MaterialInstanceEPKcf"]
+    pub fn helpers_material_instance_setParameter_float(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers37material_instance_setParameter_float2EPN8filament16MaterialInstanceEPKcRKNS0_4math7details5TVec2IfEE"]
+    pub fn helpers_material_instance_setParameter_float2(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: *const filament_math_float2,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers37material_instance_setParameter_float3EPN8filament16MaterialInstanceEPKcRKNS0_4math7details5TVec3IfEE"]
+    pub fn helpers_material_instance_setParameter_float3(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: *const filament_math_float3,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers37material_instance_setParameter_float4EPN8filament16MaterialInstanceEPKcRKNS0_4math7details5TVec4IfEE"]
+    pub fn helpers_material_instance_setParameter_float4(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: *const filament_math_float4,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers34material_instance_setParameter_intEPN8filament16MaterialInstanceEPKci"]
+    pub fn helpers_material_instance_setParameter_int(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: i32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers35material_instance_setParameter_boolEPN8filament16MaterialInstanceEPKcb"]
+    pub fn helpers_material_instance_setParameter_bool(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: bool,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36material_instance_setParameter_mat3fEPN8filament16MaterialInstanceEPKcRKNS0_4math7details6TMat33IfEE"]
+    pub fn helpers_material_instance_setParameter_mat3f(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: *const filament_math_mat3f,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers36material_instance_setParameter_mat4fEPN8filament16MaterialInstanceEPKcRKNS0_4math7details6TMat44IfEE"]
+    pub fn helpers_material_instance_setParameter_mat4f(
+        instance: *mut filament_MaterialInstance,
+        name: *const ::std::os::raw::c_char,
+        value: *const filament_math_mat4f,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers18texture_setImage3DEPN8filament7TextureERNS0_6EngineEmjjjjjjPNS0_7backend21PixelBufferDescriptorE"]
+    pub fn helpers_texture_setImage3D(
+        texture: *mut filament_Texture,
+        engine: *mut filament_Engine,
+        level: size_t,
+        xoffset: u32,
+        yoffset: u32,
+        zoffset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        buffer: *mut filament_backend_PixelBufferDescriptor,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers19stream_build_nativeERN8filament6EngineEPvjj"]
+    pub fn helpers_stream_build_native(
+        engine: *mut filament_Engine,
+        stream: *mut ::std::os::raw::c_void,
+        width: u32,
+        height: u32,
+    ) -> *mut filament_Stream;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers23stream_build_texture_idERN8filament6EngineEljj"]
+    pub fn helpers_stream_build_texture_id(
+        engine: *mut filament_Engine,
+        externalTextureId: isize,
+        width: u32,
+        height: u32,
+    ) -> *mut filament_Stream;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers21stream_build_acquiredERN8filament6EngineEjj"]
+    pub fn helpers_stream_build_acquired(
+        engine: *mut filament_Engine,
+        width: u32,
+        height: u32,
+    ) -> *mut filament_Stream;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers20stream_getStreamTypeEPKN8filament6StreamE"]
+    pub fn helpers_stream_getStreamType(stream: *const filament_Stream) -> filament_backend_StreamType;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers20stream_setDimensionsEPN8filament6StreamEjj"]
+    pub fn helpers_stream_setDimensions(stream: *mut filament_Stream, width: u32, height: u32);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers23stream_setAcquiredImageEPN8filament6StreamEPvPFvS3_S3_ES3_"]
+    pub fn helpers_stream_setAcquiredImage(
+        stream: *mut filament_Stream,
+        image: *mut ::std::os::raw::c_void,
+        callback: filament_backend_StreamCallback,
+        user: *mut ::std::os::raw::c_void,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers19stream_getTimestampEPKN8filament6StreamE"]
+    pub fn helpers_stream_getTimestamp(stream: *const filament_Stream) -> i64;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers19render_target_buildERN8filament6EngineEPNS0_7TextureES4_hNS0_7backend18TextureCubemapFaceEj"]
+    pub fn helpers_render_target_build(
+        engine: *mut filament_Engine,
+        color: *mut filament_Texture,
+        depth: *mut filament_Texture,
+        level: u8,
+        face: filament_backend_TextureCubemapFace,
+        layer: u32,
+    ) -> *mut filament_RenderTarget;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers22entity_manager_isAliveEPKN5utils13EntityManagerENS0_6EntityE"]
+    pub fn helpers_entity_manager_isAlive(
+        manager: *const utils_EntityManager,
+        entity: utils_Entity,
+    ) -> bool;
+}
+pub type helpers_EntitiesDestroyedCallback = ::std::option::Option<
+    unsafe extern "C" fn(
+        n: size_t,
+        entities: *const utils_Entity,
+        user: *mut ::std::os::raw::c_void,
+    ),
+>;
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers22entity_listener_createEPFvmPKN5utils6EntityEPvES4_"]
+    pub fn helpers_entity_listener_create(
+        callback: helpers_EntitiesDestroyedCallback,
+        user: *mut ::std::os::raw::c_void,
+    ) -> *mut utils_EntityManager_Listener;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct helpers_LightBuilderProxy {
+    _unused: [u8; 0],
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers17light_builder_newEh"]
+    pub fn helpers_light_builder_new(type_: u8) -> *mut helpers_LightBuilderProxy;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers21light_builder_destroyEPNS_17LightBuilderProxyE"]
+    pub fn helpers_light_builder_destroy(proxy: *mut helpers_LightBuilderProxy);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers25light_builder_castShadowsEPNS_17LightBuilderProxyEb"]
+    pub fn helpers_light_builder_castShadows(proxy: *mut helpers_LightBuilderProxy, enable: bool);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers19light_builder_colorEPNS_17LightBuilderProxyEPKf"]
+    pub fn helpers_light_builder_color(proxy: *mut helpers_LightBuilderProxy, color: *const f32);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers23light_builder_intensityEPNS_17LightBuilderProxyEf"]
+    pub fn helpers_light_builder_intensity(proxy: *mut helpers_LightBuilderProxy, intensity: f32);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers22light_builder_positionEPNS_17LightBuilderProxyEPKf"]
+    pub fn helpers_light_builder_position(
+        proxy: *mut helpers_LightBuilderProxy,
+        position: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers23light_builder_directionEPNS_17LightBuilderProxyEPKf"]
+    pub fn helpers_light_builder_direction(
+        proxy: *mut helpers_LightBuilderProxy,
+        direction: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers21light_builder_falloffEPNS_17LightBuilderProxyEf"]
+    pub fn helpers_light_builder_falloff(proxy: *mut helpers_LightBuilderProxy, radius: f32);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers27light_builder_spotLightConeEPNS_17LightBuilderProxyEff"]
+    pub fn helpers_light_builder_spotLightCone(
+        proxy: *mut helpers_LightBuilderProxy,
+        inner: f32,
+        outer: f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers19light_builder_buildEPNS_17LightBuilderProxyERN8filament6EngineEN5utils6EntityE"]
+    pub fn helpers_light_builder_build(
+        proxy: *mut helpers_LightBuilderProxy,
+        engine: *mut filament_Engine,
+        entity: utils_Entity,
+    ) -> bool;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers25light_manager_getInstanceEPN8filament12LightManagerEN5utils6EntityE"]
+    pub fn helpers_light_manager_getInstance(
+        manager: *mut filament_LightManager,
+        entity: utils_Entity,
+    ) -> u32;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers26light_manager_hasComponentEPN8filament12LightManagerEN5utils6EntityE"]
+    pub fn helpers_light_manager_hasComponent(
+        manager: *mut filament_LightManager,
+        entity: utils_Entity,
+    ) -> bool;
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers21light_manager_destroyEPN8filament12LightManagerEN5utils6EntityE"]
+    pub fn helpers_light_manager_destroy(manager: *mut filament_LightManager, entity: utils_Entity);
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers25light_manager_setPositionEPN8filament12LightManagerEjPKf"]
+    pub fn helpers_light_manager_setPosition(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        position: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers26light_manager_setDirectionEPN8filament12LightManagerEjPKf"]
+    pub fn helpers_light_manager_setDirection(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        direction: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers22light_manager_setColorEPN8filament12LightManagerEjPKf"]
+    pub fn helpers_light_manager_setColor(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        color: *const f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers26light_manager_setIntensityEPN8filament12LightManagerEjf"]
+    pub fn helpers_light_manager_setIntensity(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        intensity: f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers24light_manager_setFalloffEPN8filament12LightManagerEjf"]
+    pub fn helpers_light_manager_setFalloff(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        radius: f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers30light_manager_setSpotLightConeEPN8filament12LightManagerEjff"]
+    pub fn helpers_light_manager_setSpotLightCone(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        inner: f32,
+        outer: f32,
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_ZN7helpers29light_manager_setShadowCasterEPN8filament12LightManagerEjb"]
+    pub fn helpers_light_manager_setShadowCaster(
+        manager: *mut filament_LightManager,
+        instance: u32,
+        enable: bool,
+    );
+}
 extern "C" {
     #[link_name = "\u{1}_ZN4test10texture_eqERKN8filament7TextureE"]
     pub fn test_texture_eq(texture: *const filament_Texture) -> bool;
@@ -0,0 +1,25 @@
+#include "entities.h"
+
+namespace helpers {
+    class CallbackListener : public utils::EntityManager::Listener {
+    public:
+        CallbackListener(EntitiesDestroyedCallback callback, void *user) : mCallback(callback), mUser(user) {}
+        ~CallbackListener() noexcept override = default;
+
+        void onEntitiesDestroyed(size_t n, utils::Entity const* entities) noexcept override {
+            mCallback(n, entities, mUser);
+        }
+
+    private:
+        EntitiesDestroyedCallback mCallback;
+        void *mUser;
+    };
+
+    bool entity_manager_isAlive(utils::EntityManager const* manager, utils::Entity entity) {
+        return manager->isAlive(entity);
+    }
+
+    utils::EntityManager::Listener *entity_listener_create(EntitiesDestroyedCallback callback, void *user) {
+        return new CallbackListener(callback, user);
+    }
+}
@@ -0,0 +1,16 @@
+#ifndef FILAMENT_RS_ENTITIES_H
+#define FILAMENT_RS_ENTITIES_H
+
+#include <utils/Entity.h>
+#include <utils/EntityManager.h>
+
+namespace helpers {
+    bool entity_manager_isAlive(utils::EntityManager const* manager, utils::Entity entity);
+
+    using EntitiesDestroyedCallback = void(*)(size_t n, utils::Entity const* entities, void *user);
+
+    // A listener forwarding to a callback, which can be registered with EntityManager::registerListener
+    utils::EntityManager::Listener *entity_listener_create(EntitiesDestroyedCallback callback, void *user);
+}
+
+#endif //FILAMENT_RS_ENTITIES_H
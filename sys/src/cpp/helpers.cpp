@@ -37,4 +37,11 @@ namespace helpers {
     ) {
         return filament::backend::PixelBufferDescriptor(buffer, size, format, ty, callback, user);
     }
+
+    filament::backend::PixelBufferDescriptor make_CompressedPixelBufferDescriptor(
+            void const *buffer, size_t size, filament::backend::BufferDescriptor::Callback callback, void *user,
+            filament::backend::CompressedPixelDataType format, uint32_t imageSize
+    ) {
+        return filament::backend::PixelBufferDescriptor(buffer, size, format, imageSize, callback, user);
+    }
 }
\ No newline at end of file
@@ -30,5 +30,10 @@ namespace helpers {
             filament::backend::PixelDataType ty
             );
 
+    filament::backend::PixelBufferDescriptor make_CompressedPixelBufferDescriptor(
+            void const *buffer, size_t size, filament::backend::BufferDescriptor::Callback callback, void *user,
+            filament::backend::CompressedPixelDataType format, uint32_t imageSize
+            );
+
 }
 #endif //FILAMENT_RS_HELPERS_H
@@ -0,0 +1,85 @@
+#include "lights.h"
+#include <filament/LightManager.h>
+#include <math/vec3.h>
+
+namespace helpers {
+    class LightBuilderProxy {
+    public:
+        LightBuilderProxy(filament::LightManager::Builder * ptr): ptr(ptr) {}
+        ~LightBuilderProxy() {delete ptr; }
+
+        filament::LightManager::Builder * ptr;
+    };
+
+    using LightInstance = filament::LightManager::Instance;
+
+    static filament::math::float3 to_float3(const float *v) {
+        return { v[0], v[1], v[2] };
+    }
+
+    LightBuilderProxy *light_builder_new(uint8_t type) {
+        return new LightBuilderProxy(new filament::LightManager::Builder(
+                static_cast<filament::LightManager::Type>(type)));
+    }
+    void light_builder_destroy(LightBuilderProxy *proxy) {
+        delete proxy;
+    }
+
+    void light_builder_castShadows(LightBuilderProxy *proxy, bool enable) {
+        proxy->ptr->castShadows(enable);
+    }
+    void light_builder_color(LightBuilderProxy *proxy, const float *color) {
+        proxy->ptr->color(to_float3(color));
+    }
+    void light_builder_intensity(LightBuilderProxy *proxy, float intensity) {
+        proxy->ptr->intensity(intensity);
+    }
+    void light_builder_position(LightBuilderProxy *proxy, const float *position) {
+        proxy->ptr->position(to_float3(position));
+    }
+    void light_builder_direction(LightBuilderProxy *proxy, const float *direction) {
+        proxy->ptr->direction(to_float3(direction));
+    }
+    void light_builder_falloff(LightBuilderProxy *proxy, float radius) {
+        proxy->ptr->falloff(radius);
+    }
+    void light_builder_spotLightCone(LightBuilderProxy *proxy, float inner, float outer) {
+        proxy->ptr->spotLightCone(inner, outer);
+    }
+
+    bool light_builder_build(LightBuilderProxy *proxy, filament::Engine &engine, utils::Entity entity) {
+        return proxy->ptr->build(engine, entity) == 0;
+    }
+
+    uint32_t light_manager_getInstance(filament::LightManager *manager, utils::Entity entity) {
+        return manager->getInstance(entity).asValue();
+    }
+    bool light_manager_hasComponent(filament::LightManager *manager, utils::Entity entity) {
+        return manager->hasComponent(entity);
+    }
+    void light_manager_destroy(filament::LightManager *manager, utils::Entity entity) {
+        manager->destroy(entity);
+    }
+
+    void light_manager_setPosition(filament::LightManager *manager, uint32_t instance, const float *position) {
+        manager->setPosition(LightInstance(instance), to_float3(position));
+    }
+    void light_manager_setDirection(filament::LightManager *manager, uint32_t instance, const float *direction) {
+        manager->setDirection(LightInstance(instance), to_float3(direction));
+    }
+    void light_manager_setColor(filament::LightManager *manager, uint32_t instance, const float *color) {
+        manager->setColor(LightInstance(instance), to_float3(color));
+    }
+    void light_manager_setIntensity(filament::LightManager *manager, uint32_t instance, float intensity) {
+        manager->setIntensity(LightInstance(instance), intensity);
+    }
+    void light_manager_setFalloff(filament::LightManager *manager, uint32_t instance, float radius) {
+        manager->setFalloff(LightInstance(instance), radius);
+    }
+    void light_manager_setSpotLightCone(filament::LightManager *manager, uint32_t instance, float inner, float outer) {
+        manager->setSpotLightCone(LightInstance(instance), inner, outer);
+    }
+    void light_manager_setShadowCaster(filament::LightManager *manager, uint32_t instance, bool enable) {
+        manager->setShadowCaster(LightInstance(instance), enable);
+    }
+}
@@ -0,0 +1,41 @@
+#ifndef FILAMENT_RS_LIGHTS_H
+#define FILAMENT_RS_LIGHTS_H
+
+#include <utils/Entity.h>
+#include <filament/Engine.h>
+
+namespace filament {
+    class LightManager;
+}
+
+namespace helpers {
+    class LightBuilderProxy;
+
+    // type is a filament::LightManager::Type
+    LightBuilderProxy *light_builder_new(uint8_t type);
+    void light_builder_destroy(LightBuilderProxy *proxy);
+
+    void light_builder_castShadows(LightBuilderProxy *proxy, bool enable);
+    void light_builder_color(LightBuilderProxy *proxy, const float *color);
+    void light_builder_intensity(LightBuilderProxy *proxy, float intensity);
+    void light_builder_position(LightBuilderProxy *proxy, const float *position);
+    void light_builder_direction(LightBuilderProxy *proxy, const float *direction);
+    void light_builder_falloff(LightBuilderProxy *proxy, float radius);
+    void light_builder_spotLightCone(LightBuilderProxy *proxy, float inner, float outer);
+
+    bool light_builder_build(LightBuilderProxy *proxy, filament::Engine &engine, utils::Entity entity);
+
+    uint32_t light_manager_getInstance(filament::LightManager *manager, utils::Entity entity);
+    bool light_manager_hasComponent(filament::LightManager *manager, utils::Entity entity);
+    void light_manager_destroy(filament::LightManager *manager, utils::Entity entity);
+
+    void light_manager_setPosition(filament::LightManager *manager, uint32_t instance, const float *position);
+    void light_manager_setDirection(filament::LightManager *manager, uint32_t instance, const float *direction);
+    void light_manager_setColor(filament::LightManager *manager, uint32_t instance, const float *color);
+    void light_manager_setIntensity(filament::LightManager *manager, uint32_t instance, float intensity);
+    void light_manager_setFalloff(filament::LightManager *manager, uint32_t instance, float radius);
+    void light_manager_setSpotLightCone(filament::LightManager *manager, uint32_t instance, float inner, float outer);
+    void light_manager_setShadowCaster(filament::LightManager *manager, uint32_t instance, bool enable);
+}
+
+#endif //FILAMENT_RS_LIGHTS_H
@@ -12,6 +12,12 @@ namespace helpers {
     filament::MaterialInstance * material_getDefaultInstance(filament::Material *material) {
         return material->getDefaultInstance();
     }
+    filament::MaterialInstance * material_createInstance(filament::Material *material) {
+        return material->createInstance();
+    }
+    bool material_hasParameter(filament::Material const* material, const char *name) {
+        return material->hasParameter(name);
+    }
 
     filament::Material * material_build(filament::Engine & engine, void *buffer, size_t len) {
         return filament::Material::Builder().package(buffer, len).build(engine);
@@ -21,4 +27,36 @@ namespace helpers {
                                                 filament::Texture const* texture, filament::TextureSampler const& sampler) {
         instance->setParameter(name, texture, sampler);
     }
+
+    void material_instance_setParameter_float(filament::MaterialInstance * instance, const char *name, float value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_float2(filament::MaterialInstance * instance, const char *name, filament::math::float2 const& value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_float3(filament::MaterialInstance * instance, const char *name, filament::math::float3 const& value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_float4(filament::MaterialInstance * instance, const char *name, filament::math::float4 const& value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_int(filament::MaterialInstance * instance, const char *name, int32_t value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_bool(filament::MaterialInstance * instance, const char *name, bool value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_mat3f(filament::MaterialInstance * instance, const char *name, filament::math::mat3f const& value) {
+        instance->setParameter(name, value);
+    }
+
+    void material_instance_setParameter_mat4f(filament::MaterialInstance * instance, const char *name, filament::math::mat4f const& value) {
+        instance->setParameter(name, value);
+    }
 }
\ No newline at end of file
@@ -6,6 +6,11 @@
 #define FILAMENT_RS_MATERIALS_H
 
 #include <filament/Engine.h>
+#include <math/mat3.h>
+#include <math/mat4.h>
+#include <math/vec2.h>
+#include <math/vec3.h>
+#include <math/vec4.h>
 
 namespace filament {
     class Material;
@@ -17,9 +22,20 @@ namespace filament {
 namespace helpers {
     filament::Material * material_build(filament::Engine & engine, void *buffer, size_t len);
     filament::MaterialInstance * material_getDefaultInstance(filament::Material *material);
+    filament::MaterialInstance * material_createInstance(filament::Material *material);
+    bool material_hasParameter(filament::Material const* material, const char *name);
 
     void material_instance_setParameter_texture(filament::MaterialInstance * instance, const char *name,
                                                 filament::Texture const* texture, filament::TextureSampler const& sampler);
+
+    void material_instance_setParameter_float(filament::MaterialInstance * instance, const char *name, float value);
+    void material_instance_setParameter_float2(filament::MaterialInstance * instance, const char *name, filament::math::float2 const& value);
+    void material_instance_setParameter_float3(filament::MaterialInstance * instance, const char *name, filament::math::float3 const& value);
+    void material_instance_setParameter_float4(filament::MaterialInstance * instance, const char *name, filament::math::float4 const& value);
+    void material_instance_setParameter_int(filament::MaterialInstance * instance, const char *name, int32_t value);
+    void material_instance_setParameter_bool(filament::MaterialInstance * instance, const char *name, bool value);
+    void material_instance_setParameter_mat3f(filament::MaterialInstance * instance, const char *name, filament::math::mat3f const& value);
+    void material_instance_setParameter_mat4f(filament::MaterialInstance * instance, const char *name, filament::math::mat4f const& value);
 }
 
 
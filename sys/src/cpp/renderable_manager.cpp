@@ -4,6 +4,7 @@
 
 #include "renderable_manager.h"
 #include <filament/RenderableManager.h>
+#include <math/mat4.h>
 
 
 
@@ -30,6 +31,36 @@ namespace helpers {
     ) {
         proxy->ptr->geometry(index, type, vertices, indices);
     }
+    void renderable_builder_geometry_range(BuilderProxy * proxy,
+                                    size_t index, filament::backend::PrimitiveType type,
+                                    filament::VertexBuffer* vertices, filament::IndexBuffer* indices,
+                                    size_t offset, size_t count
+    ) {
+        proxy->ptr->geometry(index, type, vertices, indices, offset, count);
+    }
+    void renderable_builder_geometry_range_bounded(BuilderProxy * proxy,
+                                    size_t index, filament::backend::PrimitiveType type,
+                                    filament::VertexBuffer* vertices, filament::IndexBuffer* indices,
+                                    size_t offset, size_t minIndex, size_t maxIndex, size_t count
+    ) {
+        proxy->ptr->geometry(index, type, vertices, indices, offset, minIndex, maxIndex, count);
+    }
+
+    void renderable_builder_boundingBox(BuilderProxy * proxy, const float *center, const float *halfExtent) {
+        filament::Box box;
+        box.center = { center[0], center[1], center[2] };
+        box.halfExtent = { halfExtent[0], halfExtent[1], halfExtent[2] };
+        proxy->ptr->boundingBox(box);
+    }
+    void renderable_builder_layerMask(BuilderProxy * proxy, uint8_t select, uint8_t values) {
+        proxy->ptr->layerMask(select, values);
+    }
+    void renderable_builder_priority(BuilderProxy * proxy, uint8_t priority) {
+        proxy->ptr->priority(priority);
+    }
+    void renderable_builder_blendOrder(BuilderProxy * proxy, size_t index, uint16_t order) {
+        proxy->ptr->blendOrder(index, order);
+    }
 
     void renderable_builder_culling(BuilderProxy * proxy, bool value) {
         proxy->ptr->culling(value);
@@ -47,6 +78,19 @@ namespace helpers {
         proxy->ptr->morphing(value);
     }
 
+    static_assert(sizeof(filament::RenderableManager::Bone) == sizeof(float) * 8, "Unexpected Bone layout");
+    static_assert(sizeof(filament::math::mat4f) == sizeof(float) * 16, "Unexpected mat4f layout");
+
+    void renderable_builder_skinning(BuilderProxy * proxy, size_t boneCount) {
+        proxy->ptr->skinning(boneCount);
+    }
+    void renderable_builder_skinning_bones(BuilderProxy * proxy, size_t boneCount, const float *bones) {
+        proxy->ptr->skinning(boneCount, reinterpret_cast<filament::RenderableManager::Bone const*>(bones));
+    }
+    void renderable_builder_skinning_matrices(BuilderProxy * proxy, size_t boneCount, const float *transforms) {
+        proxy->ptr->skinning(boneCount, reinterpret_cast<filament::math::mat4f const*>(transforms));
+    }
+
     void renderable_builder_material(BuilderProxy * proxy, size_t index,
             filament::MaterialInstance * material) {
         proxy->ptr->material(index, material);
@@ -56,4 +100,67 @@ namespace helpers {
         return proxy->ptr->build(engine, entity) == 0;
     }
 
+    using RenderableInstance = filament::RenderableManager::Instance;
+
+    uint32_t renderable_manager_getInstance(filament::RenderableManager * manager, utils::Entity entity) {
+        return manager->getInstance(entity).asValue();
+    }
+    bool renderable_manager_hasComponent(filament::RenderableManager * manager, utils::Entity entity) {
+        return manager->hasComponent(entity);
+    }
+    void renderable_manager_destroy(filament::RenderableManager * manager, utils::Entity entity) {
+        manager->destroy(entity);
+    }
+
+    void renderable_manager_setMaterialInstanceAt(filament::RenderableManager * manager, uint32_t instance,
+                                                  size_t primitiveIndex, filament::MaterialInstance const* material) {
+        manager->setMaterialInstanceAt(RenderableInstance(instance), primitiveIndex, material);
+    }
+    void renderable_manager_setGeometryAt(filament::RenderableManager * manager, uint32_t instance,
+                                          size_t primitiveIndex, filament::backend::PrimitiveType type,
+                                          filament::VertexBuffer* vertices, filament::IndexBuffer* indices,
+                                          size_t offset, size_t count) {
+        manager->setGeometryAt(RenderableInstance(instance), primitiveIndex, type, vertices, indices, offset, count);
+    }
+    void renderable_manager_setAxisAlignedBoundingBox(filament::RenderableManager * manager, uint32_t instance,
+                                                      const float *center, const float *halfExtent) {
+        filament::Box box;
+        box.center = { center[0], center[1], center[2] };
+        box.halfExtent = { halfExtent[0], halfExtent[1], halfExtent[2] };
+        manager->setAxisAlignedBoundingBox(RenderableInstance(instance), box);
+    }
+    void renderable_manager_setLayerMask(filament::RenderableManager * manager, uint32_t instance,
+                                         uint8_t select, uint8_t values) {
+        manager->setLayerMask(RenderableInstance(instance), select, values);
+    }
+    void renderable_manager_setCastShadows(filament::RenderableManager * manager, uint32_t instance, bool enable) {
+        manager->setCastShadows(RenderableInstance(instance), enable);
+    }
+    void renderable_manager_setReceiveShadows(filament::RenderableManager * manager, uint32_t instance, bool enable) {
+        manager->setReceiveShadows(RenderableInstance(instance), enable);
+    }
+    bool renderable_manager_isShadowCaster(filament::RenderableManager * manager, uint32_t instance) {
+        return manager->isShadowCaster(RenderableInstance(instance));
+    }
+    bool renderable_manager_isShadowReceiver(filament::RenderableManager * manager, uint32_t instance) {
+        return manager->isShadowReceiver(RenderableInstance(instance));
+    }
+    size_t renderable_manager_getPrimitiveCount(filament::RenderableManager * manager, uint32_t instance) {
+        return manager->getPrimitiveCount(RenderableInstance(instance));
+    }
+
+    void renderable_manager_setBones(filament::RenderableManager * manager, uint32_t instance,
+                                     const float *bones, size_t boneCount, size_t offset) {
+        manager->setBones(RenderableInstance(instance),
+                reinterpret_cast<filament::RenderableManager::Bone const*>(bones), boneCount, offset);
+    }
+    void renderable_manager_setBones_matrices(filament::RenderableManager * manager, uint32_t instance,
+                                              const float *transforms, size_t boneCount, size_t offset) {
+        manager->setBones(RenderableInstance(instance),
+                reinterpret_cast<filament::math::mat4f const*>(transforms), boneCount, offset);
+    }
+
+    void renderable_manager_setMorphWeights(filament::RenderableManager * manager, uint32_t instance, const float *weights) {
+        manager->setMorphWeights(RenderableInstance(instance), { weights[0], weights[1], weights[2], weights[3] });
+    }
 }
\ No newline at end of file
@@ -13,6 +13,7 @@ namespace filament {
     class VertexBuffer;
     class IndexBuffer;
     class MaterialInstance;
+    class RenderableManager;
 }
 
 namespace helpers {
@@ -24,6 +25,19 @@ namespace helpers {
     void renderable_builder_geometry(BuilderProxy * build,
             size_t index, filament::backend::PrimitiveType type,
             filament::VertexBuffer* vertices, filament::IndexBuffer* indices);
+    void renderable_builder_geometry_range(BuilderProxy * build,
+            size_t index, filament::backend::PrimitiveType type,
+            filament::VertexBuffer* vertices, filament::IndexBuffer* indices,
+            size_t offset, size_t count);
+    void renderable_builder_geometry_range_bounded(BuilderProxy * build,
+            size_t index, filament::backend::PrimitiveType type,
+            filament::VertexBuffer* vertices, filament::IndexBuffer* indices,
+            size_t offset, size_t minIndex, size_t maxIndex, size_t count);
+
+    void renderable_builder_boundingBox(BuilderProxy * build, const float *center, const float *halfExtent);
+    void renderable_builder_layerMask(BuilderProxy * build, uint8_t select, uint8_t values);
+    void renderable_builder_priority(BuilderProxy * build, uint8_t priority);
+    void renderable_builder_blendOrder(BuilderProxy * build, size_t index, uint16_t order);
 
     void renderable_builder_culling(BuilderProxy * build, bool culling);
 
@@ -33,9 +47,41 @@ namespace helpers {
 
     void renderable_builder_morphing(BuilderProxy * build, bool);
 
+    // Bones are laid out as filament::RenderableManager::Bone (8 floats), matrices as filament::math::mat4f (16 floats)
+    void renderable_builder_skinning(BuilderProxy * build, size_t boneCount);
+    void renderable_builder_skinning_bones(BuilderProxy * build, size_t boneCount, const float *bones);
+    void renderable_builder_skinning_matrices(BuilderProxy * build, size_t boneCount, const float *transforms);
+
     void renderable_builder_material(BuilderProxy * build, size_t index, filament::MaterialInstance * material);
 
     bool renderable_builder_build(BuilderProxy * proxy, filament::Engine &engine, utils::Entity entity);
+
+    uint32_t renderable_manager_getInstance(filament::RenderableManager * manager, utils::Entity entity);
+    bool renderable_manager_hasComponent(filament::RenderableManager * manager, utils::Entity entity);
+    void renderable_manager_destroy(filament::RenderableManager * manager, utils::Entity entity);
+
+    void renderable_manager_setMaterialInstanceAt(filament::RenderableManager * manager, uint32_t instance,
+            size_t primitiveIndex, filament::MaterialInstance const* material);
+    void renderable_manager_setGeometryAt(filament::RenderableManager * manager, uint32_t instance,
+            size_t primitiveIndex, filament::backend::PrimitiveType type,
+            filament::VertexBuffer* vertices, filament::IndexBuffer* indices, size_t offset, size_t count);
+    void renderable_manager_setAxisAlignedBoundingBox(filament::RenderableManager * manager, uint32_t instance,
+            const float *center, const float *halfExtent);
+    void renderable_manager_setLayerMask(filament::RenderableManager * manager, uint32_t instance,
+            uint8_t select, uint8_t values);
+    void renderable_manager_setCastShadows(filament::RenderableManager * manager, uint32_t instance, bool enable);
+    void renderable_manager_setReceiveShadows(filament::RenderableManager * manager, uint32_t instance, bool enable);
+    bool renderable_manager_isShadowCaster(filament::RenderableManager * manager, uint32_t instance);
+    bool renderable_manager_isShadowReceiver(filament::RenderableManager * manager, uint32_t instance);
+    size_t renderable_manager_getPrimitiveCount(filament::RenderableManager * manager, uint32_t instance);
+
+    void renderable_manager_setBones(filament::RenderableManager * manager, uint32_t instance,
+            const float *bones, size_t boneCount, size_t offset);
+    void renderable_manager_setBones_matrices(filament::RenderableManager * manager, uint32_t instance,
+            const float *transforms, size_t boneCount, size_t offset);
+
+    // Weights must point to 4 floats, one per morph target
+    void renderable_manager_setMorphWeights(filament::RenderableManager * manager, uint32_t instance, const float *weights);
 }
 
 #endif //FILAMENT_RS_RENDERABLE_MANAGER_H
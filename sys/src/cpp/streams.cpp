@@ -0,0 +1,45 @@
+#include "streams.h"
+#include <filament/Stream.h>
+
+namespace helpers {
+    filament::Stream *stream_build_native(filament::Engine &engine, void *stream, uint32_t width, uint32_t height) {
+        return filament::Stream::Builder()
+                .stream(stream)
+                .width(width)
+                .height(height)
+                .build(engine);
+    }
+
+    filament::Stream *stream_build_texture_id(filament::Engine &engine, intptr_t externalTextureId,
+                                              uint32_t width, uint32_t height) {
+        return filament::Stream::Builder()
+                .stream(externalTextureId)
+                .width(width)
+                .height(height)
+                .build(engine);
+    }
+
+    filament::Stream *stream_build_acquired(filament::Engine &engine, uint32_t width, uint32_t height) {
+        return filament::Stream::Builder()
+                .width(width)
+                .height(height)
+                .build(engine);
+    }
+
+    filament::backend::StreamType stream_getStreamType(const filament::Stream *stream) {
+        return stream->getStreamType();
+    }
+
+    void stream_setDimensions(filament::Stream *stream, uint32_t width, uint32_t height) {
+        stream->setDimensions(width, height);
+    }
+
+    void stream_setAcquiredImage(filament::Stream *stream, void *image,
+                                 filament::backend::StreamCallback callback, void *user) {
+        stream->setAcquiredImage(image, callback, user);
+    }
+
+    int64_t stream_getTimestamp(const filament::Stream *stream) {
+        return stream->getTimestamp();
+    }
+}
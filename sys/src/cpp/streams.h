@@ -0,0 +1,24 @@
+#ifndef FILAMENT_RS_STREAMS_H
+#define FILAMENT_RS_STREAMS_H
+
+#include <filament/Engine.h>
+#include <backend/DriverEnums.h>
+
+namespace filament {
+    class Stream;
+}
+
+namespace helpers {
+    filament::Stream *stream_build_native(filament::Engine &engine, void *stream, uint32_t width, uint32_t height);
+    filament::Stream *stream_build_texture_id(filament::Engine &engine, intptr_t externalTextureId,
+                                              uint32_t width, uint32_t height);
+    filament::Stream *stream_build_acquired(filament::Engine &engine, uint32_t width, uint32_t height);
+
+    filament::backend::StreamType stream_getStreamType(const filament::Stream *stream);
+    void stream_setDimensions(filament::Stream *stream, uint32_t width, uint32_t height);
+    void stream_setAcquiredImage(filament::Stream *stream, void *image,
+                                 filament::backend::StreamCallback callback, void *user);
+    int64_t stream_getTimestamp(const filament::Stream *stream);
+}
+
+#endif //FILAMENT_RS_STREAMS_H
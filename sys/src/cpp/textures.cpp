@@ -0,0 +1,29 @@
+#include "textures.h"
+#include <filament/Texture.h>
+#include <filament/RenderTarget.h>
+
+namespace helpers {
+    void texture_setImage3D(filament::Texture *texture, filament::Engine &engine, size_t level,
+                            uint32_t xoffset, uint32_t yoffset, uint32_t zoffset,
+                            uint32_t width, uint32_t height, uint32_t depth,
+                            filament::backend::PixelBufferDescriptor *buffer) {
+        texture->setImage(engine, level, xoffset, yoffset, zoffset, width, height, depth, std::move(*buffer));
+    }
+
+    filament::RenderTarget *render_target_build(filament::Engine &engine, filament::Texture *color,
+                                                filament::Texture *depth, uint8_t level,
+                                                filament::backend::TextureCubemapFace face, uint32_t layer) {
+        filament::RenderTarget::Builder builder;
+        builder.texture(filament::RenderTarget::COLOR, color)
+                .mipLevel(filament::RenderTarget::COLOR, level)
+                .face(filament::RenderTarget::COLOR, face)
+                .layer(filament::RenderTarget::COLOR, layer);
+        if (depth) {
+            builder.texture(filament::RenderTarget::DEPTH, depth)
+                    .mipLevel(filament::RenderTarget::DEPTH, level)
+                    .face(filament::RenderTarget::DEPTH, face)
+                    .layer(filament::RenderTarget::DEPTH, layer);
+        }
+        return builder.build(engine);
+    }
+}
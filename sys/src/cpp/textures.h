@@ -0,0 +1,23 @@
+#ifndef FILAMENT_RS_TEXTURES_H
+#define FILAMENT_RS_TEXTURES_H
+
+#include <filament/Engine.h>
+#include <backend/PixelBufferDescriptor.h>
+
+namespace filament {
+    class Texture;
+    class RenderTarget;
+}
+
+namespace helpers {
+    void texture_setImage3D(filament::Texture *texture, filament::Engine &engine, size_t level,
+                            uint32_t xoffset, uint32_t yoffset, uint32_t zoffset,
+                            uint32_t width, uint32_t height, uint32_t depth,
+                            filament::backend::PixelBufferDescriptor *buffer);
+
+    filament::RenderTarget *render_target_build(filament::Engine &engine, filament::Texture *color,
+                                                filament::Texture *depth, uint8_t level,
+                                                filament::backend::TextureCubemapFace face, uint32_t layer);
+}
+
+#endif //FILAMENT_RS_TEXTURES_H
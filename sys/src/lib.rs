@@ -2,6 +2,9 @@
 
 use std::convert::TryInto;
 
+pub mod math;
+pub use math::{Double3, Double4, Float2, Float3, Float4, Mat3f, Mat4, Mat4f, Quatf};
+
 pub mod ffi {
     #![allow(
         non_upper_case_globals,
@@ -10,21 +13,28 @@ pub mod ffi {
         dead_code
     )]
     include!("bindings.rs");
-
-    // TODO: Not sure why bindgen gets this wrong
-    pub type filament_math_mat4f = [u32; 16];
 }
 
 impl Default for ffi::filament_Renderer_ClearOptions {
     fn default() -> Self {
         Self {
-            clearColor: [0.0, 0.0, 1.0, 1.0].into_filament(),
+            clearColor: Float4::new(0.0, 0.0, 1.0, 1.0),
             clear: true,
             discard: true,
         }
     }
 }
 
+impl Default for ffi::filament_Texture_PrefilterOptions {
+    fn default() -> Self {
+        Self {
+            sampleCount: 8,
+            mirror: true,
+            reserved: [0; 3],
+        }
+    }
+}
+
 pub use ffi::filament_backend_Viewport as Viewport;
 
 impl Viewport {
@@ -51,6 +61,8 @@ impl Viewport {
     }
 }
 
+/// Converts plain arrays into Filament's math types. The math types also implement `From` and
+/// `Into` for the same arrays.
 pub trait IntoFilament<D> {
     fn into_filament(self) -> D;
 }
@@ -59,60 +71,35 @@ pub trait FromFilament<D> {
     fn into_array(self) -> D;
 }
 
-impl IntoFilament<ffi::filament_math_float3> for [f32; 3] {
-    #[inline(always)]
-    fn into_filament(self) -> ffi::filament_math_float3 {
-        let mut r: [u32; 3] = [0; 3];
-
-        unsafe { std::ptr::copy_nonoverlapping(self.as_ptr(), r.as_mut_ptr() as *mut _, 3) }
-
-        r
-    }
-}
-
-impl FromFilament<[f32; 3]> for ffi::filament_math_float3 {
-    fn into_array(self) -> [f32; 3] {
-        unsafe { std::slice::from_raw_parts(self.as_ptr() as *const _, 3) }
-            .try_into()
-            .unwrap()
-    }
+macro_rules! impl_filament_conversions {
+    ($($array:ty => $math:ty),+) => {
+        $(
+            impl IntoFilament<$math> for $array {
+                #[inline(always)]
+                fn into_filament(self) -> $math {
+                    self.into()
+                }
+            }
+            impl FromFilament<$array> for $math {
+                #[inline(always)]
+                fn into_array(self) -> $array {
+                    self.into()
+                }
+            }
+        )+
+    };
 }
 
-impl IntoFilament<ffi::filament_math_float4> for [f32; 4] {
-    #[inline(always)]
-    fn into_filament(self) -> ffi::filament_math_float4 {
-        let mut r: [u32; 4] = [0; 4];
-
-        unsafe { std::ptr::copy_nonoverlapping(self.as_ptr(), r.as_mut_ptr() as *mut _, 4) }
-
-        r
-    }
-}
-impl FromFilament<[f32; 4]> for ffi::filament_math_float4 {
-    fn into_array(self) -> [f32; 4] {
-        unsafe { std::slice::from_raw_parts(self.as_ptr() as *const _, 4) }
-            .try_into()
-            .unwrap()
-    }
-}
-
-impl IntoFilament<ffi::filament_math_mat4f> for [f32; 16] {
-    #[inline(always)]
-    fn into_filament(self) -> ffi::filament_math_mat4f {
-        let mut r: [u32; 16] = [0; 16];
-
-        unsafe { std::ptr::copy_nonoverlapping(self.as_ptr(), r.as_mut_ptr() as *mut _, 16) }
-
-        r
-    }
-}
-impl FromFilament<[f32; 16]> for ffi::filament_math_mat4f {
-    fn into_array(self) -> [f32; 16] {
-        unsafe { std::slice::from_raw_parts(self.as_ptr() as *const _, 16) }
-            .try_into()
-            .unwrap()
-    }
-}
+impl_filament_conversions!(
+    [f32; 2] => Float2,
+    [f32; 3] => Float3,
+    [f32; 4] => Float4,
+    [f64; 3] => Double3,
+    [f64; 4] => Double4,
+    [f32; 9] => Mat3f,
+    [f32; 16] => Mat4f,
+    [f64; 16] => Mat4
+);
 
 #[no_mangle]
 /// A callback from Filament to de-allocate a buffer (after it has been copied to the GPU).
@@ -149,63 +136,187 @@ impl Default for ffi::utils_Entity {
         Self { mIdentity: 0 }
     }
 }
+impl ffi::utils_Entity {
+    /// Whether this is the null entity, which is never alive.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.mIdentity == 0
+    }
+}
+impl PartialEq for ffi::utils_Entity {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.mIdentity == other.mIdentity
+    }
+}
+impl Eq for ffi::utils_Entity {}
+impl std::hash::Hash for ffi::utils_Entity {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.mIdentity.hash(state)
+    }
+}
+
+/// How a sampler magnifies a texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SamplerMagFilter {
+    /// No filtering, the nearest texel is used
+    Nearest,
+    /// Weighted average of the 4 nearest texels
+    Linear,
+}
+impl From<SamplerMagFilter> for ffi::filament_backend_SamplerMagFilter {
+    fn from(filter: SamplerMagFilter) -> Self {
+        match filter {
+            SamplerMagFilter::Nearest => Self::NEAREST,
+            SamplerMagFilter::Linear => Self::LINEAR,
+        }
+    }
+}
+
+/// How a sampler minifies a texture, and whether it reads from its mip levels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SamplerMinFilter {
+    Nearest,
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear,
+}
+impl From<SamplerMinFilter> for ffi::filament_backend_SamplerMinFilter {
+    fn from(filter: SamplerMinFilter) -> Self {
+        match filter {
+            SamplerMinFilter::Nearest => Self::NEAREST,
+            SamplerMinFilter::Linear => Self::LINEAR,
+            SamplerMinFilter::NearestMipmapNearest => Self::NEAREST_MIPMAP_NEAREST,
+            SamplerMinFilter::LinearMipmapNearest => Self::LINEAR_MIPMAP_NEAREST,
+            SamplerMinFilter::NearestMipmapLinear => Self::NEAREST_MIPMAP_LINEAR,
+            SamplerMinFilter::LinearMipmapLinear => Self::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+}
 
+/// What a sampler reads outside of the [0, 1] texture coordinate range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SamplerWrapMode {
+    ClampToEdge,
+    Repeat,
+    MirroredRepeat,
+}
+impl From<SamplerWrapMode> for ffi::filament_backend_SamplerWrapMode {
+    fn from(mode: SamplerWrapMode) -> Self {
+        match mode {
+            SamplerWrapMode::ClampToEdge => Self::CLAMP_TO_EDGE,
+            SamplerWrapMode::Repeat => Self::REPEAT,
+            SamplerWrapMode::MirroredRepeat => Self::MIRRORED_REPEAT,
+        }
+    }
+}
+
+/// Whether a sampler compares depth textures against a reference value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SamplerCompareMode {
+    None,
+    CompareToTexture,
+}
+impl From<SamplerCompareMode> for ffi::filament_backend_SamplerCompareMode {
+    fn from(mode: SamplerCompareMode) -> Self {
+        match mode {
+            SamplerCompareMode::None => Self::NONE,
+            SamplerCompareMode::CompareToTexture => Self::COMPARE_TO_TEXTURE,
+        }
+    }
+}
+
+/// The comparison used by depth samplers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SamplerCompareFunc {
+    LessEqual,
+    GreaterEqual,
+    Less,
+    Greater,
+    Equal,
+    NotEqual,
+    Always,
+    Never,
+}
+impl From<SamplerCompareFunc> for ffi::filament_backend_SamplerCompareFunc {
+    fn from(func: SamplerCompareFunc) -> Self {
+        match func {
+            SamplerCompareFunc::LessEqual => Self::LE,
+            SamplerCompareFunc::GreaterEqual => Self::GE,
+            SamplerCompareFunc::Less => Self::L,
+            SamplerCompareFunc::Greater => Self::G,
+            SamplerCompareFunc::Equal => Self::E,
+            SamplerCompareFunc::NotEqual => Self::NE,
+            SamplerCompareFunc::Always => Self::A,
+            SamplerCompareFunc::Never => Self::N,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct TextureSamplerBuilder {
-    pub filter_mag: ffi::filament_backend_SamplerMagFilter,
-    pub filter_min: ffi::filament_backend_SamplerMinFilter,
-    pub wrap_s: ffi::filament_backend_SamplerWrapMode,
-    pub wrap_t: ffi::filament_backend_SamplerWrapMode,
-    pub wrap_r: ffi::filament_backend_SamplerWrapMode,
+    pub filter_mag: SamplerMagFilter,
+    pub filter_min: SamplerMinFilter,
+    pub wrap_s: SamplerWrapMode,
+    pub wrap_t: SamplerWrapMode,
+    pub wrap_r: SamplerWrapMode,
     pub anisotropy_log2: u8,
-    pub compare_mode: ffi::filament_backend_SamplerCompareMode,
-    pub compare_func: ffi::filament_backend_SamplerCompareFunc,
+    pub compare_mode: SamplerCompareMode,
+    pub compare_func: SamplerCompareFunc,
 }
 impl TextureSamplerBuilder {
-    pub fn filter_mag(mut self, filter: ffi::filament_backend_SamplerMagFilter) -> Self {
+    pub fn filter_mag(mut self, filter: SamplerMagFilter) -> Self {
         self.filter_mag = filter;
         self
     }
-    pub fn filter_min(mut self, filter: ffi::filament_backend_SamplerMinFilter) -> Self {
+    pub fn filter_min(mut self, filter: SamplerMinFilter) -> Self {
         self.filter_min = filter;
         self
     }
-    pub fn wrap_s(mut self, mode: ffi::filament_backend_SamplerWrapMode) -> Self {
+    pub fn wrap_s(mut self, mode: SamplerWrapMode) -> Self {
         self.wrap_s = mode;
         self
     }
-    pub fn wrap_t(mut self, mode: ffi::filament_backend_SamplerWrapMode) -> Self {
+    pub fn wrap_t(mut self, mode: SamplerWrapMode) -> Self {
         self.wrap_t = mode;
         self
     }
-    pub fn wrap_r(mut self, mode: ffi::filament_backend_SamplerWrapMode) -> Self {
+    pub fn wrap_r(mut self, mode: SamplerWrapMode) -> Self {
         self.wrap_r = mode;
         self
     }
+    /// Sets the wrap mode of all three texture coordinates.
+    pub fn wrap(self, mode: SamplerWrapMode) -> Self {
+        self.wrap_s(mode).wrap_t(mode).wrap_r(mode)
+    }
     pub fn anisotropy_log2(mut self, anisotropy_log2: u8) -> Self {
         self.anisotropy_log2 = anisotropy_log2;
         self
     }
-    pub fn compare_mode(mut self, mode: ffi::filament_backend_SamplerCompareMode) -> Self {
+    pub fn compare_mode(mut self, mode: SamplerCompareMode) -> Self {
         self.compare_mode = mode;
         self
     }
-    pub fn compare_func(mut self, mode: ffi::filament_backend_SamplerCompareFunc) -> Self {
-        self.compare_func = mode;
+    pub fn compare_func(mut self, func: SamplerCompareFunc) -> Self {
+        self.compare_func = func;
         self
     }
 
-    fn build(self) -> ffi::filament_TextureSampler {
+    pub fn build(self) -> ffi::filament_TextureSampler {
         ffi::filament_TextureSampler {
             mSamplerParams: unsafe {
                 ffi::helpers_make_SamplerParams(
-                    self.filter_mag,
-                    self.filter_min,
-                    self.wrap_s,
-                    self.wrap_t,
-                    self.wrap_r,
+                    self.filter_mag.into(),
+                    self.filter_min.into(),
+                    self.wrap_s.into(),
+                    self.wrap_t.into(),
+                    self.wrap_r.into(),
                     self.anisotropy_log2,
-                    self.compare_mode,
-                    self.compare_func,
+                    self.compare_mode.into(),
+                    self.compare_func.into(),
                 )
             },
         }
@@ -214,14 +325,14 @@ impl TextureSamplerBuilder {
 impl Default for TextureSamplerBuilder {
     fn default() -> Self {
         Self {
-            filter_mag: ffi::filament_backend_SamplerMagFilter::NEAREST,
-            filter_min: ffi::filament_backend_SamplerMinFilter::NEAREST,
-            wrap_s: ffi::filament_backend_SamplerWrapMode::CLAMP_TO_EDGE,
-            wrap_t: ffi::filament_backend_SamplerWrapMode::CLAMP_TO_EDGE,
-            wrap_r: ffi::filament_backend_SamplerWrapMode::CLAMP_TO_EDGE,
+            filter_mag: SamplerMagFilter::Nearest,
+            filter_min: SamplerMinFilter::Nearest,
+            wrap_s: SamplerWrapMode::ClampToEdge,
+            wrap_t: SamplerWrapMode::ClampToEdge,
+            wrap_r: SamplerWrapMode::ClampToEdge,
             anisotropy_log2: 0,
-            compare_mode: ffi::filament_backend_SamplerCompareMode::NONE,
-            compare_func: ffi::filament_backend_SamplerCompareFunc::LE,
+            compare_mode: SamplerCompareMode::None,
+            compare_func: SamplerCompareFunc::LessEqual,
         }
     }
 }
@@ -0,0 +1,407 @@
+//! `#[repr(C)]` equivalents of the vector, matrix and quaternion types of `filament::math`, laid
+//! out exactly as in C++ so that they can be passed to and returned from Filament by value.
+//!
+//! Matrices are stored column-major, as in Filament.
+//!
+//! The `mint`, `glam` and `nalgebra` features add conversions from and to the equivalent types of
+//! those crates.
+use std::ops::{Index, IndexMut, Mul};
+
+#[cfg(feature = "glam")]
+mod glam;
+#[cfg(feature = "mint")]
+mod mint;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+
+macro_rules! impl_vector {
+    ($name:ident, $ty:ty, $n:expr, $($field:ident),+) => {
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone, Default, PartialEq)]
+        pub struct $name {
+            $(pub $field: $ty,)+
+        }
+        impl $name {
+            #[inline]
+            pub const fn new($($field: $ty),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            #[inline]
+            pub fn to_array(self) -> [$ty; $n] {
+                self.into()
+            }
+        }
+        impl From<[$ty; $n]> for $name {
+            #[inline]
+            fn from(v: [$ty; $n]) -> Self {
+                let [$($field),+] = v;
+                Self { $($field),+ }
+            }
+        }
+        impl From<$name> for [$ty; $n] {
+            #[inline]
+            fn from(v: $name) -> Self {
+                [$(v.$field),+]
+            }
+        }
+        impl Index<usize> for $name {
+            type Output = $ty;
+
+            #[inline]
+            fn index(&self, index: usize) -> &$ty {
+                // The fields of a `repr(C)` struct of one type are laid out like an array
+                unsafe { &(*(self as *const Self as *const [$ty; $n]))[index] }
+            }
+        }
+        impl IndexMut<usize> for $name {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut $ty {
+                unsafe { &mut (*(self as *mut Self as *mut [$ty; $n]))[index] }
+            }
+        }
+    };
+}
+
+impl_vector!(Float2, f32, 2, x, y);
+impl_vector!(Float3, f32, 3, x, y, z);
+impl_vector!(Float4, f32, 4, x, y, z, w);
+impl_vector!(Double3, f64, 3, x, y, z);
+impl_vector!(Double4, f64, 4, x, y, z, w);
+
+macro_rules! impl_matrix {
+    ($name:ident, $column:ident, $ty:ty, $n:expr) => {
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        pub struct $name {
+            pub columns: [$column; $n],
+        }
+        impl $name {
+            pub const IDENTITY: Self = {
+                let mut columns = [[0.0; $n]; $n];
+                let mut i = 0;
+                while i < $n {
+                    columns[i][i] = 1.0;
+                    i += 1;
+                }
+                Self::from_cols(columns)
+            };
+
+            #[inline]
+            pub const fn from_cols(columns: [[$ty; $n]; $n]) -> Self {
+                let mut result = [$column::new_splat(0.0); $n];
+                let mut i = 0;
+                while i < $n {
+                    result[i] = $column::from_slice(&columns[i]);
+                    i += 1;
+                }
+                Self { columns: result }
+            }
+
+            /// Builds a matrix from its elements in column-major order.
+            pub fn from_cols_array(elements: &[$ty; $n * $n]) -> Self {
+                let mut columns = [[0.0; $n]; $n];
+                for (i, column) in columns.iter_mut().enumerate() {
+                    column.copy_from_slice(&elements[i * $n..(i + 1) * $n]);
+                }
+                Self::from_cols(columns)
+            }
+
+            /// The elements of the matrix in column-major order.
+            pub fn to_cols_array(&self) -> [$ty; $n * $n] {
+                let mut elements = [0.0; $n * $n];
+                for (i, column) in self.columns.iter().enumerate() {
+                    elements[i * $n..(i + 1) * $n].copy_from_slice(&column.to_array());
+                }
+                elements
+            }
+        }
+        impl Default for $name {
+            #[inline]
+            fn default() -> Self {
+                Self::IDENTITY
+            }
+        }
+        impl From<[[$ty; $n]; $n]> for $name {
+            #[inline]
+            fn from(columns: [[$ty; $n]; $n]) -> Self {
+                Self::from_cols(columns)
+            }
+        }
+        impl From<$name> for [[$ty; $n]; $n] {
+            #[inline]
+            fn from(m: $name) -> Self {
+                let mut columns = [[0.0; $n]; $n];
+                for (column, source) in columns.iter_mut().zip(m.columns.iter()) {
+                    *column = source.to_array();
+                }
+                columns
+            }
+        }
+        impl From<[$ty; $n * $n]> for $name {
+            #[inline]
+            fn from(elements: [$ty; $n * $n]) -> Self {
+                Self::from_cols_array(&elements)
+            }
+        }
+        impl From<$name> for [$ty; $n * $n] {
+            #[inline]
+            fn from(m: $name) -> Self {
+                m.to_cols_array()
+            }
+        }
+        impl Index<usize> for $name {
+            type Output = $column;
+
+            #[inline]
+            fn index(&self, column: usize) -> &$column {
+                &self.columns[column]
+            }
+        }
+        impl IndexMut<usize> for $name {
+            #[inline]
+            fn index_mut(&mut self, column: usize) -> &mut $column {
+                &mut self.columns[column]
+            }
+        }
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                let mut columns = [[0.0; $n]; $n];
+                for (c, column) in columns.iter_mut().enumerate() {
+                    for (r, element) in column.iter_mut().enumerate() {
+                        for k in 0..$n {
+                            *element += self[k][r] * rhs[c][k];
+                        }
+                    }
+                }
+                Self::from_cols(columns)
+            }
+        }
+    };
+}
+
+// The const constructors used by `IDENTITY`, which `From` cannot provide
+macro_rules! impl_const_column {
+    ($name:ident, $ty:ty, $n:expr, $($field:ident = $i:expr),+) => {
+        impl $name {
+            #[inline]
+            const fn new_splat(v: $ty) -> Self {
+                Self { $($field: v),+ }
+            }
+
+            #[inline]
+            const fn from_slice(v: &[$ty; $n]) -> Self {
+                Self { $($field: v[$i]),+ }
+            }
+        }
+    };
+}
+
+impl_const_column!(Float3, f32, 3, x = 0, y = 1, z = 2);
+impl_const_column!(Float4, f32, 4, x = 0, y = 1, z = 2, w = 3);
+impl_const_column!(Double4, f64, 4, x = 0, y = 1, z = 2, w = 3);
+
+impl_matrix!(Mat3f, Float3, f32, 3);
+impl_matrix!(Mat4f, Float4, f32, 4);
+impl_matrix!(Mat4, Double4, f64, 4);
+
+impl Mat4f {
+    /// The inverse of the matrix, or `None` when it is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = self.to_cols_array();
+        let mut inv = [0.0f32; 16];
+
+        inv[0] = m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15]
+            + m[9] * m[7] * m[14]
+            + m[13] * m[6] * m[11]
+            - m[13] * m[7] * m[10];
+        inv[4] = -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15]
+            - m[8] * m[7] * m[14]
+            - m[12] * m[6] * m[11]
+            + m[12] * m[7] * m[10];
+        inv[8] = m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15]
+            + m[8] * m[7] * m[13]
+            + m[12] * m[5] * m[11]
+            - m[12] * m[7] * m[9];
+        inv[12] = -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14]
+            - m[8] * m[6] * m[13]
+            - m[12] * m[5] * m[10]
+            + m[12] * m[6] * m[9];
+        inv[1] = -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15]
+            - m[9] * m[3] * m[14]
+            - m[13] * m[2] * m[11]
+            + m[13] * m[3] * m[10];
+        inv[5] = m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15]
+            + m[8] * m[3] * m[14]
+            + m[12] * m[2] * m[11]
+            - m[12] * m[3] * m[10];
+        inv[9] = -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15]
+            - m[8] * m[3] * m[13]
+            - m[12] * m[1] * m[11]
+            + m[12] * m[3] * m[9];
+        inv[13] = m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14]
+            + m[8] * m[2] * m[13]
+            + m[12] * m[1] * m[10]
+            - m[12] * m[2] * m[9];
+        inv[2] = m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15]
+            + m[5] * m[3] * m[14]
+            + m[13] * m[2] * m[7]
+            - m[13] * m[3] * m[6];
+        inv[6] = -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15]
+            - m[4] * m[3] * m[14]
+            - m[12] * m[2] * m[7]
+            + m[12] * m[3] * m[6];
+        inv[10] = m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15]
+            + m[4] * m[3] * m[13]
+            + m[12] * m[1] * m[7]
+            - m[12] * m[3] * m[5];
+        inv[14] = -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14]
+            - m[4] * m[2] * m[13]
+            - m[12] * m[1] * m[6]
+            + m[12] * m[2] * m[5];
+        inv[3] = -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11]
+            - m[5] * m[3] * m[10]
+            - m[9] * m[2] * m[7]
+            + m[9] * m[3] * m[6];
+        inv[7] = m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11]
+            + m[4] * m[3] * m[10]
+            + m[8] * m[2] * m[7]
+            - m[8] * m[3] * m[6];
+        inv[11] = -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11]
+            - m[4] * m[3] * m[9]
+            - m[8] * m[1] * m[7]
+            + m[8] * m[3] * m[5];
+        inv[15] = m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10]
+            + m[4] * m[2] * m[9]
+            + m[8] * m[1] * m[6]
+            - m[8] * m[2] * m[5];
+
+        let det = m[0] * inv[0] + m[1] * inv[4] + m[2] * inv[8] + m[3] * inv[12];
+        if det == 0.0 {
+            return None;
+        }
+        for element in inv.iter_mut() {
+            *element /= det;
+        }
+        Some(Self::from_cols_array(&inv))
+    }
+}
+
+/// A quaternion stored as `x, y, z, w`, with `w` the real part.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quatf {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+impl Quatf {
+    pub const IDENTITY: Self = Self::new(0.0, 0.0, 0.0, 1.0);
+
+    #[inline]
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    #[inline]
+    pub fn to_array(self) -> [f32; 4] {
+        self.into()
+    }
+}
+impl Default for Quatf {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+impl From<[f32; 4]> for Quatf {
+    #[inline]
+    fn from([x, y, z, w]: [f32; 4]) -> Self {
+        Self { x, y, z, w }
+    }
+}
+impl From<Quatf> for [f32; 4] {
+    #[inline]
+    fn from(q: Quatf) -> Self {
+        [q.x, q.y, q.z, q.w]
+    }
+}
+
+impl From<Mat4f> for Mat4 {
+    fn from(m: Mat4f) -> Self {
+        let mut elements = [0.0; 16];
+        for (element, source) in elements.iter_mut().zip(m.to_cols_array().iter()) {
+            *element = f64::from(*source);
+        }
+        Self::from_cols_array(&elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        use std::mem::{align_of, size_of};
+
+        assert_eq!((size_of::<Float2>(), align_of::<Float2>()), (8, 4));
+        assert_eq!((size_of::<Float3>(), align_of::<Float3>()), (12, 4));
+        assert_eq!((size_of::<Float4>(), align_of::<Float4>()), (16, 4));
+        assert_eq!((size_of::<Double3>(), align_of::<Double3>()), (24, 8));
+        assert_eq!((size_of::<Double4>(), align_of::<Double4>()), (32, 8));
+        assert_eq!((size_of::<Mat3f>(), align_of::<Mat3f>()), (36, 4));
+        assert_eq!((size_of::<Mat4f>(), align_of::<Mat4f>()), (64, 4));
+        assert_eq!((size_of::<Mat4>(), align_of::<Mat4>()), (128, 8));
+        assert_eq!((size_of::<Quatf>(), align_of::<Quatf>()), (16, 4));
+    }
+
+    #[test]
+    fn conversions() {
+        let mut v = Float3::from([1.0, 2.0, 3.0]);
+        assert_eq!(v, Float3::new(1.0, 2.0, 3.0));
+        v[2] = 4.0;
+        assert_eq!((v[0], v.z), (1.0, 4.0));
+        assert_eq!(<[f32; 3]>::from(v), [1.0, 2.0, 4.0]);
+
+        let elements = [
+            1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
+        ];
+        let m = Mat4f::from(elements);
+        assert_eq!(m[3], Float4::new(13.0, 14.0, 15.0, 16.0));
+        assert_eq!(m.to_cols_array(), elements);
+        assert_eq!(Mat4::from(m).columns[1].y, 6.0);
+
+        assert_eq!(Mat3f::default()[1], Float3::new(0.0, 1.0, 0.0));
+        assert_eq!(Quatf::default().to_array(), [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn multiply_and_invert() {
+        #[rustfmt::skip]
+        let translate = Mat4f::from([
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            1.0, 2.0, 3.0, 1.0,
+        ]);
+        #[rustfmt::skip]
+        let scale = Mat4f::from([
+            2.0, 0.0, 0.0, 0.0,
+            0.0, 4.0, 0.0, 0.0,
+            0.0, 0.0, 8.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ]);
+
+        let m = translate * scale;
+        assert_eq!(m[0].x, 2.0);
+        assert_eq!(m[3], Float4::new(1.0, 2.0, 3.0, 1.0));
+        assert_eq!((scale * translate)[3], Float4::new(2.0, 8.0, 24.0, 1.0));
+
+        assert_eq!(m * m.inverse().unwrap(), Mat4f::IDENTITY);
+        assert_eq!(Mat4f::from([0.0; 16]).inverse(), None);
+    }
+}
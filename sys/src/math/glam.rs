@@ -0,0 +1,90 @@
+//! Conversions between the math types and `glam`'s, enabled by the `glam` feature.
+use super::{Double3, Double4, Float2, Float3, Float4, Mat3f, Mat4, Mat4f, Quatf};
+
+macro_rules! impl_vector {
+    ($($math:ty, $glam:ty;)+) => {
+        $(
+            impl From<$glam> for $math {
+                #[inline]
+                fn from(v: $glam) -> Self {
+                    v.to_array().into()
+                }
+            }
+            impl From<$math> for $glam {
+                #[inline]
+                fn from(v: $math) -> Self {
+                    <$glam>::from_array(v.into())
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_matrix {
+    ($($math:ty, $glam:ty;)+) => {
+        $(
+            impl From<$glam> for $math {
+                #[inline]
+                fn from(m: $glam) -> Self {
+                    Self::from_cols_array(&m.to_cols_array())
+                }
+            }
+            impl From<$math> for $glam {
+                #[inline]
+                fn from(m: $math) -> Self {
+                    <$glam>::from_cols_array(&m.to_cols_array())
+                }
+            }
+        )+
+    };
+}
+
+impl_vector!(
+    Float2, ::glam::Vec2;
+    Float3, ::glam::Vec3;
+    Float3, ::glam::Vec3A;
+    Float4, ::glam::Vec4;
+    Double3, ::glam::DVec3;
+    Double4, ::glam::DVec4;
+);
+
+impl_matrix!(
+    Mat3f, ::glam::Mat3;
+    Mat4f, ::glam::Mat4;
+    Mat4, ::glam::DMat4;
+);
+
+impl From<::glam::Quat> for Quatf {
+    #[inline]
+    fn from(q: ::glam::Quat) -> Self {
+        q.to_array().into()
+    }
+}
+impl From<Quatf> for ::glam::Quat {
+    #[inline]
+    fn from(q: Quatf) -> Self {
+        ::glam::Quat::from_xyzw(q.x, q.y, q.z, q.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Float3, Mat4f, Quatf};
+
+    #[test]
+    fn glam_conversions() {
+        assert_eq!(
+            Float3::from(::glam::Vec3::new(1.0, 2.0, 3.0)),
+            Float3::new(1.0, 2.0, 3.0)
+        );
+
+        let translation = ::glam::Mat4::from_translation(::glam::Vec3::new(1.0, 2.0, 3.0));
+        let m = Mat4f::from(translation);
+        assert_eq!(m[3].to_array(), [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(::glam::Mat4::from(m), translation);
+
+        let q = ::glam::Quat::from_rotation_y(1.0);
+        assert_eq!(::glam::Quat::from(Quatf::from(q)), q);
+        assert_eq!(Quatf::from(::glam::Quat::IDENTITY), Quatf::IDENTITY);
+    }
+}
@@ -0,0 +1,77 @@
+//! Conversions between the math types and `mint`'s, enabled by the `mint` feature.
+use super::{Double3, Double4, Float2, Float3, Float4, Mat3f, Mat4, Mat4f, Quatf};
+
+// Both sides convert to and from the same array, which is how the conversions go
+macro_rules! impl_via_array {
+    ($($math:ty, $mint:ty, $array:ty;)+) => {
+        $(
+            impl From<$mint> for $math {
+                #[inline]
+                fn from(v: $mint) -> Self {
+                    <$array>::from(v).into()
+                }
+            }
+            impl From<$math> for $mint {
+                #[inline]
+                fn from(v: $math) -> Self {
+                    <$array>::from(v).into()
+                }
+            }
+        )+
+    };
+}
+
+impl_via_array!(
+    Float2, ::mint::Vector2<f32>, [f32; 2];
+    Float3, ::mint::Vector3<f32>, [f32; 3];
+    Float3, ::mint::Point3<f32>, [f32; 3];
+    Float4, ::mint::Vector4<f32>, [f32; 4];
+    Double3, ::mint::Vector3<f64>, [f64; 3];
+    Double4, ::mint::Vector4<f64>, [f64; 4];
+    Mat3f, ::mint::ColumnMatrix3<f32>, [[f32; 3]; 3];
+    Mat4f, ::mint::ColumnMatrix4<f32>, [[f32; 4]; 4];
+    Mat4, ::mint::ColumnMatrix4<f64>, [[f64; 4]; 4];
+);
+
+impl From<::mint::Quaternion<f32>> for Quatf {
+    #[inline]
+    fn from(q: ::mint::Quaternion<f32>) -> Self {
+        Self::new(q.v.x, q.v.y, q.v.z, q.s)
+    }
+}
+impl From<Quatf> for ::mint::Quaternion<f32> {
+    #[inline]
+    fn from(q: Quatf) -> Self {
+        Self {
+            v: ::mint::Vector3 {
+                x: q.x,
+                y: q.y,
+                z: q.z,
+            },
+            s: q.w,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Float3, Mat4f, Quatf};
+
+    #[test]
+    fn mint_conversions() {
+        let v = ::mint::Vector3 {
+            x: 1.0f32,
+            y: 2.0,
+            z: 3.0,
+        };
+        assert_eq!(Float3::from(v), Float3::new(1.0, 2.0, 3.0));
+
+        let m = ::mint::ColumnMatrix4::from(Mat4f::IDENTITY);
+        assert_eq!(m.w.w, 1.0);
+        assert_eq!(Mat4f::from(m), Mat4f::IDENTITY);
+
+        let q = ::mint::Quaternion::from(Quatf::new(1.0, 2.0, 3.0, 4.0));
+        assert_eq!((q.v.x, q.s), (1.0, 4.0));
+        assert_eq!(Quatf::from(q), Quatf::new(1.0, 2.0, 3.0, 4.0));
+    }
+}
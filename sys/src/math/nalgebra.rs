@@ -0,0 +1,108 @@
+//! Conversions between the math types and `nalgebra`'s, enabled by the `nalgebra` feature.
+use super::{Double3, Double4, Float2, Float3, Float4, Mat3f, Mat4, Mat4f, Quatf};
+
+// nalgebra's vectors and matrices are column-major like Filament's, so both go through their
+// elements in column-major order
+macro_rules! impl_via_slice {
+    ($($math:ty, $nalgebra:ty, $array:ty;)+) => {
+        $(
+            impl From<$nalgebra> for $math {
+                #[inline]
+                fn from(m: $nalgebra) -> Self {
+                    let mut elements = <$array>::default();
+                    elements.copy_from_slice(m.as_slice());
+                    elements.into()
+                }
+            }
+            impl From<$math> for $nalgebra {
+                #[inline]
+                fn from(m: $math) -> Self {
+                    <$nalgebra>::from_column_slice(&<$array>::from(m))
+                }
+            }
+        )+
+    };
+}
+
+impl_via_slice!(
+    Float2, ::nalgebra::Vector2<f32>, [f32; 2];
+    Float3, ::nalgebra::Vector3<f32>, [f32; 3];
+    Float4, ::nalgebra::Vector4<f32>, [f32; 4];
+    Double3, ::nalgebra::Vector3<f64>, [f64; 3];
+    Double4, ::nalgebra::Vector4<f64>, [f64; 4];
+    Mat3f, ::nalgebra::Matrix3<f32>, [f32; 9];
+    Mat4f, ::nalgebra::Matrix4<f32>, [f32; 16];
+    Mat4, ::nalgebra::Matrix4<f64>, [f64; 16];
+);
+
+impl From<::nalgebra::Point3<f32>> for Float3 {
+    #[inline]
+    fn from(p: ::nalgebra::Point3<f32>) -> Self {
+        Self::new(p.x, p.y, p.z)
+    }
+}
+impl From<Float3> for ::nalgebra::Point3<f32> {
+    #[inline]
+    fn from(v: Float3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<::nalgebra::Isometry3<f32>> for Mat4f {
+    #[inline]
+    fn from(isometry: ::nalgebra::Isometry3<f32>) -> Self {
+        isometry.to_homogeneous().into()
+    }
+}
+
+impl From<::nalgebra::Quaternion<f32>> for Quatf {
+    #[inline]
+    fn from(q: ::nalgebra::Quaternion<f32>) -> Self {
+        Self::new(q.i, q.j, q.k, q.w)
+    }
+}
+impl From<::nalgebra::UnitQuaternion<f32>> for Quatf {
+    #[inline]
+    fn from(q: ::nalgebra::UnitQuaternion<f32>) -> Self {
+        q.into_inner().into()
+    }
+}
+impl From<Quatf> for ::nalgebra::Quaternion<f32> {
+    #[inline]
+    fn from(q: Quatf) -> Self {
+        Self::new(q.w, q.x, q.y, q.z)
+    }
+}
+impl From<Quatf> for ::nalgebra::UnitQuaternion<f32> {
+    /// Normalizes the quaternion, which Filament expects to be of unit length anyway.
+    #[inline]
+    fn from(q: Quatf) -> Self {
+        Self::from_quaternion(q.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Float3, Mat4f, Quatf};
+
+    #[test]
+    fn nalgebra_conversions() {
+        assert_eq!(
+            Float3::from(::nalgebra::Vector3::new(1.0, 2.0, 3.0)),
+            Float3::new(1.0, 2.0, 3.0)
+        );
+
+        let translation =
+            ::nalgebra::Matrix4::new_translation(&::nalgebra::Vector3::new(1.0f32, 2.0, 3.0));
+        let m = Mat4f::from(translation);
+        assert_eq!(m[3].to_array(), [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(::nalgebra::Matrix4::from(m), translation);
+
+        let q = ::nalgebra::UnitQuaternion::from_euler_angles(0.0f32, 1.0, 0.0);
+        assert_eq!(Quatf::from(q).y, q.j);
+        assert_eq!(
+            Quatf::from(::nalgebra::UnitQuaternion::identity()),
+            Quatf::IDENTITY
+        );
+    }
+}